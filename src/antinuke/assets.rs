@@ -1,16 +1,52 @@
 use crate::rest::RestClient;
 use crate::db::Database;
+use crate::models::audit_action;
+use crate::antinuke::Incident;
+use std::collections::HashMap;
 use std::sync::Arc;
-use serde_json::Value;
+use serde_json::{json, Value};
 use tracing::{warn, error};
 
+struct AssetKind {
+    kind: &'static str,
+    list_key: &'static str,
+    create: (&'static str, u8),
+    update: (&'static str, u8),
+    delete: (&'static str, u8),
+}
+
+const EMOJI: AssetKind = AssetKind {
+    kind: "emoji",
+    list_key: "emojis",
+    create: ("anti_emoji_create", audit_action::EMOJI_CREATE),
+    update: ("anti_emoji_update", audit_action::EMOJI_UPDATE),
+    delete: ("anti_emoji_delete", audit_action::EMOJI_DELETE),
+};
+
+const STICKER: AssetKind = AssetKind {
+    kind: "sticker",
+    list_key: "stickers",
+    create: ("anti_sticker_create", audit_action::STICKER_CREATE),
+    update: ("anti_sticker_update", audit_action::STICKER_UPDATE),
+    delete: ("anti_sticker_delete", audit_action::STICKER_DELETE),
+};
+
+enum AssetChange {
+    Created(Value),
+    Renamed(Value, Value),
+    Deleted(Value),
+}
+
 pub async fn handle_event(
     event_type: &str,
     data: Value,
-    _rest: Arc<RestClient>,
+    rest: Arc<RestClient>,
     db: Arc<Database>,
 ) {
-    let guild_id = data.get("guild_id").and_then(|v| v.as_str()).unwrap_or("");
+    let guild_id = match event_type {
+        "GUILD_CREATE" => data.get("id").and_then(|v| v.as_str()).unwrap_or(""),
+        _ => data.get("guild_id").and_then(|v| v.as_str()).unwrap_or(""),
+    };
     let settings = match db.get_antinuke_settings(guild_id).await {
         Ok(s) => s,
         Err(e) => {
@@ -19,25 +55,328 @@ pub async fn handle_event(
         }
     };
 
-    let executor_id = data.get("executor_id").and_then(|v| v.as_str()).unwrap_or("");
-
     match event_type {
-        "GUILD_EMOJIS_UPDATE" | "GUILD_STICKERS_UPDATE" => {
-            if *settings.get("anti_emoji_delete").unwrap_or(&false) {
-                if !executor_id.is_empty() && (db.is_whitelisted(executor_id).await.unwrap_or(false) || db.is_admin(executor_id).await.unwrap_or(false)) {
-                    return;
+        "GUILD_CREATE" => {
+            for asset in [&EMOJI, &STICKER] {
+                let items = asset_list(&data, asset);
+                if let Err(e) = db.replace_snapshots(guild_id, asset.kind, &items).await {
+                    error!("Failed to cache {}s for guild {}: {:?}", asset.kind, guild_id, e);
+                }
+            }
+            spawn_emoji_archive(guild_id, asset_list(&data, &EMOJI), &rest, &db);
+
+            match rest.get_guild_webhooks(guild_id).await {
+                Ok(hooks) => {
+                    let items: Vec<Value> = hooks.iter().map(webhook_snapshot).collect();
+                    if let Err(e) = db.replace_snapshots(guild_id, "webhook", &items).await {
+                        error!("Failed to cache webhooks for guild {}: {:?}", guild_id, e);
+                    }
                 }
-                warn!("ANTINUKE: Asset modification detected in server {}.", guild_id);
+                Err(e) => warn!("Failed to fetch webhooks for guild {}: {:?}", guild_id, e),
             }
         }
+        "GUILD_EMOJIS_UPDATE" => {
+            spawn_emoji_archive(guild_id, asset_list(&data, &EMOJI), &rest, &db);
+            handle_asset_update(guild_id, &data, &EMOJI, &settings, &rest, &db).await;
+        }
+        "GUILD_STICKERS_UPDATE" => {
+            handle_asset_update(guild_id, &data, &STICKER, &settings, &rest, &db).await;
+        }
         "WEBHOOKS_UPDATE" => {
-            if *settings.get("anti_webhook_create").unwrap_or(&false) {
-                if !executor_id.is_empty() && (db.is_whitelisted(executor_id).await.unwrap_or(false) || db.is_admin(executor_id).await.unwrap_or(false)) {
-                    return;
+            let channel_id = data.get("channel_id").and_then(|v| v.as_str()).unwrap_or("");
+            handle_webhooks_update(guild_id, channel_id, &settings, &rest, &db).await;
+        }
+        _ => {}
+    }
+}
+
+fn asset_list(data: &Value, asset: &AssetKind) -> Vec<Value> {
+    data.get(asset.list_key)
+        .and_then(|v| v.as_array())
+        .map(|arr| {
+            arr.iter()
+                .map(|a| json!({ "id": a["id"], "name": a["name"], "animated": a["animated"] }))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn diff_assets(previous: &[Value], current: &[Value]) -> Vec<AssetChange> {
+    let old: HashMap<&str, &Value> = previous.iter().filter_map(|a| a["id"].as_str().map(|id| (id, a))).collect();
+    let new: HashMap<&str, &Value> = current.iter().filter_map(|a| a["id"].as_str().map(|id| (id, a))).collect();
+
+    let mut changes = Vec::new();
+    for (id, asset) in &new {
+        match old.get(id) {
+            None => changes.push(AssetChange::Created((*asset).clone())),
+            Some(before) if before["name"] != asset["name"] => {
+                changes.push(AssetChange::Renamed((*before).clone(), (*asset).clone()));
+            }
+            _ => {}
+        }
+    }
+    for (id, asset) in &old {
+        if !new.contains_key(id) {
+            changes.push(AssetChange::Deleted((*asset).clone()));
+        }
+    }
+    changes
+}
+
+fn spawn_emoji_archive(guild_id: &str, emojis: Vec<Value>, rest: &Arc<RestClient>, db: &Arc<Database>) {
+    let (guild_id, rest, db) = (guild_id.to_string(), rest.clone(), db.clone());
+    tokio::spawn(async move {
+        archive_missing_emojis(&guild_id, &emojis, &rest, &db).await;
+    });
+}
+
+async fn archive_missing_emojis(guild_id: &str, emojis: &[Value], rest: &RestClient, db: &Database) {
+    let archived = db.archived_emoji_ids(guild_id).await.unwrap_or_default();
+
+    for emoji in emojis {
+        let id = emoji["id"].as_str().unwrap_or("");
+        if id.is_empty() || archived.contains(id) { continue; }
+
+        let ext = if emoji["animated"].as_bool().unwrap_or(false) { "gif" } else { "png" };
+        let url = format!("https://cdn.discordapp.com/emojis/{}.{}", id, ext);
+        match rest.download_image(&url).await {
+            Ok((content_type, bytes)) => {
+                let name = emoji["name"].as_str().unwrap_or("restored");
+                if let Err(e) = db.archive_emoji(guild_id, id, name, &content_type, &bytes).await {
+                    error!("Failed to archive emoji {} for guild {}: {:?}", id, guild_id, e);
                 }
-                warn!("ANTINUKE: Webhook creation/update detected in server {}.", guild_id);
             }
+            Err(e) => warn!("Failed to download emoji {} for archive: {:?}", id, e),
         }
-        _ => {}
+    }
+}
+
+async fn handle_asset_update(
+    guild_id: &str,
+    data: &Value,
+    asset: &AssetKind,
+    settings: &HashMap<String, bool>,
+    rest: &RestClient,
+    db: &Database,
+) {
+    let current = asset_list(data, asset);
+    let previous = db.list_snapshots(guild_id, asset.kind).await.unwrap_or_default();
+
+    if let Err(e) = db.replace_snapshots(guild_id, asset.kind, &current).await {
+        error!("Failed to cache {}s for guild {}: {:?}", asset.kind, guild_id, e);
+    }
+
+    let mut incidents: HashMap<String, Incident> = HashMap::new();
+
+    for change in diff_assets(&previous, &current) {
+        let (setting, action_type, target) = match &change {
+            AssetChange::Created(a) => (asset.create.0, asset.create.1, a),
+            AssetChange::Renamed(_, a) => (asset.update.0, asset.update.1, a),
+            AssetChange::Deleted(a) => (asset.delete.0, asset.delete.1, a),
+        };
+
+        if !*settings.get(setting).unwrap_or(&false) { continue; }
+
+        let target_id = target["id"].as_str().unwrap_or("");
+        let entry = match crate::antinuke::find_audit_entry(guild_id, action_type, Some(target_id), rest).await {
+            Some(e) => e,
+            None => continue,
+        };
+
+        let executor_id = entry.get("user_id").and_then(|v| v.as_str()).unwrap_or("");
+        if executor_id.is_empty() || crate::antinuke::is_trusted(guild_id, executor_id, setting, rest, db).await {
+            if let AssetChange::Deleted(_) = change {
+                let _ = db.delete_archived_emoji(guild_id, target_id).await;
+            }
+            continue;
+        }
+
+        warn!("ANTINUKE: Unauthorized {} change by {} in server {}.", asset.kind, executor_id, guild_id);
+
+        let monitor = db.is_monitor_mode(guild_id, setting).await;
+        let incident = incidents.entry(executor_id.to_string()).or_insert_with(|| {
+            let mut incident = Incident::new(guild_id, setting, executor_id, format!("Modified server {}s", asset.kind)).with_audit_entry(&entry);
+            incident.monitor = monitor;
+            incident
+        });
+        incident.monitor &= monitor;
+        incident.targets.push(format!("{}:{}", asset.kind, target_id));
+
+        if monitor {
+            incident.would(match &change {
+                AssetChange::Created(_) => format!("delete {} {}", asset.kind, target_id),
+                AssetChange::Renamed(before, _) => format!("rename {} {} back to {}", asset.kind, target_id, before["name"].as_str().unwrap_or("")),
+                AssetChange::Deleted(_) => format!("restore {} {} from archive", asset.kind, target_id),
+            });
+            continue;
+        }
+
+        let reason = "Rimuru Antinuke: Reverting Unauthorized Asset Change";
+        let recovery = revert_change(guild_id, asset, &change, reason, rest, db).await;
+        incident.recovery.push(recovery);
+    }
+
+    for (executor_id, mut incident) in incidents {
+        incident.punishment = crate::antinuke::punish(guild_id, &executor_id, "Rimuru Antinuke: Unauthorized Emoji/Sticker Change", incident.monitor, rest, db).await;
+        incident.report(rest, db).await;
+    }
+}
+
+async fn revert_change(guild_id: &str, asset: &AssetKind, change: &AssetChange, reason: &str, rest: &RestClient, db: &Database) -> String {
+    let is_emoji = asset.kind == "emoji";
+
+    match change {
+        AssetChange::Created(a) => {
+            let id = a["id"].as_str().unwrap_or("");
+            let result = if is_emoji {
+                rest.delete_guild_emoji(guild_id, id, reason).await
+            } else {
+                rest.delete_guild_sticker(guild_id, id, reason).await
+            };
+            match result {
+                Ok(_) => format!("Deleted {} {}", asset.kind, id),
+                Err(e) => format!("Failed to delete {} {}: {}", asset.kind, id, e),
+            }
+        }
+        AssetChange::Renamed(before, after) => {
+            let id = after["id"].as_str().unwrap_or("");
+            let name = before["name"].as_str().unwrap_or("");
+            let result = if is_emoji {
+                rest.modify_guild_emoji(guild_id, id, name, reason).await
+            } else {
+                rest.modify_guild_sticker(guild_id, id, name, reason).await
+            };
+            match result {
+                Ok(_) => format!("Renamed {} {} back to {}", asset.kind, id, name),
+                Err(e) => format!("Failed to rename {} {}: {}", asset.kind, id, e),
+            }
+        }
+        AssetChange::Deleted(a) => {
+            let id = a["id"].as_str().unwrap_or("");
+            if !is_emoji {
+                return format!("Sticker {} cannot be restored automatically", id);
+            }
+            match db.get_archived_emoji(guild_id, id).await {
+                Ok(Some((name, content_type, image))) => {
+                    let data_uri = crate::rest::image_data_uri(&content_type, &image);
+                    match rest.create_guild_emoji(guild_id, &name, &data_uri, reason).await {
+                        Ok(_) => {
+                            let _ = db.delete_archived_emoji(guild_id, id).await;
+                            format!("Restored emoji :{}: from archive", name)
+                        }
+                        Err(e) => format!("Failed to restore emoji {}: {}", id, e),
+                    }
+                }
+                _ => format!("No archived copy of emoji {}", id),
+            }
+        }
+    }
+}
+
+fn webhook_snapshot(hook: &Value) -> Value {
+    json!({
+        "id": hook["id"],
+        "name": hook["name"],
+        "channel_id": hook["channel_id"],
+        "creator_id": hook["user"]["id"]
+    })
+}
+
+async fn handle_webhooks_update(
+    guild_id: &str,
+    channel_id: &str,
+    settings: &HashMap<String, bool>,
+    rest: &RestClient,
+    db: &Database,
+) {
+    let current: Vec<Value> = match rest.get_channel_webhooks(channel_id).await {
+        Ok(hooks) => hooks.iter().map(webhook_snapshot).collect(),
+        Err(e) => {
+            error!("Failed to fetch webhooks for channel {}: {:?}", channel_id, e);
+            return;
+        }
+    };
+    let known = db.list_snapshots(guild_id, "webhook").await.unwrap_or_default();
+    let known_by_id: HashMap<&str, &Value> = known.iter().filter_map(|h| h["id"].as_str().map(|id| (id, h))).collect();
+
+    let mut changes: Vec<(&str, u8, &Value)> = Vec::new();
+    for hook in &current {
+        let id = hook["id"].as_str().unwrap_or("");
+        match known_by_id.get(id) {
+            None => changes.push(("anti_webhook_create", audit_action::WEBHOOK_CREATE, hook)),
+            Some(before) if before["name"] != hook["name"] || before["channel_id"] != hook["channel_id"] => {
+                changes.push(("anti_webhook_update", audit_action::WEBHOOK_UPDATE, hook));
+            }
+            _ => {}
+        }
+    }
+    let deleted: Vec<&Value> = known.iter()
+        .filter(|h| h["channel_id"].as_str() == Some(channel_id))
+        .filter(|h| !current.iter().any(|c| c["id"] == h["id"]))
+        .collect();
+    for hook in &deleted {
+        changes.push(("anti_webhook_delete", audit_action::WEBHOOK_DELETE, hook));
+    }
+
+    for hook in &current {
+        if let Err(e) = db.save_snapshot(guild_id, "webhook", hook["id"].as_str().unwrap_or(""), hook).await {
+            error!("Failed to cache webhook for guild {}: {:?}", guild_id, e);
+        }
+    }
+    for hook in &deleted {
+        let _ = db.delete_snapshot(guild_id, "webhook", hook["id"].as_str().unwrap_or("")).await;
+    }
+
+    let mut incidents: HashMap<String, Incident> = HashMap::new();
+
+    for (setting, action_type, hook) in changes {
+        if !*settings.get(setting).unwrap_or(&false) { continue; }
+
+        let hook_id = hook["id"].as_str().unwrap_or("");
+        let entry = crate::antinuke::find_audit_entry(guild_id, action_type, Some(hook_id), rest).await;
+        let executor_id = entry.as_ref()
+            .and_then(|e| e.get("user_id"))
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .to_string();
+
+        if executor_id.is_empty() || crate::antinuke::is_trusted(guild_id, &executor_id, setting, rest, db).await {
+            continue;
+        }
+
+        warn!("ANTINUKE: Unauthorized webhook change ({}) by {} in server {}.", setting, executor_id, guild_id);
+
+        let monitor = db.is_monitor_mode(guild_id, setting).await;
+        let incident = incidents.entry(executor_id.clone()).or_insert_with(|| {
+            let mut incident = Incident::new(guild_id, setting, &executor_id, format!("Modified webhooks in <#{}>", channel_id))
+                .with_audit_entry(entry.as_ref().unwrap_or(&Value::Null));
+            incident.monitor = monitor;
+            incident
+        });
+        incident.monitor &= monitor;
+        incident.targets.push(format!("webhook:{}", hook_id));
+
+        if setting == "anti_webhook_delete" {
+            incident.recovery.push(format!("Webhook {} was deleted and cannot be restored", hook_id));
+            continue;
+        }
+
+        if monitor {
+            incident.would(format!("delete webhook {}", hook_id));
+            continue;
+        }
+
+        match rest.delete_webhook(hook_id, "Rimuru Antinuke: Unauthorized Webhook").await {
+            Ok(_) => {
+                let _ = db.delete_snapshot(guild_id, "webhook", hook_id).await;
+                incident.recovery.push(format!("Deleted webhook {}", hook_id));
+            }
+            Err(e) => incident.recovery.push(format!("Failed to delete webhook {}: {}", hook_id, e)),
+        }
+    }
+
+    for (executor_id, mut incident) in incidents {
+        incident.punishment = crate::antinuke::punish(guild_id, &executor_id, "Rimuru Antinuke: Unauthorized Webhook Change", incident.monitor, rest, db).await;
+        incident.report(rest, db).await;
     }
 }
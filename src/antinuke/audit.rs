@@ -0,0 +1,225 @@
+use crate::rest::RestClient;
+use crate::db::{Database, WhitelistEntry};
+use crate::constants::permissions;
+use crate::models::app_flag;
+use super::{bot_top_position, role_permissions};
+use std::collections::HashMap;
+use serde_json::Value;
+use tracing::warn;
+
+const MAX_LISTED: usize = 10;
+
+const RISKY_EVERYONE: u64 = permissions::NIGHTMODE | permissions::MENTION_EVERYONE;
+
+pub struct Finding {
+    pub check: &'static str,
+    pub ok: bool,
+    pub details: Vec<String>,
+    pub fix: Option<String>,
+}
+
+impl Finding {
+    fn new(check: &'static str) -> Self {
+        Self { check, ok: true, details: Vec::new(), fix: None }
+    }
+
+    fn flag(&mut self, detail: String) {
+        self.ok = false;
+        self.details.push(detail);
+    }
+
+    fn flag_all(&mut self, mut items: Vec<String>, fix: &str) {
+        if items.is_empty() { return; }
+        let extra = items.len().saturating_sub(MAX_LISTED);
+        items.truncate(MAX_LISTED);
+        if extra > 0 {
+            items.push(format!("…and {} more", extra));
+        }
+        for item in items {
+            self.flag(item);
+        }
+        self.fix = Some(fix.to_string());
+    }
+}
+
+fn perm_list(perms: u64) -> String {
+    permissions::names(perms).join(", ")
+}
+
+fn member_permissions(member: &Value, everyone: u64, roles: &HashMap<&str, &Value>) -> u64 {
+    member["roles"].as_array().into_iter().flatten()
+        .filter_map(|id| id.as_str().and_then(|id| roles.get(id)))
+        .fold(everyone, |acc, role| acc | role_permissions(role))
+}
+
+fn is_public(channel: &Value, guild_id: &str, everyone: u64) -> bool {
+    let overwrite_deny = channel["permission_overwrites"].as_array().into_iter().flatten()
+        .find(|o| o["id"].as_str() == Some(guild_id))
+        .and_then(|o| o["deny"].as_str())
+        .and_then(|d| d.parse::<u64>().ok())
+        .unwrap_or(0);
+    everyone & (permissions::VIEW_CHANNEL | permissions::ADMINISTRATOR) != 0 && overwrite_deny & permissions::VIEW_CHANNEL == 0
+}
+
+pub async fn hierarchy(guild_id: &str, roles: &[Value], rest: &RestClient) -> Finding {
+    let mut finding = Finding::new("Role Hierarchy");
+    let (bot_id, bot_top) = match bot_top_position(guild_id, roles, rest).await {
+        Some(b) => b,
+        None => {
+            finding.flag("Could not resolve my roles in this server".to_string());
+            return finding;
+        }
+    };
+
+    let by_id: HashMap<&str, &Value> = roles.iter().filter_map(|r| r["id"].as_str().map(|id| (id, r))).collect();
+    let everyone = by_id.get(guild_id).map(|r| role_permissions(r)).unwrap_or(0);
+    let bot_perms = rest.get_guild_member(guild_id, &bot_id).await.ok()
+        .map(|m| member_permissions(&m, everyone, &by_id))
+        .unwrap_or(everyone);
+
+    let mut above: Vec<&Value> = roles.iter()
+        .filter(|r| r["id"].as_str() != Some(guild_id))
+        .filter(|r| r["tags"]["bot_id"].as_str() != Some(bot_id.as_str()))
+        .filter(|r| r["position"].as_u64().unwrap_or(0) >= bot_top)
+        .filter(|r| role_permissions(r) & permissions::DANGEROUS != 0)
+        .collect();
+    above.sort_by_key(|r| std::cmp::Reverse(r["position"].as_u64().unwrap_or(0)));
+
+    finding.flag_all(
+        above.iter()
+            .map(|r| format!("<@&{}> is at or above my highest role ({})", r["id"].as_str().unwrap_or(""), perm_list(role_permissions(r) & permissions::DANGEROUS)))
+            .collect(),
+        "Drag my role above these roles in **Server Settings → Roles**, otherwise I cannot punish members who hold them.",
+    );
+
+    let missing = permissions::ANTINUKE & !bot_perms;
+    if bot_perms & permissions::ADMINISTRATOR == 0 && missing != 0 {
+        finding.flag(format!("I am missing: {}", perm_list(missing)));
+        let grant = "Grant my role Administrator, or at least the permissions listed above.";
+        finding.fix = Some(match finding.fix.take() {
+            Some(fix) => format!("{} {}", fix, grant),
+            None => grant.to_string(),
+        });
+    }
+
+    finding
+}
+
+async fn is_whitelisted(guild_id: &str, member: &Value, whitelist: &[WhitelistEntry], db: &Database) -> bool {
+    let user_id = member["user"]["id"].as_str().unwrap_or("");
+    let roles: Vec<&str> = member["roles"].as_array()
+        .map(|arr| arr.iter().filter_map(|v| v.as_str()).collect())
+        .unwrap_or_default();
+    whitelist.iter().any(|e| match e.kind.as_str() {
+        "role" => roles.contains(&e.target_id.as_str()),
+        _ => e.target_id == user_id,
+    }) || db.is_admin(guild_id, user_id).await.unwrap_or(false)
+}
+
+pub async fn run(guild_id: &str, rest: &RestClient, db: &Database) -> anyhow::Result<Vec<Finding>> {
+    let guild = rest.get_guild(guild_id).await?;
+    let owner_id = guild["owner_id"].as_str().unwrap_or("");
+    let roles = rest.get_guild_roles(guild_id).await?;
+    let by_id: HashMap<&str, &Value> = roles.iter().filter_map(|r| r["id"].as_str().map(|id| (id, r))).collect();
+    let everyone = by_id.get(guild_id).map(|r| role_permissions(r)).unwrap_or(0);
+
+    let mut findings = vec![hierarchy(guild_id, &roles, rest).await];
+
+    let members = match rest.get_all_guild_members(guild_id).await {
+        Ok(m) => m,
+        Err(e) => {
+            warn!("Audit could not list members of guild {}: {:?}", guild_id, e);
+            Vec::new()
+        }
+    };
+    let self_id = rest.validate_token().await.map(|u| u.id).unwrap_or_default();
+    let whitelist = db.list_whitelist(guild_id).await.unwrap_or_default();
+
+    let mut admins = Finding::new("Administrators");
+    let admin_roles: Vec<String> = roles.iter()
+        .filter(|r| r["id"].as_str() != Some(guild_id) && !r["managed"].as_bool().unwrap_or(false))
+        .filter(|r| role_permissions(r) & permissions::ADMINISTRATOR != 0)
+        .map(|r| format!("Role <@&{}>", r["id"].as_str().unwrap_or("")))
+        .collect();
+    let mut admin_members = Vec::new();
+    for member in &members {
+        let user_id = member["user"]["id"].as_str().unwrap_or("");
+        if user_id == owner_id || member["user"]["bot"].as_bool().unwrap_or(false) { continue; }
+        if member_permissions(member, everyone, &by_id) & permissions::ADMINISTRATOR == 0 { continue; }
+        let trusted = is_whitelisted(guild_id, member, &whitelist, db).await;
+        admin_members.push(format!("<@{}>{}", user_id, if trusted { " (whitelisted)" } else { " (not whitelisted)" }));
+    }
+    if members.is_empty() {
+        admins.details.push("Member list unavailable: only roles were checked".to_string());
+    }
+    admins.flag_all(
+        [admin_roles, admin_members].concat(),
+        "Remove Administrator from roles that do not need it. Whitelist the admins you trust with `!whitelist add @user`; anyone else acting with these permissions is treated as an attacker.",
+    );
+    findings.push(admins);
+
+    let mut bots = Finding::new("Bots With Dangerous Permissions");
+    let mut risky_bots = Vec::new();
+    for member in members.iter().filter(|m| m["user"]["bot"].as_bool().unwrap_or(false)) {
+        let bot_id = member["user"]["id"].as_str().unwrap_or("");
+        if bot_id == self_id { continue; }
+        let perms = member_permissions(member, everyone, &by_id) & permissions::DANGEROUS;
+        if perms == 0 { continue; }
+        let trusted = is_whitelisted(guild_id, member, &whitelist, db).await;
+        risky_bots.push(format!("<@{}>{}: {}", bot_id, if trusted { " (whitelisted)" } else { "" }, perm_list(perms)));
+    }
+    if members.is_empty() {
+        bots.details.push("Member list unavailable: bots were not checked".to_string());
+    }
+    bots.flag_all(
+        risky_bots,
+        "A compromised bot can nuke the server in seconds. Strip permissions these bots do not need, and only whitelist bots you fully trust.",
+    );
+    findings.push(bots);
+
+    let mut hooks = Finding::new("Public Webhooks");
+    let channels = rest.get_guild_channels(guild_id).await?.as_array().cloned().unwrap_or_default();
+    let webhooks = rest.get_guild_webhooks(guild_id).await.unwrap_or_default();
+    let public_hooks = webhooks.iter()
+        .filter(|w| {
+            let channel_id = w["channel_id"].as_str();
+            channels.iter().any(|c| c["id"].as_str() == channel_id && is_public(c, guild_id, everyone))
+        })
+        .map(|w| format!("`{}` in <#{}>", w["name"].as_str().unwrap_or("Unnamed"), w["channel_id"].as_str().unwrap_or("")))
+        .collect();
+    hooks.flag_all(
+        public_hooks,
+        "Anyone with a leaked webhook URL can post (and ping @everyone) in these channels. Delete unused webhooks or move them to staff-only channels.",
+    );
+    findings.push(hooks);
+
+    let mut everyone_check = Finding::new("@everyone Permissions");
+    let risky = everyone & RISKY_EVERYONE;
+    if risky != 0 {
+        everyone_check.flag(format!("@everyone has: {}", perm_list(risky)));
+        everyone_check.fix = Some("Remove these permissions from @everyone in **Server Settings → Roles → @everyone**.".to_string());
+    }
+    findings.push(everyone_check);
+
+    let mut intents = Finding::new("Gateway Intents");
+    match rest.get_current_application().await {
+        Ok(app) => {
+            let flags = app["flags"].as_u64().unwrap_or(0);
+            for (bits, name) in [
+                (app_flag::GATEWAY_GUILD_MEMBERS | app_flag::GATEWAY_GUILD_MEMBERS_LIMITED, "Server Members"),
+                (app_flag::GATEWAY_MESSAGE_CONTENT | app_flag::GATEWAY_MESSAGE_CONTENT_LIMITED, "Message Content"),
+            ] {
+                if flags & bits == 0 {
+                    intents.flag(format!("Privileged **{}** intent is disabled for this application", name));
+                }
+            }
+        }
+        Err(_) => intents.details.push("Could not read application flags: privileged intents were not checked".to_string()),
+    }
+    if !intents.ok {
+        intents.fix = Some("Enable the privileged intents under **Developer Portal → Bot → Privileged Gateway Intents** and restart the bot.".to_string());
+    }
+    findings.push(intents);
+
+    Ok(findings)
+}
@@ -0,0 +1,144 @@
+use crate::rest::RestClient;
+use crate::db::Database;
+use crate::models::audit_action;
+use crate::antinuke::Incident;
+use std::sync::Arc;
+use serde_json::{json, Value};
+use tracing::{warn, error};
+
+pub async fn handle_event(
+    event_type: &str,
+    data: Value,
+    rest: Arc<RestClient>,
+    db: Arc<Database>,
+) {
+    let guild_id = match event_type {
+        "GUILD_CREATE" => data.get("id").and_then(|v| v.as_str()).unwrap_or(""),
+        _ => data.get("guild_id").and_then(|v| v.as_str()).unwrap_or(""),
+    };
+    let settings = match db.get_antinuke_settings(guild_id).await {
+        Ok(s) => s,
+        Err(e) => {
+            error!("Failed to fetch antinuke settings for guild {}: {:?}", guild_id, e);
+            return;
+        }
+    };
+
+    let rule_id = data.get("id").and_then(|v| v.as_str()).unwrap_or("");
+
+    let (setting, action_type) = match event_type {
+        "GUILD_CREATE" => {
+            match rest.get_automod_rules(guild_id).await {
+                Ok(rules) => {
+                    if let Err(e) = db.replace_snapshots(guild_id, "automod_rule", &rules).await {
+                        error!("Failed to cache AutoMod rules for guild {}: {:?}", guild_id, e);
+                    }
+                }
+                Err(e) => warn!("Failed to fetch AutoMod rules for guild {}: {:?}", guild_id, e),
+            }
+            return;
+        }
+        "AUTO_MODERATION_RULE_CREATE" => ("anti_automod_create", audit_action::AUTO_MODERATION_RULE_CREATE),
+        "AUTO_MODERATION_RULE_UPDATE" => ("anti_automod_update", audit_action::AUTO_MODERATION_RULE_UPDATE),
+        "AUTO_MODERATION_RULE_DELETE" => ("anti_automod_delete", audit_action::AUTO_MODERATION_RULE_DELETE),
+        _ => return,
+    };
+
+    let previous = db.get_snapshot(guild_id, "automod_rule", rule_id).await.ok().flatten();
+
+    let entry = if *settings.get(setting).unwrap_or(&false) {
+        crate::antinuke::find_audit_entry(guild_id, action_type, Some(rule_id), &rest).await
+    } else {
+        None
+    };
+    let entry = match entry {
+        Some(e) => e,
+        None => {
+            sync_snapshot(event_type, guild_id, rule_id, &data, &db).await;
+            return;
+        }
+    };
+    let executor_id = entry.get("user_id").and_then(|v| v.as_str()).unwrap_or("").to_string();
+
+    if executor_id.is_empty() || crate::antinuke::is_trusted(guild_id, &executor_id, setting, &rest, &db).await {
+        sync_snapshot(event_type, guild_id, rule_id, &data, &db).await;
+        return;
+    }
+
+    let rule_name = data.get("name").and_then(|v| v.as_str()).unwrap_or("unknown");
+    warn!("ANTINUKE: Unauthorized AutoMod change ({}) by {} in server {}.", event_type, executor_id, guild_id);
+
+    let mut incident = Incident::new(guild_id, setting, &executor_id, format!("Modified AutoMod rule '{}'", rule_name))
+        .with_audit_entry(&entry)
+        .monitored(&db).await;
+    incident.targets.push(format!("automod_rule:{}", rule_id));
+    let reason = "Rimuru Antinuke: Reverting Unauthorized AutoMod Change";
+
+    if incident.monitor {
+        incident.would(match event_type {
+            "AUTO_MODERATION_RULE_CREATE" => format!("delete AutoMod rule '{}'", rule_name),
+            "AUTO_MODERATION_RULE_UPDATE" => format!("restore AutoMod rule '{}' from snapshot", rule_name),
+            _ => format!("recreate AutoMod rule '{}' from snapshot", rule_name),
+        });
+        incident.punishment = crate::antinuke::punish(guild_id, &executor_id, "Rimuru Antinuke: Unauthorized AutoMod Change", incident.monitor, &rest, &db).await;
+        incident.report(&rest, &db).await;
+        sync_snapshot(event_type, guild_id, rule_id, &data, &db).await;
+        return;
+    }
+
+    match event_type {
+        "AUTO_MODERATION_RULE_CREATE" => {
+            match rest.delete_automod_rule(guild_id, rule_id, reason).await {
+                Ok(_) => incident.recovery.push(format!("Deleted AutoMod rule '{}'", rule_name)),
+                Err(e) => incident.recovery.push(format!("Failed to delete AutoMod rule: {}", e)),
+            }
+        }
+        "AUTO_MODERATION_RULE_UPDATE" => match &previous {
+            Some(snapshot) => match rest.modify_automod_rule(guild_id, rule_id, restore_body(snapshot, false), reason).await {
+                Ok(_) => incident.recovery.push(format!("Restored AutoMod rule '{}' from snapshot", rule_name)),
+                Err(e) => incident.recovery.push(format!("Failed to restore AutoMod rule: {}", e)),
+            },
+            None => incident.recovery.push("No snapshot available to restore the rule".to_string()),
+        },
+        _ => match &previous {
+            Some(snapshot) => match rest.create_automod_rule(guild_id, restore_body(snapshot, true), reason).await {
+                Ok(_) => {
+                    let _ = db.delete_snapshot(guild_id, "automod_rule", rule_id).await;
+                    incident.recovery.push(format!("Recreated AutoMod rule '{}' from snapshot", rule_name));
+                }
+                Err(e) => incident.recovery.push(format!("Failed to recreate AutoMod rule: {}", e)),
+            },
+            None => incident.recovery.push("No snapshot available to recreate the rule".to_string()),
+        },
+    }
+
+    incident.punishment = crate::antinuke::punish(guild_id, &executor_id, "Rimuru Antinuke: Unauthorized AutoMod Change", incident.monitor, &rest, &db).await;
+    incident.report(&rest, &db).await;
+}
+
+fn restore_body(snapshot: &Value, include_trigger_type: bool) -> Value {
+    let mut body = json!({
+        "name": snapshot["name"],
+        "event_type": snapshot["event_type"],
+        "trigger_metadata": snapshot["trigger_metadata"],
+        "actions": snapshot["actions"],
+        "enabled": snapshot["enabled"],
+        "exempt_roles": snapshot["exempt_roles"],
+        "exempt_channels": snapshot["exempt_channels"]
+    });
+    if include_trigger_type {
+        body["trigger_type"] = snapshot["trigger_type"].clone();
+    }
+    body
+}
+
+async fn sync_snapshot(event_type: &str, guild_id: &str, rule_id: &str, data: &Value, db: &Database) {
+    let result = if event_type == "AUTO_MODERATION_RULE_DELETE" {
+        db.delete_snapshot(guild_id, "automod_rule", rule_id).await
+    } else {
+        db.save_snapshot(guild_id, "automod_rule", rule_id, data).await
+    };
+    if let Err(e) = result {
+        error!("Failed to update AutoMod snapshot for guild {}: {:?}", guild_id, e);
+    }
+}
@@ -0,0 +1,561 @@
+use crate::rest::RestClient;
+use crate::db::Database;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use serde_json::{json, Value};
+use tokio::time::{interval, Duration};
+use tracing::{info, warn, error};
+
+pub const BACKUP_VERSION: u64 = 1;
+
+const RESTORE_REASON: &str = "Rimuru Backup: Restoring guild structure";
+
+const SETTINGS_FIELDS: [&str; 12] = [
+    "name", "description", "verification_level", "default_message_notifications", "explicit_content_filter",
+    "afk_timeout", "afk_channel_id", "system_channel_id", "system_channel_flags", "rules_channel_id",
+    "public_updates_channel_id", "preferred_locale",
+];
+
+const SETTINGS_CHANNEL_FIELDS: [&str; 4] = ["afk_channel_id", "system_channel_id", "rules_channel_id", "public_updates_channel_id"];
+
+const ROLE_FIELDS: [&str; 5] = ["name", "color", "hoist", "mentionable", "permissions"];
+
+const CHANNEL_FIELDS: [&str; 7] = ["name", "topic", "nsfw", "rate_limit_per_user", "bitrate", "user_limit", "parent_id"];
+
+pub const SCOPES: [&str; 4] = ["roles", "channels", "settings", "emojis"];
+
+fn pick(source: &Value, fields: &[&str]) -> Value {
+    let mut out = json!({});
+    for field in fields {
+        out[*field] = source.get(*field).cloned().unwrap_or(Value::Null);
+    }
+    out
+}
+
+fn items<'a>(doc: &'a Value, key: &str) -> Vec<&'a Value> {
+    doc[key].as_array().map(|arr| arr.iter().collect()).unwrap_or_default()
+}
+
+fn id_of(item: &Value) -> &str {
+    item["id"].as_str().unwrap_or("")
+}
+
+pub async fn capture(guild_id: &str, rest: &RestClient) -> anyhow::Result<Value> {
+    let guild = rest.get_guild(guild_id).await?;
+    let roles = rest.get_guild_roles(guild_id).await?;
+    let channels = rest.get_guild_channels(guild_id).await?.as_array().cloned().unwrap_or_default();
+
+    let roles: Vec<Value> = roles.iter().map(|r| {
+        let mut role = pick(r, &ROLE_FIELDS);
+        role["id"] = r["id"].clone();
+        role["position"] = r["position"].clone();
+        role["managed"] = r["managed"].clone();
+        role
+    }).collect();
+
+    let channels: Vec<Value> = channels.iter().map(|c| {
+        let mut channel = pick(c, &CHANNEL_FIELDS);
+        channel["id"] = c["id"].clone();
+        channel["type"] = c["type"].clone();
+        channel["position"] = c["position"].clone();
+        channel["permission_overwrites"] = c["permission_overwrites"].as_array()
+            .map(|arr| arr.iter().map(|o| pick(o, &["id", "type", "allow", "deny"])).collect::<Vec<_>>())
+            .map(Value::from)
+            .unwrap_or_else(|| json!([]));
+        channel
+    }).collect();
+
+    let emojis: Vec<Value> = guild["emojis"].as_array()
+        .map(|arr| arr.iter().map(|e| pick(e, &["id", "name", "animated", "roles"])).collect())
+        .unwrap_or_default();
+
+    Ok(json!({
+        "version": BACKUP_VERSION,
+        "guild_id": guild_id,
+        "created_at": chrono::Utc::now().to_rfc3339(),
+        "settings": pick(&guild, &SETTINGS_FIELDS),
+        "roles": roles,
+        "channels": channels,
+        "emojis": emojis,
+    }))
+}
+
+pub fn validate(doc: &Value) -> anyhow::Result<()> {
+    match doc["version"].as_u64() {
+        Some(v) if v <= BACKUP_VERSION => {}
+        Some(v) => anyhow::bail!("Backup version {} is newer than this bot supports ({})", v, BACKUP_VERSION),
+        None => anyhow::bail!("Backup is missing a version number"),
+    }
+    for key in ["roles", "channels", "emojis"] {
+        if !doc[key].is_array() {
+            anyhow::bail!("Backup is missing its `{}` list", key);
+        }
+    }
+    if !doc["settings"].is_object() {
+        anyhow::bail!("Backup is missing its `settings` object");
+    }
+    Ok(())
+}
+
+pub struct BackupDiff {
+    pub role_map: HashMap<String, String>,
+    pub channel_map: HashMap<String, String>,
+    pub roles_missing: Vec<Value>,
+    pub roles_changed: Vec<(String, Value, Vec<&'static str>)>,
+    pub role_order: Vec<(String, u64)>,
+    pub channels_missing: Vec<Value>,
+    pub channels_changed: Vec<(String, Value, Vec<&'static str>)>,
+    pub settings_changed: Vec<&'static str>,
+    pub emojis_missing: Vec<Value>,
+}
+
+impl BackupDiff {
+    pub fn is_empty(&self) -> bool {
+        self.roles_missing.is_empty() && self.roles_changed.is_empty() && self.role_order.is_empty()
+            && self.channels_missing.is_empty() && self.channels_changed.is_empty()
+            && self.settings_changed.is_empty() && self.emojis_missing.is_empty()
+    }
+}
+
+fn match_items(saved: &[&Value], current: &[&Value], same_kind: impl Fn(&Value, &Value) -> bool) -> HashMap<String, String> {
+    let mut map = HashMap::new();
+    let mut used: HashSet<String> = HashSet::new();
+
+    for item in saved {
+        if current.iter().any(|c| id_of(c) == id_of(item)) {
+            map.insert(id_of(item).to_string(), id_of(item).to_string());
+            used.insert(id_of(item).to_string());
+        }
+    }
+    for item in saved {
+        if map.contains_key(id_of(item)) { continue; }
+        if let Some(found) = current.iter().find(|c| !used.contains(id_of(c)) && c["name"] == item["name"] && same_kind(c, item)) {
+            map.insert(id_of(item).to_string(), id_of(found).to_string());
+            used.insert(id_of(found).to_string());
+        }
+    }
+    map
+}
+
+fn mapped_overwrites(overwrites: &Value, role_map: Option<&HashMap<String, String>>) -> Vec<Value> {
+    let mut out: Vec<Value> = overwrites.as_array().into_iter().flatten().filter_map(|o| {
+        let id = id_of(o);
+        let target = match role_map {
+            Some(map) if o["type"].as_u64() == Some(0) => map.get(id).cloned()?,
+            _ => id.to_string(),
+        };
+        Some(json!({ "id": target, "type": o["type"], "allow": o["allow"], "deny": o["deny"] }))
+    }).collect();
+    out.sort_by(|a, b| id_of(a).cmp(id_of(b)));
+    out
+}
+
+fn unmapped_overwrites(overwrites: &Value, role_map: &HashMap<String, String>) -> usize {
+    overwrites.as_array().into_iter().flatten()
+        .filter(|o| o["type"].as_u64() == Some(0) && !role_map.contains_key(id_of(o)))
+        .count()
+}
+
+fn role_order(saved_roles: &[&Value], current_roles: &[&Value], role_map: &HashMap<String, String>, guild_id: &str) -> Vec<(String, u64)> {
+    let position = |r: &Value| r["position"].as_u64().unwrap_or(0);
+    let mut saved: Vec<&Value> = saved_roles.iter().copied()
+        .filter(|r| id_of(r) != guild_id && !r["managed"].as_bool().unwrap_or(false))
+        .collect();
+    saved.sort_by_key(|r| (position(r), id_of(r).to_string()));
+
+    let desired: Vec<&Value> = saved.iter()
+        .filter_map(|r| role_map.get(id_of(r)))
+        .filter_map(|id| current_roles.iter().copied().find(|c| id_of(c) == id))
+        .collect();
+    let mut slots: Vec<u64> = desired.iter().map(|r| position(r)).collect();
+    slots.sort_unstable();
+
+    desired.iter().zip(slots)
+        .filter(|(role, slot)| position(role) != *slot)
+        .map(|(role, slot)| (id_of(role).to_string(), slot))
+        .collect()
+}
+
+type ChannelDiff = (HashMap<String, String>, Vec<Value>, Vec<(String, Value, Vec<&'static str>)>);
+
+fn diff_channels(backup: &Value, current: &Value, role_map: &HashMap<String, String>) -> ChannelDiff {
+    let saved_channels = items(backup, "channels");
+    let current_channels = items(current, "channels");
+    let channel_map = match_items(&saved_channels, &current_channels, |a, b| a["type"] == b["type"]);
+
+    let mut channels_missing = Vec::new();
+    let mut channels_changed = Vec::new();
+    for channel in &saved_channels {
+        match channel_map.get(id_of(channel)).and_then(|id| current_channels.iter().find(|c| id_of(c) == id)) {
+            Some(found) => {
+                let mut changed: Vec<&'static str> = CHANNEL_FIELDS.iter().copied()
+                    .filter(|f| {
+                        if *f == "parent_id" {
+                            let expected = channel["parent_id"].as_str().and_then(|p| channel_map.get(p));
+                            return found["parent_id"].as_str() != expected.map(String::as_str);
+                        }
+                        !channel[*f].is_null() && found[*f] != channel[*f]
+                    })
+                    .collect();
+                if mapped_overwrites(&channel["permission_overwrites"], Some(role_map)) != mapped_overwrites(&found["permission_overwrites"], None) {
+                    changed.push("permission_overwrites");
+                }
+                if unmapped_overwrites(&channel["permission_overwrites"], role_map) > 0 {
+                    changed.push("overwrites for deleted roles");
+                }
+                if !changed.is_empty() {
+                    channels_changed.push((id_of(found).to_string(), (*channel).clone(), changed));
+                }
+            }
+            None => channels_missing.push((*channel).clone()),
+        }
+    }
+    (channel_map, channels_missing, channels_changed)
+}
+
+pub fn diff(backup: &Value, current: &Value) -> BackupDiff {
+    let guild_id = backup["guild_id"].as_str().unwrap_or("");
+    let saved_roles = items(backup, "roles");
+    let current_roles = items(current, "roles");
+    let mut role_map = match_items(&saved_roles, &current_roles, |_, _| true);
+    role_map.insert(guild_id.to_string(), current["guild_id"].as_str().unwrap_or(guild_id).to_string());
+
+    let mut roles_missing = Vec::new();
+    let mut roles_changed = Vec::new();
+    for role in &saved_roles {
+        if role["managed"].as_bool().unwrap_or(false) { continue; }
+        match role_map.get(id_of(role)).and_then(|id| current_roles.iter().find(|c| id_of(c) == id)) {
+            Some(found) => {
+                let changed: Vec<&'static str> = ROLE_FIELDS.iter().copied().filter(|f| found[*f] != role[*f]).collect();
+                if !changed.is_empty() {
+                    roles_changed.push((id_of(found).to_string(), (*role).clone(), changed));
+                }
+            }
+            None => roles_missing.push((*role).clone()),
+        }
+    }
+    let role_order = role_order(&saved_roles, &current_roles, &role_map, guild_id);
+
+    let (channel_map, channels_missing, channels_changed) = diff_channels(backup, current, &role_map);
+
+    let settings_changed: Vec<&'static str> = SETTINGS_FIELDS.iter().copied()
+        .filter(|f| {
+            if SETTINGS_CHANNEL_FIELDS.contains(f) {
+                let expected = backup["settings"][*f].as_str().and_then(|c| channel_map.get(c));
+                return current["settings"][*f].as_str() != expected.map(String::as_str);
+            }
+            backup["settings"][*f] != current["settings"][*f]
+        })
+        .collect();
+
+    let current_emojis = items(current, "emojis");
+    let emojis_missing = items(backup, "emojis").into_iter()
+        .filter(|e| !current_emojis.iter().any(|c| id_of(c) == id_of(e) || c["name"] == e["name"]))
+        .cloned()
+        .collect();
+
+    BackupDiff { role_map, channel_map, roles_missing, roles_changed, role_order, channels_missing, channels_changed, settings_changed, emojis_missing }
+}
+
+#[derive(Default)]
+pub struct RestoreSummary {
+    pub created: usize,
+    pub updated: usize,
+    pub failed: Vec<String>,
+}
+
+impl RestoreSummary {
+    fn record(&mut self, result: anyhow::Result<()>, created: bool, what: String) {
+        match result {
+            Ok(_) if created => self.created += 1,
+            Ok(_) => self.updated += 1,
+            Err(e) => {
+                error!("Backup restore failed for {}: {:?}", what, e);
+                self.failed.push(what);
+            }
+        }
+    }
+}
+
+fn channel_body(channel: &Value, diff: &BackupDiff) -> Value {
+    let mut body = json!({});
+    for field in CHANNEL_FIELDS {
+        if field == "parent_id" { continue; }
+        if !channel[field].is_null() {
+            body[field] = channel[field].clone();
+        }
+    }
+    body["parent_id"] = channel["parent_id"].as_str()
+        .and_then(|p| diff.channel_map.get(p))
+        .map(|p| json!(p))
+        .unwrap_or(Value::Null);
+    body["permission_overwrites"] = json!(mapped_overwrites(&channel["permission_overwrites"], Some(&diff.role_map)));
+    body
+}
+
+pub async fn restore(guild_id: &str, backup: &Value, scopes: &[&str], rest: &RestClient, db: &Database) -> anyhow::Result<RestoreSummary> {
+    validate(backup)?;
+    let current = capture(guild_id, rest).await?;
+    let mut diff = diff(backup, &current);
+    let mut summary = RestoreSummary::default();
+
+    if scopes.contains(&"roles") {
+        for role in diff.roles_missing.clone() {
+            let name = role["name"].as_str().unwrap_or("restored");
+            let created = rest.create_role(
+                guild_id,
+                name,
+                role["color"].as_u64().unwrap_or(0) as u32,
+                role["hoist"].as_bool().unwrap_or(false),
+                role["permissions"].as_str().unwrap_or("0"),
+            ).await;
+            let result = match created {
+                Ok(new_role) => {
+                    let new_id = id_of(&new_role).to_string();
+                    diff.role_map.insert(id_of(&role).to_string(), new_id.clone());
+                    if role["mentionable"].as_bool().unwrap_or(false) {
+                        let _ = rest.modify_role(guild_id, &new_id, json!({ "mentionable": true }), RESTORE_REASON).await;
+                    }
+                    if let Some(position) = role["position"].as_u64() {
+                        let _ = rest.modify_role_positions(guild_id, &new_id, position).await;
+                    }
+                    Ok(())
+                }
+                Err(e) => Err(e),
+            };
+            summary.record(result, true, format!("role {}", name));
+        }
+
+        for (role_id, role, _) in &diff.roles_changed {
+            let result = rest.modify_role(guild_id, role_id, pick(role, &ROLE_FIELDS), RESTORE_REASON).await;
+            summary.record(result, false, format!("role {}", role["name"].as_str().unwrap_or(role_id)));
+        }
+
+        let refreshed = capture(guild_id, rest).await?;
+        let order = role_order(&items(backup, "roles"), &items(&refreshed, "roles"), &diff.role_map, guild_id);
+        if !order.is_empty() {
+            let result = rest.modify_role_order(guild_id, &order, RESTORE_REASON).await;
+            summary.record(result, false, "role order".to_string());
+        }
+
+        let (channel_map, channels_missing, channels_changed) = diff_channels(backup, &refreshed, &diff.role_map);
+        diff.channel_map = channel_map;
+        diff.channels_missing = channels_missing;
+        diff.channels_changed = channels_changed;
+    }
+
+    if scopes.contains(&"channels") {
+        let mut missing = diff.channels_missing.clone();
+        missing.sort_by_key(|c| (c["type"].as_u64() != Some(4), c["position"].as_u64().unwrap_or(0)));
+
+        for channel in missing {
+            let mut body = channel_body(&channel, &diff);
+            body["type"] = channel["type"].clone();
+            body["position"] = channel["position"].clone();
+            let name = channel["name"].as_str().unwrap_or("restored").to_string();
+            let result = match rest.create_guild_channel(guild_id, body, RESTORE_REASON).await {
+                Ok(created) => {
+                    diff.channel_map.insert(id_of(&channel).to_string(), id_of(&created).to_string());
+                    Ok(())
+                }
+                Err(e) => Err(e),
+            };
+            summary.record(result, true, format!("channel #{}", name));
+            tokio::time::sleep(Duration::from_millis(250)).await;
+        }
+
+        for (channel_id, channel, _) in diff.channels_changed.clone() {
+            let result = rest.modify_channel(&channel_id, channel_body(&channel, &diff), RESTORE_REASON).await;
+            summary.record(result, false, format!("channel #{}", channel["name"].as_str().unwrap_or(&channel_id)));
+            tokio::time::sleep(Duration::from_millis(250)).await;
+        }
+    }
+
+    if scopes.contains(&"settings") && !diff.settings_changed.is_empty() {
+        let mut body = json!({});
+        for field in &diff.settings_changed {
+            body[*field] = if SETTINGS_CHANNEL_FIELDS.contains(field) {
+                backup["settings"][*field].as_str()
+                    .and_then(|c| diff.channel_map.get(c))
+                    .map(|c| json!(c))
+                    .unwrap_or(Value::Null)
+            } else {
+                backup["settings"][*field].clone()
+            };
+        }
+        let result = rest.modify_guild(guild_id, body, RESTORE_REASON).await;
+        summary.record(result, false, "server settings".to_string());
+    }
+
+    if scopes.contains(&"emojis") {
+        for emoji in &diff.emojis_missing {
+            let name = emoji["name"].as_str().unwrap_or("restored");
+            let result = match db.get_archived_emoji(guild_id, id_of(emoji)).await {
+                Ok(Some((_, content_type, image))) => {
+                    let data_uri = crate::rest::image_data_uri(&content_type, &image);
+                    rest.create_guild_emoji(guild_id, name, &data_uri, RESTORE_REASON).await.map(|_| ())
+                }
+                _ => Err(anyhow::anyhow!("no archived image")),
+            };
+            summary.record(result, true, format!("emoji :{}:", name));
+        }
+    }
+
+    info!(
+        "Backup restore in guild {}: {} created, {} updated, {} failed",
+        guild_id, summary.created, summary.updated, summary.failed.len()
+    );
+    Ok(summary)
+}
+
+pub async fn run_scheduler(rest: Arc<RestClient>, db: Arc<Database>) {
+    let mut ticker = interval(Duration::from_secs(600));
+    loop {
+        ticker.tick().await;
+
+        let schedules = match db.list_backup_schedules().await {
+            Ok(s) => s,
+            Err(e) => {
+                error!("Failed to load backup schedules: {:?}", e);
+                continue;
+            }
+        };
+
+        let now = chrono::Utc::now();
+        for (guild_id, hours, keep, last_run) in schedules {
+            let due = last_run.as_deref()
+                .and_then(|t| chrono::DateTime::parse_from_rfc3339(t).ok())
+                .map(|t| now.signed_duration_since(t) >= chrono::Duration::hours(hours as i64))
+                .unwrap_or(true);
+            if !due { continue; }
+
+            match capture(&guild_id, &rest).await {
+                Ok(doc) => {
+                    if let Err(e) = db.save_backup(&guild_id, "Scheduled backup", "scheduler", true, &doc).await {
+                        error!("Failed to store scheduled backup for guild {}: {:?}", guild_id, e);
+                        continue;
+                    }
+                    let _ = db.prune_automatic_backups(&guild_id, keep).await;
+                    let _ = db.mark_backup_run(&guild_id).await;
+                    info!("Scheduled backup stored for guild {}", guild_id);
+                }
+                Err(e) => warn!("Scheduled backup failed for guild {}: {:?}", guild_id, e),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(roles: Value, channels: Value) -> Value {
+        json!({
+            "version": BACKUP_VERSION,
+            "guild_id": "1",
+            "settings": {},
+            "roles": roles,
+            "channels": channels,
+            "emojis": []
+        })
+    }
+
+    fn role(id: &str, name: &str, position: u64) -> Value {
+        json!({ "id": id, "name": name, "position": position, "color": 0, "hoist": false, "mentionable": false, "permissions": "0", "managed": false })
+    }
+
+    fn channel(id: &str, name: &str, kind: u64, parent: Option<&str>, overwrites: Value) -> Value {
+        json!({ "id": id, "name": name, "type": kind, "parent_id": parent, "permission_overwrites": overwrites })
+    }
+
+    #[test]
+    fn validate_rejects_incomplete_backups() {
+        assert!(validate(&snapshot(json!([]), json!([]))).is_ok());
+
+        let mut newer = snapshot(json!([]), json!([]));
+        newer["version"] = json!(BACKUP_VERSION + 1);
+        assert!(validate(&newer).is_err());
+
+        let mut no_channels = snapshot(json!([]), json!([]));
+        no_channels.as_object_mut().unwrap().remove("channels");
+        assert!(validate(&no_channels).is_err());
+
+        let mut no_settings = snapshot(json!([]), json!([]));
+        no_settings["settings"] = json!([]);
+        assert!(validate(&no_settings).is_err());
+    }
+
+    #[test]
+    fn recreated_roles_and_channels_are_remapped() {
+        let overwrite = |id: &str| json!([{ "id": id, "type": 0, "allow": "1024", "deny": "0" }]);
+        let backup = snapshot(
+            json!([role("10", "Mods", 1)]),
+            json!([
+                channel("100", "staff", 4, None, json!([])),
+                channel("101", "mod-chat", 0, Some("100"), overwrite("10"))
+            ]),
+        );
+        let current = snapshot(
+            json!([role("20", "Mods", 1)]),
+            json!([
+                channel("200", "staff", 4, None, json!([])),
+                channel("201", "mod-chat", 0, Some("200"), overwrite("20"))
+            ]),
+        );
+
+        let diff = diff(&backup, &current);
+        assert_eq!(diff.role_map.get("10").map(String::as_str), Some("20"));
+        assert_eq!(diff.channel_map.get("101").map(String::as_str), Some("201"));
+        assert!(diff.is_empty());
+    }
+
+    #[test]
+    fn moved_channel_and_changed_overwrites_are_reported() {
+        let backup = snapshot(
+            json!([role("10", "Mods", 1)]),
+            json!([
+                channel("100", "staff", 4, None, json!([])),
+                channel("101", "mod-chat", 0, Some("100"), json!([{ "id": "10", "type": 0, "allow": "1024", "deny": "0" }]))
+            ]),
+        );
+        let current = snapshot(
+            json!([role("10", "Mods", 1)]),
+            json!([
+                channel("100", "staff", 4, None, json!([])),
+                channel("101", "mod-chat", 0, None, json!([{ "id": "10", "type": 0, "allow": "0", "deny": "1024" }]))
+            ]),
+        );
+
+        let diff = diff(&backup, &current);
+        assert_eq!(diff.channels_changed.len(), 1);
+        let (id, _, fields) = &diff.channels_changed[0];
+        assert_eq!(id, "101");
+        assert_eq!(fields, &vec!["parent_id", "permission_overwrites"]);
+    }
+
+    #[test]
+    fn overwrites_for_deleted_roles_are_a_change() {
+        let backup = snapshot(
+            json!([role("10", "Mods", 1)]),
+            json!([channel("101", "mod-chat", 0, None, json!([{ "id": "10", "type": 0, "allow": "1024", "deny": "0" }]))]),
+        );
+        let current = snapshot(json!([]), json!([channel("101", "mod-chat", 0, None, json!([]))]));
+
+        let diff = diff(&backup, &current);
+        assert_eq!(diff.roles_missing.len(), 1);
+        assert_eq!(diff.channels_changed.len(), 1);
+        assert_eq!(diff.channels_changed[0].2, vec!["overwrites for deleted roles"]);
+        assert!(!diff.is_empty());
+    }
+
+    #[test]
+    fn swapped_roles_are_put_back_in_order() {
+        let backup = snapshot(json!([role("10", "Mods", 1), role("11", "Admins", 2)]), json!([]));
+        let current = snapshot(json!([role("10", "Mods", 2), role("11", "Admins", 1)]), json!([]));
+
+        let diff = diff(&backup, &current);
+        assert!(diff.roles_changed.is_empty());
+        assert_eq!(diff.role_order, vec![("10".to_string(), 1), ("11".to_string(), 2)]);
+        assert!(super::diff(&backup, &backup).role_order.is_empty());
+    }
+}
@@ -0,0 +1,80 @@
+use crate::rest::RestClient;
+use crate::db::Database;
+use crate::models::audit_action;
+use crate::antinuke::Incident;
+use std::sync::Arc;
+use serde_json::Value;
+use tracing::{info, warn, error};
+
+pub async fn handle_event(
+    event_type: &str,
+    data: Value,
+    rest: Arc<RestClient>,
+    db: Arc<Database>,
+) {
+    let guild_id = data.get("guild_id").and_then(|v| v.as_str()).unwrap_or("");
+    let settings = match db.get_antinuke_settings(guild_id).await {
+        Ok(s) => s,
+        Err(e) => {
+            error!("Failed to fetch antinuke settings for guild {}: {:?}", guild_id, e);
+            return;
+        }
+    };
+
+    if event_type == "GUILD_MEMBER_ADD" {
+        let is_bot = data.get("user").and_then(|u| u.get("bot")).and_then(|v| v.as_bool()).unwrap_or(false);
+        if is_bot && *settings.get("anti_bot").unwrap_or(&false) {
+            handle_bot_add(guild_id, &data, &rest, &db).await;
+        }
+    }
+}
+
+async fn handle_bot_add(guild_id: &str, data: &Value, rest: &RestClient, db: &Database) {
+    let bot_id = data.get("user").and_then(|u| u.get("id")).and_then(|v| v.as_str()).unwrap_or("");
+    if bot_id.is_empty() { return; }
+
+    if db.is_trusted_bot(guild_id, bot_id).await.unwrap_or(false) {
+        info!("ANTINUKE: Trusted bot {} joined server {}.", bot_id, guild_id);
+        return;
+    }
+
+    let entry = match crate::antinuke::find_audit_entry(guild_id, audit_action::BOT_ADD, Some(bot_id), rest).await {
+        Some(e) => e,
+        None => {
+            warn!("ANTINUKE: Bot {} joined server {} but no BOT_ADD audit entry was found.", bot_id, guild_id);
+            return;
+        }
+    };
+
+    let inviter_id = entry.get("user_id").and_then(|v| v.as_str()).unwrap_or("");
+    if inviter_id.is_empty() || crate::antinuke::is_trusted(guild_id, inviter_id, "anti_bot", rest, db).await {
+        return;
+    }
+
+    warn!("ANTINUKE: Unauthorized bot {} added by {} in server {}.", bot_id, inviter_id, guild_id);
+
+    let mut incident = Incident::new(guild_id, "anti_bot", inviter_id, "Added an unauthorized bot").with_audit_entry(&entry).monitored(db).await;
+    incident.targets.push(bot_id.to_string());
+
+    if incident.monitor {
+        incident.would(format!("ban bot {}", bot_id));
+    } else {
+        remove_bot(guild_id, bot_id, &mut incident, rest).await;
+    }
+
+    incident.punishment = crate::antinuke::punish(guild_id, inviter_id, "Rimuru Antinuke: Unauthorized Bot Addition", incident.monitor, rest, db).await;
+    incident.report(rest, db).await;
+}
+
+async fn remove_bot(guild_id: &str, bot_id: &str, incident: &mut Incident, rest: &RestClient) {
+    match rest.ban_user(guild_id, bot_id, "Rimuru Antinuke: Unauthorized Bot Addition").await {
+        Ok(_) => incident.recovery.push(format!("Banned bot {}", bot_id)),
+        Err(e) => {
+            error!("Failed to ban unauthorized bot {}: {:?}", bot_id, e);
+            match rest.kick_user(guild_id, bot_id, "Rimuru Antinuke: Unauthorized Bot Addition").await {
+                Ok(_) => incident.recovery.push(format!("Kicked bot {}", bot_id)),
+                Err(e) => incident.recovery.push(format!("Failed to remove bot {}: {}", bot_id, e)),
+            }
+        }
+    }
+}
@@ -1,6 +1,7 @@
 use crate::rest::RestClient;
 use crate::db::Database;
-use crate::constants::emojis;
+use crate::models::audit_action;
+use crate::antinuke::Incident;
 use std::sync::Arc;
 use serde_json::{json, Value};
 use tracing::{info, warn, error};
@@ -11,7 +12,10 @@ pub async fn handle_event(
     rest: Arc<RestClient>,
     db: Arc<Database>,
 ) {
-    let guild_id = data.get("guild_id").and_then(|v| v.as_str()).unwrap_or("");
+    let guild_id = match event_type {
+        "GUILD_CREATE" => data.get("id").and_then(|v| v.as_str()).unwrap_or(""),
+        _ => data.get("guild_id").and_then(|v| v.as_str()).unwrap_or(""),
+    };
     let settings = match db.get_antinuke_settings(guild_id).await {
         Ok(s) => s,
         Err(e) => {
@@ -21,9 +25,23 @@ pub async fn handle_event(
     };
 
     match event_type {
+        "GUILD_CREATE" => {
+            let channels: Vec<Value> = data.get("channels")
+                .and_then(|v| v.as_array())
+                .map(|arr| arr.iter().map(channel_snapshot).collect())
+                .unwrap_or_default();
+            if let Err(e) = db.replace_snapshots(guild_id, "channel", &channels).await {
+                error!("Failed to cache channels for guild {}: {:?}", guild_id, e);
+            }
+        }
+        "CHANNEL_UPDATE" => {
+            handle_channel_update(guild_id, &data, *settings.get("anti_channel_update").unwrap_or(&false), &rest, &db).await;
+        }
         "CHANNEL_DELETE" => {
+            let channel_id = data.get("id").and_then(|v| v.as_str()).unwrap_or("");
+            let _ = db.delete_snapshot(guild_id, "channel", channel_id).await;
             if *settings.get("anti_channel_delete").unwrap_or(&false) {
-                detect_mass_channel_action(guild_id, rest, db).await;
+                detect_mass_channel_action(guild_id, channel_id, rest, db).await;
             }
         }
         "THREAD_CREATE" => {
@@ -32,40 +50,137 @@ pub async fn handle_event(
             }
         }
         "CHANNEL_CREATE" => {
+            let channel_id = data.get("id").and_then(|v| v.as_str()).unwrap_or("");
+            let _ = db.save_snapshot(guild_id, "channel", channel_id, &channel_snapshot(&data)).await;
             if *settings.get("anti_channel_create").unwrap_or(&false) {
                 warn!("Antinuke: Unauthorized channel creation detected in {}.", guild_id);
-                crate::antinuke::punish_offender(guild_id, 10, rest.clone(), db.clone()).await;
+                crate::antinuke::punish_offender(guild_id, 10, "anti_channel_create", rest.clone(), db.clone()).await;
             }
         }
         _ => {}
     }
 }
 
-async fn detect_mass_channel_action(guild_id: &str, rest: Arc<RestClient>, db: Arc<Database>) {
-    let now = tokio::time::Instant::now();
-    let mut map = db.channel_tracker.write().await;
-    let entries = map.entry(guild_id.to_string()).or_insert(std::collections::VecDeque::new());
+fn channel_snapshot(channel: &Value) -> Value {
+    let mut overwrites: Vec<Value> = channel.get("permission_overwrites")
+        .and_then(|v| v.as_array())
+        .map(|arr| {
+            arr.iter()
+                .map(|o| json!({ "id": o["id"], "type": o["type"], "allow": o["allow"], "deny": o["deny"] }))
+                .collect()
+        })
+        .unwrap_or_default();
+    overwrites.sort_by(|a, b| a["id"].as_str().cmp(&b["id"].as_str()));
+
+    json!({
+        "id": channel["id"],
+        "name": channel["name"],
+        "topic": channel["topic"],
+        "nsfw": channel.get("nsfw").cloned().unwrap_or(json!(false)),
+        "parent_id": channel["parent_id"],
+        "permission_overwrites": overwrites
+    })
+}
+
+async fn handle_channel_update(guild_id: &str, data: &Value, enabled: bool, rest: &RestClient, db: &Database) {
+    let channel_id = data.get("id").and_then(|v| v.as_str()).unwrap_or("");
+    let current = channel_snapshot(data);
+
+    let previous = match db.get_snapshot(guild_id, "channel", channel_id).await.ok().flatten() {
+        Some(p) if enabled => p,
+        _ => {
+            let _ = db.save_snapshot(guild_id, "channel", channel_id, &current).await;
+            return;
+        }
+    };
+
+    let changed: Vec<&str> = ["name", "topic", "nsfw", "parent_id", "permission_overwrites"]
+        .into_iter()
+        .filter(|f| previous[*f] != current[*f])
+        .collect();
+
+    if changed.is_empty() { return; }
+
+    let mut audit_actions = Vec::new();
+    if changed.iter().any(|f| *f != "permission_overwrites") {
+        audit_actions.push(audit_action::CHANNEL_UPDATE);
+    }
+    if changed.contains(&"permission_overwrites") {
+        audit_actions.extend([
+            audit_action::CHANNEL_OVERWRITE_CREATE,
+            audit_action::CHANNEL_OVERWRITE_UPDATE,
+            audit_action::CHANNEL_OVERWRITE_DELETE,
+        ]);
+    }
 
-    while let Some(&t) = entries.front() {
-        if now.duration_since(t) >= tokio::time::Duration::from_secs(10) {
-            entries.pop_front();
-        } else {
-            break;
+    let mut entry = None;
+    for action_type in audit_actions {
+        entry = crate::antinuke::find_audit_entry(guild_id, action_type, Some(channel_id), rest).await;
+        if entry.is_some() { break; }
+    }
+    let entry = match entry {
+        Some(e) => e,
+        None => {
+            let _ = db.save_snapshot(guild_id, "channel", channel_id, &current).await;
+            return;
         }
+    };
+    let executor_id = entry.get("user_id").and_then(|v| v.as_str()).unwrap_or("").to_string();
+
+    if executor_id.is_empty() || crate::antinuke::is_trusted(guild_id, &executor_id, "anti_channel_update", rest, db).await {
+        let _ = db.save_snapshot(guild_id, "channel", channel_id, &current).await;
+        return;
     }
-    entries.push_back(now);
-
-    if entries.len() >= 1 {
-        warn!("CRITICAL: CHANNEL DELETE detected in server {}.", guild_id);
-        let _alert = json!({
-            "title": format!("{} SECURITY ALERT: CHANNEL DELETION DETECTED", emojis::WARNING),
-            "description": "An unauthorized channel deletion has been detected and intercepted.",
-            "color": 0xff0000,
-            "footer": { "text": "Rimuru Advanced Security | Cog Protection Active" }
-        });
-        info!("ANTINUKE ALERT: Server {} | Action: CHANNEL_DELETE", guild_id);
-
-        crate::antinuke::punish_offender(guild_id, 12, rest, db.clone()).await;
+
+    warn!("ANTINUKE: Unauthorized channel update ({}) by {} in server {}.", changed.join(", "), executor_id, guild_id);
+
+    let mut incident = Incident::new(guild_id, "anti_channel_update", &executor_id, format!("Changed channel settings: {}", changed.join(", ")))
+        .with_audit_entry(&entry)
+        .monitored(db).await;
+    incident.targets.push(format!("channel:{}", channel_id));
+
+    if incident.monitor {
+        incident.would(format!("restore {} on <#{}>", changed.join(", "), channel_id));
+        incident.punishment = crate::antinuke::punish(guild_id, &executor_id, "Rimuru Antinuke: Unauthorized Channel Update", incident.monitor, rest, db).await;
+        incident.report(rest, db).await;
+        let _ = db.save_snapshot(guild_id, "channel", channel_id, &current).await;
+        return;
+    }
+
+    let mut body = json!({});
+    for field in &changed {
+        body[*field] = previous[*field].clone();
+    }
+
+    match rest.modify_channel(channel_id, body, "Rimuru Antinuke: Reverting Unauthorized Channel Update").await {
+        Ok(_) => incident.recovery.push(format!("Restored {} on <#{}>", changed.join(", "), channel_id)),
+        Err(e) => incident.recovery.push(format!("Failed to restore channel <#{}>: {}", channel_id, e)),
+    }
+
+    incident.punishment = crate::antinuke::punish(guild_id, &executor_id, "Rimuru Antinuke: Unauthorized Channel Update", incident.monitor, rest, db).await;
+    incident.report(rest, db).await;
+}
+
+async fn detect_mass_channel_action(guild_id: &str, channel_id: &str, rest: Arc<RestClient>, db: Arc<Database>) {
+    let entry = match crate::antinuke::find_audit_entry(guild_id, audit_action::CHANNEL_DELETE, Some(channel_id), &rest).await {
+        Some(e) => e,
+        None => return,
+    };
+    let executor_id = entry.get("user_id").and_then(|v| v.as_str()).unwrap_or("");
+    if executor_id.is_empty() { return; }
+
+    let (max_actions, window_secs) = db.get_threshold(guild_id, "anti_channel_delete").await;
+    let burst = db.track_action(guild_id, executor_id, "anti_channel_delete", window_secs).await;
+    if burst < max_actions as usize {
+        info!("CHANNEL DELETE by {} in server {} is below threshold ({}/{} within {}s).", executor_id, guild_id, burst, max_actions, window_secs);
+        return;
+    }
+
+    warn!("CRITICAL: CHANNEL DELETE detected in server {} ({} by {} within {}s).", guild_id, burst, executor_id, window_secs);
+
+    let punished = crate::antinuke::punish_entry(guild_id, audit_action::CHANNEL_DELETE, "anti_channel_delete", &entry, &rest, &db).await;
+    if punished && burst >= crate::antinuke::SEVERE_BURST {
+        crate::antinuke::lockdown::auto_engage(guild_id, "Mass channel deletion detected", &rest, &db).await;
     }
 }
 
@@ -74,20 +189,16 @@ async fn handle_thread_lock(data: Value, rest: &RestClient, db: Arc<Database>) {
     let thread_id = data.get("id").and_then(|v| v.as_str()).unwrap_or("");
     let owner_id = data.get("owner_id").and_then(|v| v.as_str()).unwrap_or("");
 
-    if db.is_whitelisted(owner_id).await.unwrap_or(false) || db.is_admin(owner_id).await.unwrap_or(false) {
+    if crate::antinuke::is_trusted(guild_id, owner_id, "thread_lock_enabled", rest, &db).await {
         return;
     }
 
-    if let Ok(bot) = rest.validate_token().await {
-        if bot.id == owner_id {
-            return;
-        }
-    }
-
     if let Ok(threads) = rest.get_active_threads(guild_id).await {
         let count = threads.as_array().map(|a| a.len()).unwrap_or(0);
 
-        if count > 49 {
+        if count > 49 && db.is_monitor_mode(guild_id, "thread_lock_enabled").await {
+            info!("MONITOR: Thread limit reached in {}. Would delete unauthorized thread {}.", guild_id, thread_id);
+        } else if count > 49 {
             warn!("THREAD LOCK: Limit reached in {}. Deleting unauthorized thread {}.", guild_id, thread_id);
             if let Err(e) = rest.delete_channel(thread_id).await {
                 error!("Failed to execute THREAD LOCK via delete_channel: {:?}", e);
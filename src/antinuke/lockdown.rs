@@ -0,0 +1,278 @@
+use crate::rest::RestClient;
+use crate::db::Database;
+use crate::constants::{emojis, colors, permissions};
+use super::is_trusted;
+use serde_json::{json, Value};
+use tracing::{info, warn, error};
+
+const LOCKDOWN_REASON: &str = "Rimuru Lockdown";
+
+const LOCKDOWN_VERIFICATION_LEVEL: u64 = 4;
+
+const LOCKED_PERMS: u64 = permissions::SEND_MESSAGES | permissions::SEND_MESSAGES_IN_THREADS;
+
+const INVITES_DISABLED: &str = "INVITES_DISABLED";
+
+pub struct LockdownSummary {
+    pub channels_locked: usize,
+    pub channels_failed: usize,
+    pub verification_raised: bool,
+    pub invites_paused: bool,
+    pub members_timed_out: usize,
+    pub members_failed: usize,
+}
+
+pub async fn is_active(guild_id: &str, db: &Database) -> bool {
+    db.get_snapshot(guild_id, "lockdown", "state").await.ok().flatten().is_some()
+}
+
+fn guild_features(guild: &Value) -> Vec<String> {
+    guild["features"].as_array()
+        .map(|arr| arr.iter().filter_map(|v| v.as_str().map(String::from)).collect())
+        .unwrap_or_default()
+}
+
+fn notice_channel(guild: &Value, requested: Option<&str>) -> Option<String> {
+    requested.map(String::from).or_else(|| guild["system_channel_id"].as_str().map(String::from))
+}
+
+pub async fn engage(guild_id: &str, reason: &str, notice: Option<&str>, rest: &RestClient, db: &Database) -> anyhow::Result<LockdownSummary> {
+    if is_active(guild_id, db).await {
+        anyhow::bail!("Lockdown is already active");
+    }
+
+    let guild = rest.get_guild(guild_id).await?;
+    let old_level = guild["verification_level"].as_u64().unwrap_or(0);
+    let mut features = guild_features(&guild);
+    let invites_were_disabled = features.iter().any(|f| f == INVITES_DISABLED);
+
+    db.save_snapshot(guild_id, "lockdown", "state", &json!({
+        "id": "state",
+        "reason": reason,
+        "started_at": chrono::Utc::now().to_rfc3339(),
+        "verification_level": old_level,
+        "invites_disabled": invites_were_disabled,
+    })).await?;
+
+    let audit_reason = format!("{}: {}", LOCKDOWN_REASON, reason);
+    let mut summary = LockdownSummary {
+        channels_locked: 0,
+        channels_failed: 0,
+        verification_raised: false,
+        invites_paused: false,
+        members_timed_out: 0,
+        members_failed: 0,
+    };
+
+    if old_level < LOCKDOWN_VERIFICATION_LEVEL {
+        match rest.modify_guild(guild_id, json!({ "verification_level": LOCKDOWN_VERIFICATION_LEVEL }), &audit_reason).await {
+            Ok(_) => summary.verification_raised = true,
+            Err(e) => error!("Lockdown failed to raise verification level in guild {}: {:?}", guild_id, e),
+        }
+    }
+
+    if !invites_were_disabled {
+        features.push(INVITES_DISABLED.to_string());
+        match rest.modify_guild(guild_id, json!({ "features": features }), &audit_reason).await {
+            Ok(_) => summary.invites_paused = true,
+            Err(e) => error!("Lockdown failed to pause invites in guild {}: {:?}", guild_id, e),
+        }
+    }
+
+    let channels = rest.get_guild_channels(guild_id).await?.as_array().cloned().unwrap_or_default();
+    for channel in &channels {
+        let channel_id = channel["id"].as_str().unwrap_or("");
+        let kind = channel["type"].as_u64().unwrap_or(u64::MAX);
+        if channel_id.is_empty() || !(kind == 0 || kind == 5) { continue; }
+
+        let overwrite = channel["permission_overwrites"].as_array()
+            .and_then(|arr| arr.iter().find(|o| o["id"].as_str() == Some(guild_id)));
+        let allow: u64 = overwrite.and_then(|o| o["allow"].as_str()).and_then(|s| s.parse().ok()).unwrap_or(0);
+        let deny: u64 = overwrite.and_then(|o| o["deny"].as_str()).and_then(|s| s.parse().ok()).unwrap_or(0);
+
+        if deny & LOCKED_PERMS == LOCKED_PERMS && allow & LOCKED_PERMS == 0 { continue; }
+
+        db.save_snapshot(guild_id, "lockdown_channel", channel_id, &json!({
+            "id": channel_id,
+            "existed": overwrite.is_some(),
+            "allow": allow.to_string(),
+            "deny": deny.to_string(),
+        })).await?;
+
+        let new_allow = (allow & !LOCKED_PERMS).to_string();
+        let new_deny = (deny | LOCKED_PERMS).to_string();
+        match rest.modify_channel_permissions(channel_id, guild_id, &new_allow, &new_deny, 0).await {
+            Ok(_) => summary.channels_locked += 1,
+            Err(e) => {
+                error!("Lockdown failed to lock channel {}: {:?}", channel_id, e);
+                let _ = db.delete_snapshot(guild_id, "lockdown_channel", channel_id).await;
+                summary.channels_failed += 1;
+            }
+        }
+
+        tokio::time::sleep(std::time::Duration::from_millis(250)).await;
+    }
+
+    let timeout_until = (chrono::Utc::now() + chrono::Duration::days(7)).to_rfc3339();
+    for user_id in super::raid::recent_joins(guild_id, db).await {
+        let user_id = user_id.as_str();
+        if is_trusted(guild_id, user_id, "anti_raid", rest, db).await { continue; }
+        let member = match rest.get_guild_member(guild_id, user_id).await {
+            Ok(m) => m,
+            Err(_) => continue,
+        };
+
+        db.save_snapshot(guild_id, "lockdown_member", user_id, &json!({
+            "id": user_id,
+            "communication_disabled_until": member["communication_disabled_until"],
+        })).await?;
+
+        match rest.timeout_member(guild_id, user_id, Some(&timeout_until), &audit_reason).await {
+            Ok(_) => summary.members_timed_out += 1,
+            Err(e) => {
+                error!("Lockdown failed to timeout recent joiner {}: {:?}", user_id, e);
+                let _ = db.delete_snapshot(guild_id, "lockdown_member", user_id).await;
+                summary.members_failed += 1;
+            }
+        }
+    }
+
+    warn!(
+        "LOCKDOWN ENGAGED: Server {} | Reason: {} | Channels: {} | Recent joiners timed out: {}",
+        guild_id, reason, summary.channels_locked, summary.members_timed_out
+    );
+
+    if let Some(channel_id) = notice_channel(&guild, notice) {
+        let embed = json!({
+            "title": format!("{} SERVER LOCKDOWN", emojis::LOCK),
+            "description": format!(
+                "This server has been placed under lockdown.\n\n\
+                **Reason:** {}\n\
+                **Channels Locked:** {} (Failed: {})\n\
+                **Verification Raised:** {}\n\
+                **Invites Paused:** {}\n\
+                **Recent Joiners Timed Out:** {} (Failed: {})",
+                reason,
+                summary.channels_locked, summary.channels_failed,
+                if summary.verification_raised { "Yes" } else { "No" },
+                if summary.invites_paused { "Yes" } else { "No" },
+                summary.members_timed_out, summary.members_failed
+            ),
+            "color": colors::RED,
+            "footer": { "text": "Use !lockdown off to restore the previous state" }
+        });
+        if let Err(e) = rest.send_embed(&channel_id, embed).await {
+            error!("Failed to post lockdown notice in {}: {:?}", channel_id, e);
+        }
+    }
+
+    Ok(summary)
+}
+
+pub async fn lift(guild_id: &str, notice: Option<&str>, rest: &RestClient, db: &Database) -> anyhow::Result<(usize, usize)> {
+    let state = match db.get_snapshot(guild_id, "lockdown", "state").await? {
+        Some(s) => s,
+        None => anyhow::bail!("Lockdown is not active"),
+    };
+
+    let guild = rest.get_guild(guild_id).await?;
+    let mut restored = 0;
+    let mut failed = 0;
+
+    let old_level = state["verification_level"].as_u64().unwrap_or(0);
+    if guild["verification_level"].as_u64() != Some(old_level) {
+        match rest.modify_guild(guild_id, json!({ "verification_level": old_level }), LOCKDOWN_REASON).await {
+            Ok(_) => restored += 1,
+            Err(e) => {
+                error!("Failed to restore verification level in guild {}: {:?}", guild_id, e);
+                failed += 1;
+            }
+        }
+    }
+
+    let features = guild_features(&guild);
+    if !state["invites_disabled"].as_bool().unwrap_or(false) && features.iter().any(|f| f == INVITES_DISABLED) {
+        let features: Vec<String> = features.into_iter().filter(|f| f != INVITES_DISABLED).collect();
+        match rest.modify_guild(guild_id, json!({ "features": features }), LOCKDOWN_REASON).await {
+            Ok(_) => restored += 1,
+            Err(e) => {
+                error!("Failed to resume invites in guild {}: {:?}", guild_id, e);
+                failed += 1;
+            }
+        }
+    }
+
+    for snapshot in db.list_snapshots(guild_id, "lockdown_channel").await? {
+        let channel_id = snapshot["id"].as_str().unwrap_or("");
+        let result = if snapshot["existed"].as_bool().unwrap_or(false) {
+            rest.modify_channel_permissions(
+                channel_id,
+                guild_id,
+                snapshot["allow"].as_str().unwrap_or("0"),
+                snapshot["deny"].as_str().unwrap_or("0"),
+                0,
+            ).await
+        } else {
+            rest.delete_channel_permission(channel_id, guild_id, LOCKDOWN_REASON).await
+        };
+
+        match result {
+            Ok(_) => {
+                db.delete_snapshot(guild_id, "lockdown_channel", channel_id).await?;
+                restored += 1;
+            }
+            Err(e) => {
+                error!("Failed to unlock channel {}: {:?}", channel_id, e);
+                failed += 1;
+            }
+        }
+
+        tokio::time::sleep(std::time::Duration::from_millis(250)).await;
+    }
+
+    let now = chrono::Utc::now();
+    for snapshot in db.list_snapshots(guild_id, "lockdown_member").await? {
+        let user_id = snapshot["id"].as_str().unwrap_or("");
+        let previous = snapshot["communication_disabled_until"].as_str()
+            .filter(|s| chrono::DateTime::parse_from_rfc3339(s).map(|t| t > now).unwrap_or(false));
+
+        if let Err(e) = rest.timeout_member(guild_id, user_id, previous, LOCKDOWN_REASON).await {
+            error!("Failed to lift lockdown timeout for {}: {:?}", user_id, e);
+        } else {
+            restored += 1;
+        }
+        db.delete_snapshot(guild_id, "lockdown_member", user_id).await?;
+    }
+
+    if failed == 0 {
+        db.delete_snapshot(guild_id, "lockdown", "state").await?;
+    }
+
+    info!("LOCKDOWN LIFTED: Server {} | Restored: {} | Failed: {}", guild_id, restored, failed);
+
+    if let Some(channel_id) = notice_channel(&guild, notice) {
+        let mut description = format!("The lockdown has been lifted and **{}** change(s) were restored.", restored);
+        if failed > 0 {
+            description.push_str(&format!("\n{} **{}** change(s) could not be restored. Run `!lockdown off` again to retry.", emojis::WARNING, failed));
+        }
+        let _ = rest.send_embed(&channel_id, json!({
+            "title": format!("{} Lockdown Lifted", emojis::UNLOCK),
+            "description": description,
+            "color": colors::GREEN
+        })).await;
+    }
+
+    Ok((restored, failed))
+}
+
+pub async fn auto_engage(guild_id: &str, reason: &str, rest: &RestClient, db: &Database) {
+    let enabled = db.get_antinuke_settings(guild_id).await
+        .map(|s| *s.get("auto_lockdown").unwrap_or(&false))
+        .unwrap_or(false);
+    if !enabled || is_active(guild_id, db).await { return; }
+
+    warn!("Antinuke: Severe incident in {} — engaging automatic lockdown.", guild_id);
+    if let Err(e) = engage(guild_id, reason, None, rest, db).await {
+        error!("Automatic lockdown failed for guild {}: {:?}", guild_id, e);
+    }
+}
@@ -3,60 +3,449 @@ pub mod channels;
 pub mod roles;
 pub mod server;
 pub mod assets;
+pub mod bots;
+pub mod automod;
+pub mod scheduled_events;
+pub mod nightmode;
+pub mod lockdown;
+pub mod raid;
+pub mod rollback;
+pub mod backup;
+pub mod audit;
+pub mod quarantine;
+pub mod tracker;
+pub mod template;
 
 use crate::rest::RestClient;
-use crate::db::Database;
+use crate::db::{Database, IncidentRecord};
+use crate::constants::{emojis, colors};
 use std::sync::Arc;
-use serde_json::Value;
-use tracing::{warn, error};
+use serde_json::{json, Value};
+use tracing::{info, warn, error};
 
-pub async fn punish_offender(guild_id: &str, action_type: u8, rest: Arc<RestClient>, db: Arc<Database>) {
+const DISCORD_EPOCH_MS: i64 = 1420070400000;
 
-    tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+const AUDIT_ENTRY_MAX_AGE_MS: i64 = 15_000;
+
+pub const SEVERE_BURST: usize = 3;
 
-    if let Ok(logs) = rest.get_audit_logs(guild_id, action_type, 1).await {
-        if let Some(entry) = logs.as_array().and_then(|arr| arr.first()) {
-            let executor_id = entry.get("user_id").and_then(|v| v.as_str()).unwrap_or("");
-            if executor_id.is_empty() { return; }
+pub struct Incident {
+    pub guild_id: String,
+    pub module: String,
+    pub executor_id: String,
+    pub targets: Vec<String>,
+    pub action: String,
+    pub punishment: String,
+    pub recovery: Vec<String>,
+    pub audit_entry_id: Option<String>,
+    pub detected_at: chrono::DateTime<chrono::Utc>,
+    pub monitor: bool,
+}
 
-            if db.is_whitelisted(executor_id).await.unwrap_or(false) || db.is_admin(executor_id).await.unwrap_or(false) {
-                return;
+impl Incident {
+    pub fn new(guild_id: &str, module: &str, executor_id: &str, action: impl Into<String>) -> Self {
+        Self {
+            guild_id: guild_id.to_string(),
+            module: module.to_string(),
+            executor_id: executor_id.to_string(),
+            targets: Vec::new(),
+            action: action.into(),
+            punishment: "None".to_string(),
+            recovery: Vec::new(),
+            audit_entry_id: None,
+            detected_at: chrono::Utc::now(),
+            monitor: false,
+        }
+    }
+
+    pub fn with_audit_entry(mut self, entry: &Value) -> Self {
+        self.audit_entry_id = entry.get("id").and_then(|v| v.as_str()).map(String::from);
+        self
+    }
+
+    pub async fn monitored(mut self, db: &Database) -> Self {
+        self.monitor = db.is_monitor_mode(&self.guild_id, &self.module).await;
+        self
+    }
+
+    pub fn would(&mut self, action: impl Into<String>) {
+        self.recovery.push(format!("Would {}", action.into()));
+    }
+
+    pub fn succeeded(&self) -> bool {
+        !self.punishment.contains("failed:") && !self.recovery.iter().any(|r| r.starts_with("Failed"))
+    }
+
+    fn to_record(&self) -> IncidentRecord {
+        IncidentRecord {
+            id: 0,
+            guild_id: self.guild_id.clone(),
+            module: self.module.clone(),
+            executor_id: self.executor_id.clone(),
+            targets: self.targets.clone(),
+            action: self.action.clone(),
+            punishment: self.punishment.clone(),
+            recovery: self.recovery.clone(),
+            audit_entry_id: self.audit_entry_id.clone(),
+            succeeded: self.succeeded(),
+            detected_at: self.detected_at.to_rfc3339(),
+            resolved_at: chrono::Utc::now().to_rfc3339(),
+        }
+    }
+
+    fn embed(&self, incident_id: Option<i64>) -> Value {
+        let executor = if self.executor_id.is_empty() {
+            "Unknown".to_string()
+        } else {
+            format!("<@{}> (`{}`)", self.executor_id, self.executor_id)
+        };
+        let targets = if self.targets.is_empty() { "None".to_string() } else { self.targets.join(", ") };
+        let (targets_field, description) = if targets.chars().count() > 1024 {
+            ("See description".to_string(), format!("**Targets ({}):** {}", self.targets.len(), truncate(&targets, 3800)))
+        } else {
+            (targets, String::new())
+        };
+        let recovery = if self.recovery.is_empty() { "None".to_string() } else { self.recovery.join("\n") };
+        let audit_log = match &self.audit_entry_id {
+            Some(id) => format!(
+                "[Open server](https://discord.com/channels/{}) → Server Settings → Audit Log, entry `{}`",
+                self.guild_id, id
+            ),
+            None => "No matching entry".to_string(),
+        };
+
+        let module = self.module.replace("anti_", "").replace('_', " ").to_uppercase();
+        let mut embed = json!({
+            "title": if self.monitor {
+                format!("{} ANTINUKE MONITOR: {}", emojis::EYE, module)
+            } else {
+                format!("{} ANTINUKE INCIDENT: {}", emojis::WARNING, module)
+            },
+            "color": if self.monitor { colors::YELLOW } else { colors::RED },
+            "fields": [
+                { "name": "Action", "value": truncate(&self.action, 1024), "inline": false },
+                { "name": "Executor", "value": executor, "inline": true },
+                { "name": "Punishment", "value": truncate(&self.punishment, 1024), "inline": true },
+                { "name": "Targets", "value": targets_field, "inline": false },
+                { "name": "Recovery", "value": truncate(&recovery, 1024), "inline": false },
+                { "name": "Audit Log", "value": audit_log, "inline": false }
+            ],
+            "footer": { "text": match (incident_id, self.monitor) {
+                (Some(id), true) => format!("Monitor mode: no action was taken | Incident #{}", id),
+                (None, true) => "Monitor mode: no action was taken".to_string(),
+                (Some(id), false) => format!("Rimuru Advanced Security | Incident #{}", id),
+                (None, false) => "Rimuru Advanced Security".to_string(),
+            } },
+            "timestamp": chrono::Utc::now().to_rfc3339()
+        });
+        if !description.is_empty() {
+            embed["description"] = json!(description);
+        }
+        embed
+    }
+
+    pub async fn report(&self, rest: &RestClient, db: &Database) -> Option<i64> {
+        warn!(
+            "ANTINUKE {}: Server {} | Module: {} | Executor: {} | Action: {} | Targets: [{}] | Punishment: {} | Recovery: [{}]",
+            if self.monitor { "MONITOR" } else { "INCIDENT" },
+            self.guild_id,
+            self.module,
+            self.executor_id,
+            self.action,
+            self.targets.join(", "),
+            self.punishment,
+            self.recovery.join("; ")
+        );
+
+        let incident_id = match db.record_incident(&self.to_record()).await {
+            Ok(id) => Some(id),
+            Err(e) => {
+                error!("Failed to record antinuke incident for guild {}: {:?}", self.guild_id, e);
+                None
             }
+        };
+
+        deliver_alert(&self.guild_id, self.embed(incident_id), rest, db).await;
+        incident_id
+    }
+}
 
-            if let Ok(bot) = rest.validate_token().await {
-                if bot.id == executor_id { return; }
+fn truncate(text: &str, max: usize) -> String {
+    if text.chars().count() <= max {
+        return text.to_string();
+    }
+    let mut out: String = text.chars().take(max - 1).collect();
+    out.push('…');
+    out
+}
+
+pub async fn deliver_alert(guild_id: &str, embed: Value, rest: &RestClient, db: &Database) {
+    let (log_channel, owner_dm) = db.get_alert_options(guild_id).await;
+    let guild = rest.get_guild(guild_id).await.ok();
+    let owner_id = guild.as_ref().and_then(|g| g["owner_id"].as_str()).map(String::from);
+
+    let mut delivered = false;
+    if let Some(channel_id) = &log_channel {
+        match rest.send_embed(channel_id, embed.clone()).await {
+            Ok(_) => delivered = true,
+            Err(e) => warn!("Security log channel {} unavailable for guild {}: {:?}", channel_id, guild_id, e),
+        }
+    }
+
+    if !delivered && log_channel.is_some() {
+        let fallback = guild.as_ref()
+            .and_then(|g| g["system_channel_id"].as_str())
+            .filter(|id| Some(*id) != log_channel.as_deref());
+        if let Some(channel_id) = fallback {
+            let mut notice = embed.clone();
+            let unavailable = format!(
+                "{} The configured security log channel is unavailable. Set a new one with `!antinuke log #channel`.",
+                emojis::WARNING
+            );
+            notice["description"] = match notice["description"].as_str() {
+                Some(details) => json!(format!("{}\n\n{}", unavailable, details)),
+                None => json!(unavailable),
+            };
+            delivered = rest.send_embed(channel_id, notice).await.is_ok();
+        }
+    }
+
+    let dm_owner = owner_dm || (log_channel.is_some() && !delivered);
+    if dm_owner {
+        if let Some(owner_id) = owner_id {
+            if let Err(e) = rest.send_dm_embed(&owner_id, embed).await {
+                error!("Failed to DM security alert to owner {} of guild {}: {:?}", owner_id, guild_id, e);
             }
+        }
+    }
+}
+
+pub fn snowflake_timestamp_ms(id: &str) -> Option<i64> {
+    id.parse::<u64>().ok().map(|v| (v >> 22) as i64 + DISCORD_EPOCH_MS)
+}
+
+pub fn role_permissions(role: &Value) -> u64 {
+    role.get("permissions")
+        .and_then(|v| v.as_str())
+        .and_then(|s| s.parse::<u64>().ok())
+        .unwrap_or(0)
+}
+
+fn role_ids(member: &Value) -> Vec<String> {
+    member["roles"].as_array()
+        .map(|arr| arr.iter().filter_map(|v| v.as_str().map(String::from)).collect())
+        .unwrap_or_default()
+}
 
-            warn!("PUNISHING: Banning user {} for unauthorized action.", executor_id);
-            if let Err(e) = rest.ban_user(guild_id, executor_id, "Rimuru Antinuke: Unauthorized Action").await {
-                error!("Failed to punish offender {}: {:?}", executor_id, e);
+async fn update_member_cache(event_type: &str, data: &Value, db: &Database) {
+    match event_type {
+        "GUILD_CREATE" => {
+            let guild_id = data["id"].as_str().unwrap_or("");
+            for member in data["members"].as_array().into_iter().flatten() {
+                if let Some(user_id) = member["user"]["id"].as_str() {
+                    db.cache_member_roles(guild_id, user_id, role_ids(member)).await;
+                }
+            }
+        }
+        "GUILD_MEMBER_ADD" | "GUILD_MEMBER_UPDATE" => {
+            if let (Some(guild_id), Some(user_id)) = (data["guild_id"].as_str(), data["user"]["id"].as_str()) {
+                db.cache_member_roles(guild_id, user_id, role_ids(data)).await;
+            }
+        }
+        "GUILD_MEMBER_REMOVE" => {
+            if let (Some(guild_id), Some(user_id)) = (data["guild_id"].as_str(), data["user"]["id"].as_str()) {
+                db.evict_member(guild_id, user_id).await;
             }
         }
+        "GUILD_ROLE_DELETE" => {
+            if let (Some(guild_id), Some(role_id)) = (data["guild_id"].as_str(), data["role_id"].as_str()) {
+                if db.get_whitelist_entry(guild_id, role_id).await.is_some() {
+                    let _ = db.remove_whitelist(guild_id, role_id).await;
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+pub async fn bot_top_position(guild_id: &str, roles: &[Value], rest: &RestClient) -> Option<(String, u64)> {
+    let bot = rest.validate_token().await.ok()?;
+    let member = rest.get_guild_member(guild_id, &bot.id).await.ok()?;
+    let bot_role_ids: Vec<&str> = member["roles"]
+        .as_array()
+        .map(|arr| arr.iter().filter_map(|v| v.as_str()).collect())
+        .unwrap_or_default();
+
+    let top = roles.iter()
+        .filter(|r| r["id"].as_str().map(|id| bot_role_ids.contains(&id)).unwrap_or(false))
+        .filter_map(|r| r["position"].as_u64())
+        .max()
+        .unwrap_or(0);
+
+    Some((bot.id, top))
+}
+
+pub async fn find_audit_entry(guild_id: &str, action_type: u8, target_id: Option<&str>, rest: &RestClient) -> Option<Value> {
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+
+    let logs = match rest.get_audit_logs(guild_id, action_type, 5).await {
+        Ok(l) => l,
+        Err(e) => {
+            error!("Failed to fetch audit logs for guild {}: {:?}", guild_id, e);
+            return None;
+        }
+    };
+
+    let now_ms = chrono::Utc::now().timestamp_millis();
+    logs.as_array()?.iter().find(|entry| {
+        let fresh = entry.get("id")
+            .and_then(|v| v.as_str())
+            .and_then(snowflake_timestamp_ms)
+            .map(|ts| now_ms - ts <= AUDIT_ENTRY_MAX_AGE_MS)
+            .unwrap_or(false);
+        let matches = match target_id {
+            Some(t) => entry.get("target_id").and_then(|v| v.as_str()) == Some(t),
+            None => true,
+        };
+        fresh && matches
+    }).cloned()
+}
+
+pub async fn is_trusted(guild_id: &str, user_id: &str, module: &str, rest: &RestClient, db: &Database) -> bool {
+    if user_id.is_empty() { return false; }
+
+    if db.is_whitelisted(guild_id, user_id, module).await.unwrap_or(false) || db.is_admin(guild_id, user_id).await.unwrap_or(false) {
+        return true;
+    }
+
+    let trusted_roles = db.whitelisted_roles(guild_id, module).await;
+    if !trusted_roles.is_empty() {
+        let member_roles = match db.cached_member_roles(guild_id, user_id).await {
+            Some(roles) => roles,
+            None => match rest.get_guild_member(guild_id, user_id).await {
+                Ok(member) => {
+                    let roles = role_ids(&member);
+                    db.cache_member_roles(guild_id, user_id, roles.clone()).await;
+                    roles
+                }
+                Err(_) => Vec::new(),
+            },
+        };
+        if member_roles.iter().any(|r| trusted_roles.contains(r)) { return true; }
+    }
+
+    if let Ok(bot) = rest.validate_token().await {
+        if bot.id == user_id { return true; }
+    }
+
+    if let Ok(guild) = rest.get_guild(guild_id).await {
+        if guild["owner_id"].as_str() == Some(user_id) { return true; }
+    }
+
+    false
+}
+
+pub async fn punish(guild_id: &str, user_id: &str, reason: &str, monitor: bool, rest: &RestClient, db: &Database) -> String {
+    let punishment = db.get_punishment(guild_id).await;
+    if monitor {
+        info!("MONITOR: Would apply '{}' to user {} in server {}.", punishment, user_id, guild_id);
+        return format!("Would {} (monitor mode)", punishment);
+    }
+    warn!("PUNISHING: Applying '{}' to user {} for unauthorized action.", punishment, user_id);
+
+    let result = match punishment.as_str() {
+        "kick" => rest.kick_user(guild_id, user_id, reason).await.map(|_| "Kicked".to_string()),
+        "timeout" => {
+            let until = (chrono::Utc::now() + chrono::Duration::days(7)).to_rfc3339();
+            rest.timeout_member(guild_id, user_id, Some(&until), reason).await.map(|_| "Timed out for 7 days".to_string())
+        }
+        "strip" => rest.modify_member_roles(guild_id, user_id, &[], reason).await.map(|_| "Stripped all roles".to_string()),
+        "quarantine" => quarantine::apply(guild_id, user_id, reason, "", rest, db).await.map(|n| format!("Quarantined ({} roles saved)", n)),
+        _ => rest.ban_user(guild_id, user_id, reason).await.map(|_| "Banned".to_string()),
+    };
+
+    match result {
+        Ok(outcome) => outcome,
+        Err(e) => {
+            error!("Failed to punish offender {}: {:?}", user_id, e);
+            format!("Punishment '{}' failed: {}", punishment, e)
+        }
+    }
+}
+
+pub async fn punish_offender(guild_id: &str, action_type: u8, module: &str, rest: Arc<RestClient>, db: Arc<Database>) -> bool {
+    match find_audit_entry(guild_id, action_type, None, &rest).await {
+        Some(entry) => punish_entry(guild_id, action_type, module, &entry, &rest, &db).await,
+        None => false,
     }
 }
 
+pub async fn punish_entry(guild_id: &str, action_type: u8, module: &str, entry: &Value, rest: &RestClient, db: &Database) -> bool {
+    let executor_id = entry.get("user_id").and_then(|v| v.as_str()).unwrap_or("");
+    if executor_id.is_empty() { return false; }
+
+    if is_trusted(guild_id, executor_id, module, rest, db).await {
+        return false;
+    }
+
+    let mut incident = Incident::new(guild_id, module, executor_id, format!("Unauthorized {}", module.replace("anti_", "").replace('_', " ")))
+        .with_audit_entry(entry)
+        .monitored(db).await;
+    if let Some(target_id) = entry.get("target_id").and_then(|v| v.as_str()) {
+        incident.targets.push(target_id.to_string());
+    }
+    incident.punishment = punish(guild_id, executor_id, "Rimuru Antinuke: Unauthorized Action", incident.monitor, rest, db).await;
+    let prompt = action_type == crate::models::audit_action::MEMBER_BAN_ADD
+        && rollback::collect(&mut incident, rest, db).await;
+    let incident_id = incident.report(rest, db).await;
+    if prompt {
+        rollback::prompt(guild_id, executor_id, incident_id, rest, db).await;
+    }
+    !incident.monitor
+}
+
 pub async fn handle_event(
     event_type: &str,
     data: Value,
     rest: Arc<RestClient>,
     db: Arc<Database>,
 ) {
+    update_member_cache(event_type, &data, &db).await;
+    quarantine::handle_event(event_type, &data, &rest, &db).await;
+
     match event_type {
         "GUILD_BAN_ADD" | "GUILD_BAN_REMOVE" | "GUILD_MEMBER_REMOVE" => {
             moderation::handle_event(event_type, data, rest, db).await;
         }
+        "GUILD_MEMBER_ADD" => {
+            raid::handle_event(event_type, data.clone(), rest.clone(), db.clone()).await;
+            bots::handle_event(event_type, data, rest, db).await;
+        }
         "CHANNEL_CREATE" | "CHANNEL_UPDATE" | "CHANNEL_DELETE" | "THREAD_CREATE" => {
             channels::handle_event(event_type, data, rest, db).await;
         }
         "GUILD_ROLE_CREATE" | "GUILD_ROLE_UPDATE" | "GUILD_ROLE_DELETE" | "GUILD_MEMBER_UPDATE" => {
             roles::handle_event(event_type, data, rest, db).await;
         }
+        "GUILD_CREATE" => {
+            server::handle_event(event_type, data.clone(), rest.clone(), db.clone()).await;
+            assets::handle_event(event_type, data.clone(), rest.clone(), db.clone()).await;
+            channels::handle_event(event_type, data.clone(), rest.clone(), db.clone()).await;
+            automod::handle_event(event_type, data, rest, db).await;
+        }
         "GUILD_UPDATE" | "MESSAGE_CREATE" => {
             server::handle_event(event_type, data, rest, db).await;
         }
         "GUILD_EMOJIS_UPDATE" | "GUILD_STICKERS_UPDATE" | "WEBHOOKS_UPDATE" => {
             assets::handle_event(event_type, data, rest, db).await;
         }
+        "AUTO_MODERATION_RULE_CREATE" | "AUTO_MODERATION_RULE_UPDATE" | "AUTO_MODERATION_RULE_DELETE" => {
+            automod::handle_event(event_type, data, rest, db).await;
+        }
+        "GUILD_SCHEDULED_EVENT_CREATE" | "GUILD_SCHEDULED_EVENT_UPDATE" | "GUILD_SCHEDULED_EVENT_DELETE" => {
+            scheduled_events::handle_event(event_type, data, rest, db).await;
+        }
         _ => {}
     }
 }
@@ -1,8 +1,10 @@
 use crate::rest::RestClient;
 use crate::db::Database;
-use crate::constants::emojis;
+use crate::constants::permissions;
+use crate::models::audit_action;
+use crate::antinuke::Incident;
 use std::sync::Arc;
-use serde_json::{json, Value};
+use serde_json::Value;
 use tracing::{info, warn, error};
 
 pub async fn handle_event(
@@ -28,10 +30,15 @@ pub async fn handle_event(
         }
         "GUILD_BAN_REMOVE" => {
             if *settings.get("anti_unban").unwrap_or(&false) {
-                send_alert("UNBAN", guild_id, &rest).await;
+                let user_id = data["user"]["id"].as_str().unwrap_or("");
+                detect_unban(guild_id, user_id, &rest, &db).await;
             }
         }
         "GUILD_MEMBER_REMOVE" => {
+            if *settings.get("anti_prune").unwrap_or(&false) {
+                let strip_kick = *settings.get("auto_recovery").unwrap_or(&false);
+                detect_prune(guild_id, &rest, &db, strip_kick).await;
+            }
             if *settings.get("anti_kick").unwrap_or(&false) {
                 detect_mass_action("kick", guild_id, rest.clone(), db).await;
             }
@@ -41,38 +48,142 @@ pub async fn handle_event(
 }
 
 async fn detect_mass_action(action: &str, guild_id: &str, rest: Arc<RestClient>, db: Arc<Database>) {
+    let (action_type, module) = if action == "ban" {
+        (audit_action::MEMBER_BAN_ADD, "anti_ban")
+    } else {
+        (audit_action::MEMBER_KICK, "anti_kick")
+    };
+
+    let entry = match crate::antinuke::find_audit_entry(guild_id, action_type, None, &rest).await {
+        Some(e) => e,
+        None => return,
+    };
+    let executor_id = entry.get("user_id").and_then(|v| v.as_str()).unwrap_or("");
+    if executor_id.is_empty() { return; }
+
+    let (max_actions, window_secs) = db.get_threshold(guild_id, module).await;
+    let burst = db.track_action(guild_id, executor_id, module, window_secs).await;
+    if burst < max_actions as usize {
+        info!("{} by {} in server {} is below threshold ({}/{} within {}s).", action.to_uppercase(), executor_id, guild_id, burst, max_actions, window_secs);
+        return;
+    }
+
+    warn!("CRITICAL: {} detected in server {} ({} by {} within {}s).", action.to_uppercase(), guild_id, burst, executor_id, window_secs);
+
+    let punished = crate::antinuke::punish_entry(guild_id, action_type, module, &entry, &rest, &db).await;
+    if punished && burst >= crate::antinuke::SEVERE_BURST {
+        crate::antinuke::lockdown::auto_engage(guild_id, &format!("Mass {} detected", action), &rest, &db).await;
+    }
+}
+
+async fn detect_unban(guild_id: &str, user_id: &str, rest: &RestClient, db: &Database) {
+    let entry = match crate::antinuke::find_audit_entry(guild_id, audit_action::MEMBER_BAN_REMOVE, Some(user_id), rest).await {
+        Some(e) => e,
+        None => return,
+    };
+    let executor_id = entry.get("user_id").and_then(|v| v.as_str()).unwrap_or("");
+    if executor_id.is_empty() || crate::antinuke::is_trusted(guild_id, executor_id, "anti_unban", rest, db).await {
+        return;
+    }
+
+    warn!("ANTINUKE: Unauthorized unban of {} by {} in server {}.", user_id, executor_id, guild_id);
 
-    let tracker = if action == "ban" { &db.ban_tracker } else { &db.channel_tracker }; 
+    let mut incident = Incident::new(guild_id, "anti_unban", executor_id, format!("Unbanned <@{}>", user_id))
+        .with_audit_entry(&entry)
+        .monitored(db).await;
+    incident.targets.push(user_id.to_string());
+    incident.report(rest, db).await;
+}
+
+async fn detect_prune(guild_id: &str, rest: &RestClient, db: &Database, strip_kick: bool) {
     let now = tokio::time::Instant::now();
-    let mut map = tracker.write().await;
-    let entries = map.entry(guild_id.to_string()).or_insert(std::collections::VecDeque::new());
-
-    while let Some(&t) = entries.front() {
-        if now.duration_since(t) >= tokio::time::Duration::from_secs(10) {
-            entries.pop_front();
-        } else {
-            break;
+    {
+        let mut map = db.prune_tracker.write().await;
+        match map.get_mut(guild_id) {
+            Some(tracked) => {
+                if now.duration_since(tracked.0) < tokio::time::Duration::from_secs(2) {
+                    return;
+                }
+                tracked.0 = now;
+            }
+            None => {
+                map.insert(guild_id.to_string(), (now, String::new()));
+            }
         }
     }
-    entries.push_back(now);
 
-    if entries.len() >= 1 {
-        warn!("CRITICAL: {} detected in server {}.", action.to_uppercase(), guild_id);
-        send_alert(&action.to_uppercase(), guild_id, &rest).await;
+    let entry = match crate::antinuke::find_audit_entry(guild_id, audit_action::MEMBER_PRUNE, None, rest).await {
+        Some(e) => e,
+        None => return,
+    };
+
+    let entry_id = entry.get("id").and_then(|v| v.as_str()).unwrap_or("");
+    if entry_id.is_empty() { return; }
 
-        let action_type = if action == "ban" { 22 } else { 20 }; 
-        crate::antinuke::punish_offender(guild_id, action_type, rest, db.clone()).await;
+    {
+        let mut map = db.prune_tracker.write().await;
+        if let Some(tracked) = map.get_mut(guild_id) {
+            if tracked.1 == entry_id { return; }
+            tracked.1 = entry_id.to_string();
+        }
     }
-}
 
-async fn send_alert(action: &str, guild_id: &str, _rest: &Arc<RestClient>) {
-    let _alert = json!({
-        "title": format!("{} SECURITY ALERT: MASS {} DETECTED", emojis::WARNING, action),
-        "description": format!("The Antinuke module has detected rapid-fire **{}** actions in this server. Investigation recommended.", action),
-        "color": 0xff0000,
-        "footer": { "text": "Rimuru Advanced Security | Cog Protection Active" }
-    });
+    let executor_id = entry.get("user_id").and_then(|v| v.as_str()).unwrap_or("");
+    if executor_id.is_empty() || crate::antinuke::is_trusted(guild_id, executor_id, "anti_prune", rest, db).await {
+        return;
+    }
+
+    let options = entry.get("options");
+    let removed = options.and_then(|o| o.get("members_removed")).and_then(|v| v.as_str()).unwrap_or("unknown");
+    let days = options.and_then(|o| o.get("delete_member_days")).and_then(|v| v.as_str()).unwrap_or("unknown");
+
+    warn!("CRITICAL: MEMBER PRUNE of {} members by {} in server {}.", removed, executor_id, guild_id);
+
+    let mut incident = Incident::new(
+        guild_id,
+        "anti_prune",
+        executor_id,
+        format!("Pruned {} members inactive for {} days", removed, days),
+    ).with_audit_entry(&entry).monitored(db).await;
+
+    let guild_roles = rest.get_guild_roles(guild_id).await.unwrap_or_default();
+    let executor_roles: Vec<String> = rest.get_guild_member(guild_id, executor_id).await
+        .ok()
+        .and_then(|m| m["roles"].as_array().cloned())
+        .map(|arr| arr.iter().filter_map(|v| v.as_str().map(String::from)).collect())
+        .unwrap_or_default();
 
-    info!("ANTINUKE ALERT: Server {} | Action: {}", guild_id, action);
+    let prune_perms = permissions::KICK_MEMBERS | permissions::ADMINISTRATOR;
+    let prune_roles: Vec<&serde_json::Value> = guild_roles.iter()
+        .filter(|r| r["id"].as_str().map(|id| executor_roles.iter().any(|e| e == id)).unwrap_or(false))
+        .filter(|r| crate::antinuke::role_permissions(r) & prune_perms != 0)
+        .collect();
 
+    for role in &prune_roles {
+        let role_id = role["id"].as_str().unwrap_or("");
+        incident.targets.push(format!("role:{}", role_id));
+
+        if strip_kick && incident.monitor {
+            incident.would(format!("remove KICK_MEMBERS and ADMINISTRATOR from role {}", role_id));
+        } else if strip_kick {
+            let stripped = crate::antinuke::role_permissions(role) & !prune_perms;
+            match rest.modify_role_permissions(guild_id, role_id, stripped, "Rimuru Antinuke: Kick Members and Administrator removed after unauthorized prune, pending owner review").await {
+                Ok(_) => incident.recovery.push(format!("Removed KICK_MEMBERS and ADMINISTRATOR from role {} pending owner review", role_id)),
+                Err(e) => incident.recovery.push(format!("Failed to remove KICK_MEMBERS and ADMINISTRATOR from role {}: {}", role_id, e)),
+            }
+        } else if crate::antinuke::role_permissions(role) & permissions::ADMINISTRATOR != 0 {
+            incident.recovery.push(format!("Role {} still grants ADMINISTRATOR and can prune again: review it manually", role_id));
+        }
+    }
+
+    incident.punishment = crate::antinuke::punish(guild_id, executor_id, "Rimuru Antinuke: Unauthorized Member Prune", incident.monitor, rest, db).await;
+    incident.report(rest, db).await;
+
+    if incident.monitor {
+        if db.get_antinuke_settings(guild_id).await.map(|s| *s.get("auto_lockdown").unwrap_or(&false)).unwrap_or(false) {
+            info!("MONITOR: Would engage automatic lockdown in server {} after unauthorized prune.", guild_id);
+        }
+        return;
+    }
+    crate::antinuke::lockdown::auto_engage(guild_id, "Unauthorized member prune detected", rest, db).await;
 }
@@ -0,0 +1,155 @@
+use crate::rest::RestClient;
+use crate::db::Database;
+use crate::constants::permissions;
+use super::{bot_top_position, role_permissions};
+use std::sync::Arc;
+use chrono::NaiveTime;
+use tokio::time::{interval, Duration};
+use tracing::{info, error};
+
+const NIGHTMODE_REASON: &str = "Rimuru Nightmode";
+
+pub fn parse_time(input: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(input, "%H:%M").ok()
+}
+
+pub async fn activate(guild_id: &str, scheduled: bool, rest: &RestClient, db: &Database) -> anyhow::Result<usize> {
+    let roles = rest.get_guild_roles(guild_id).await?;
+    let (bot_id, bot_top) = bot_top_position(guild_id, &roles, rest).await
+        .ok_or_else(|| anyhow::anyhow!("Could not resolve the bot's role position"))?;
+
+    let mut stripped = 0;
+    for role in &roles {
+        let role_id = match role["id"].as_str() {
+            Some(id) => id,
+            None => continue,
+        };
+        let position = role["position"].as_u64().unwrap_or(0);
+        if position >= bot_top { continue; }
+        if role["tags"]["bot_id"].as_str() == Some(bot_id.as_str()) { continue; }
+
+        let perms = role_permissions(role);
+        if perms & permissions::NIGHTMODE == 0 { continue; }
+
+        db.save_nightmode_role(guild_id, role_id, perms).await?;
+        match rest.modify_role_permissions(guild_id, role_id, perms & !permissions::NIGHTMODE, NIGHTMODE_REASON).await {
+            Ok(_) => stripped += 1,
+            Err(e) => {
+                error!("Nightmode failed to strip role {} in guild {}: {:?}", role_id, guild_id, e);
+                let _ = db.remove_nightmode_role(guild_id, role_id).await;
+            }
+        }
+    }
+
+    db.set_nightmode_active(guild_id, true, scheduled).await?;
+    info!("Nightmode enabled for guild {} ({} roles stripped)", guild_id, stripped);
+    Ok(stripped)
+}
+
+pub async fn deactivate(guild_id: &str, rest: &RestClient, db: &Database) -> anyhow::Result<(usize, usize)> {
+    let saved = db.get_nightmode_roles(guild_id).await?;
+    let mut restored = 0;
+    let mut failed = 0;
+
+    for (role_id, perms) in saved {
+        match rest.modify_role_permissions(guild_id, &role_id, perms, NIGHTMODE_REASON).await {
+            Ok(_) => {
+                db.remove_nightmode_role(guild_id, &role_id).await?;
+                restored += 1;
+            }
+            Err(e) => {
+                error!("Nightmode failed to restore role {} in guild {}: {:?}", role_id, guild_id, e);
+                failed += 1;
+            }
+        }
+    }
+
+    if failed == 0 {
+        db.set_nightmode_active(guild_id, false, false).await?;
+    }
+    info!("Nightmode disabled for guild {} ({} restored, {} failed)", guild_id, restored, failed);
+    Ok((restored, failed))
+}
+
+fn in_window(now: NaiveTime, start: NaiveTime, end: NaiveTime) -> bool {
+    if start <= end {
+        now >= start && now < end
+    } else {
+        now >= start || now < end
+    }
+}
+
+pub async fn run_scheduler(rest: Arc<RestClient>, db: Arc<Database>) {
+    let mut ticker = interval(Duration::from_secs(30));
+    loop {
+        ticker.tick().await;
+
+        let schedules = match db.list_nightmode_schedules().await {
+            Ok(s) => s,
+            Err(e) => {
+                error!("Failed to load nightmode schedules: {:?}", e);
+                continue;
+            }
+        };
+
+        let now = chrono::Utc::now().time();
+
+        for (guild_id, start, end, active, scheduled, overridden) in schedules {
+            let (start, end) = match (parse_time(&start), parse_time(&end)) {
+                (Some(s), Some(e)) => (s, e),
+                _ => continue,
+            };
+
+            if in_window(now, start, end) {
+                if !active && !overridden {
+                    if let Err(e) = activate(&guild_id, true, &rest, &db).await {
+                        error!("Scheduled nightmode failed for guild {}: {:?}", guild_id, e);
+                    }
+                }
+                continue;
+            }
+
+            if overridden {
+                if let Err(e) = db.set_nightmode_override(&guild_id, false).await {
+                    error!("Failed to clear nightmode override for guild {}: {:?}", guild_id, e);
+                }
+            }
+            if active && scheduled {
+                if let Err(e) = deactivate(&guild_id, &rest, &db).await {
+                    error!("Scheduled nightmode restore failed for guild {}: {:?}", guild_id, e);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn t(input: &str) -> NaiveTime {
+        parse_time(input).unwrap()
+    }
+
+    #[test]
+    fn same_day_window() {
+        let (start, end) = (t("09:00"), t("17:00"));
+        assert!(in_window(t("09:00"), start, end));
+        assert!(in_window(t("12:30"), start, end));
+        assert!(!in_window(t("17:00"), start, end));
+        assert!(!in_window(t("08:59"), start, end));
+        assert!(!in_window(t("23:00"), start, end));
+    }
+
+    #[test]
+    fn window_wrapping_past_midnight() {
+        let (start, end) = (t("22:00"), t("06:00"));
+        assert!(in_window(t("22:00"), start, end));
+        assert!(in_window(t("23:59"), start, end));
+        assert!(in_window(t("00:00"), start, end));
+        assert!(in_window(t("05:59"), start, end));
+        assert!(!in_window(t("06:00"), start, end));
+        assert!(!in_window(t("12:00"), start, end));
+        assert!(!in_window(t("21:59"), start, end));
+    }
+}
@@ -0,0 +1,166 @@
+use crate::rest::RestClient;
+use crate::db::Database;
+use crate::constants::{colors, permissions};
+use serde_json::Value;
+use std::collections::HashSet;
+use tracing::{info, warn, error};
+
+const QUARANTINE_ROLE_NAME: &str = "Quarantined";
+
+const QUARANTINE_REASON: &str = "Rimuru Quarantine";
+
+const OVERWRITE_ROLE: u8 = 0;
+
+fn role_ids(member: &Value) -> Vec<String> {
+    member["roles"].as_array()
+        .map(|arr| arr.iter().filter_map(|v| v.as_str().map(String::from)).collect())
+        .unwrap_or_default()
+}
+
+fn denies_view(channel: &Value, role_id: &str) -> bool {
+    channel["permission_overwrites"].as_array().into_iter().flatten()
+        .filter(|o| o["id"].as_str() == Some(role_id))
+        .filter_map(|o| o["deny"].as_str().and_then(|d| d.parse::<u64>().ok()))
+        .any(|deny| deny & permissions::VIEW_CHANNEL != 0)
+}
+
+async fn deny_view(channel: &Value, role_id: &str, rest: &RestClient) -> anyhow::Result<()> {
+    let channel_id = channel["id"].as_str().unwrap_or("");
+    let (allow, deny) = channel["permission_overwrites"].as_array().into_iter().flatten()
+        .find(|o| o["id"].as_str() == Some(role_id))
+        .map(|o| (
+            o["allow"].as_str().and_then(|a| a.parse::<u64>().ok()).unwrap_or(0),
+            o["deny"].as_str().and_then(|d| d.parse::<u64>().ok()).unwrap_or(0),
+        ))
+        .unwrap_or((0, 0));
+    rest.modify_channel_permissions(
+        channel_id,
+        role_id,
+        &(allow & !permissions::VIEW_CHANNEL).to_string(),
+        &(deny | permissions::VIEW_CHANNEL).to_string(),
+        OVERWRITE_ROLE,
+    ).await
+}
+
+pub async fn sync_channels(guild_id: &str, role_id: &str, rest: &RestClient) -> anyhow::Result<(usize, usize)> {
+    let channels = rest.get_guild_channels(guild_id).await?.as_array().cloned().unwrap_or_default();
+    let mut updated = 0;
+    let mut failed = 0;
+
+    for channel in channels.iter().filter(|c| !denies_view(c, role_id)) {
+        match deny_view(channel, role_id, rest).await {
+            Ok(_) => updated += 1,
+            Err(e) => {
+                error!("Failed to hide channel {} from quarantine role in guild {}: {:?}", channel["id"], guild_id, e);
+                failed += 1;
+            }
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(250)).await;
+    }
+
+    Ok((updated, failed))
+}
+
+pub async fn ensure_role(guild_id: &str, rest: &RestClient, db: &Database) -> anyhow::Result<String> {
+    let roles = rest.get_guild_roles(guild_id).await?;
+    if let Some(role_id) = db.get_quarantine_role(guild_id).await {
+        if roles.iter().any(|r| r["id"].as_str() == Some(role_id.as_str())) {
+            return Ok(role_id);
+        }
+        warn!("Quarantine role {} no longer exists in guild {}, recreating it.", role_id, guild_id);
+    }
+
+    let role = rest.create_role(guild_id, QUARANTINE_ROLE_NAME, colors::GRAY, false, "0").await?;
+    let role_id = role["id"].as_str()
+        .ok_or_else(|| anyhow::anyhow!("Discord did not return the quarantine role ID"))?
+        .to_string();
+    db.set_quarantine_role(guild_id, Some(&role_id)).await?;
+
+    let (updated, failed) = sync_channels(guild_id, &role_id, rest).await?;
+    info!("Quarantine role {} created in guild {} ({} channels hidden, {} failed).", role_id, guild_id, updated, failed);
+    Ok(role_id)
+}
+
+pub async fn apply(guild_id: &str, user_id: &str, reason: &str, moderator_id: &str, rest: &RestClient, db: &Database) -> anyhow::Result<usize> {
+    let role_id = ensure_role(guild_id, rest, db).await?;
+    let member = rest.get_guild_member(guild_id, user_id).await?;
+    let managed: HashSet<String> = rest.get_guild_roles(guild_id).await?
+        .iter()
+        .filter(|r| r["managed"].as_bool().unwrap_or(false))
+        .filter_map(|r| r["id"].as_str().map(String::from))
+        .collect();
+
+    let current = role_ids(&member);
+    let saved: Vec<String> = current.iter().filter(|r| **r != role_id && !managed.contains(*r)).cloned().collect();
+    let mut kept: Vec<String> = current.into_iter().filter(|r| managed.contains(r)).collect();
+    kept.push(role_id);
+
+    rest.modify_member_roles(guild_id, user_id, &kept, &format!("{}: {}", QUARANTINE_REASON, reason)).await?;
+    db.save_quarantine(guild_id, user_id, &saved, reason, moderator_id).await?;
+    db.cache_member_roles(guild_id, user_id, kept).await;
+
+    info!("Quarantined {} in guild {} ({} roles saved).", user_id, guild_id, saved.len());
+    Ok(saved.len())
+}
+
+pub async fn release(guild_id: &str, user_id: &str, rest: &RestClient, db: &Database) -> anyhow::Result<usize> {
+    let (saved, _) = db.get_quarantine(guild_id, user_id).await
+        .ok_or_else(|| anyhow::anyhow!("That member is not quarantined"))?;
+    let role_id = db.get_quarantine_role(guild_id).await.unwrap_or_default();
+
+    let roles = rest.get_guild_roles(guild_id).await?;
+    let existing: HashSet<&str> = roles.iter().filter_map(|r| r["id"].as_str()).collect();
+    let managed: HashSet<&str> = roles.iter()
+        .filter(|r| r["managed"].as_bool().unwrap_or(false))
+        .filter_map(|r| r["id"].as_str())
+        .collect();
+
+    let member = rest.get_guild_member(guild_id, user_id).await?;
+    let mut restored: Vec<String> = saved.into_iter().filter(|r| existing.contains(r.as_str())).collect();
+    let count = restored.len();
+    restored.extend(role_ids(&member).into_iter().filter(|r| managed.contains(r.as_str()) && *r != role_id));
+
+    rest.modify_member_roles(guild_id, user_id, &restored, &format!("{}: Released", QUARANTINE_REASON)).await?;
+    db.remove_quarantine(guild_id, user_id).await?;
+    db.cache_member_roles(guild_id, user_id, restored).await;
+
+    info!("Released {} from quarantine in guild {} ({} roles restored).", user_id, guild_id, count);
+    Ok(count)
+}
+
+pub async fn handle_event(event_type: &str, data: &Value, rest: &RestClient, db: &Database) {
+    let guild_id = data["guild_id"].as_str().unwrap_or("");
+    if guild_id.is_empty() { return; }
+
+    match event_type {
+        "CHANNEL_CREATE" => {
+            let role_id = match db.get_quarantine_role(guild_id).await {
+                Some(r) => r,
+                None => return,
+            };
+            if denies_view(data, &role_id) { return; }
+            if let Err(e) = deny_view(data, &role_id, rest).await {
+                error!("Failed to hide new channel {} from quarantine role in guild {}: {:?}", data["id"], guild_id, e);
+            }
+        }
+        "GUILD_ROLE_DELETE" => {
+            let deleted = data["role_id"].as_str();
+            if deleted.is_none() || db.get_quarantine_role(guild_id).await.as_deref() != deleted { return; }
+            warn!("Quarantine role was deleted in guild {}; it will be recreated on next use.", guild_id);
+            let _ = db.set_quarantine_role(guild_id, None).await;
+        }
+        "GUILD_MEMBER_ADD" => {
+            let user_id = data["user"]["id"].as_str().unwrap_or("");
+            if db.get_quarantine(guild_id, user_id).await.is_none() { return; }
+            let role_id = match db.get_quarantine_role(guild_id).await {
+                Some(r) => r,
+                None => return,
+            };
+            match rest.add_member_role(guild_id, user_id, &role_id).await {
+                Ok(_) => info!("Re-applied quarantine to {} after rejoining guild {}.", user_id, guild_id),
+                Err(e) => error!("Failed to re-apply quarantine to {} in guild {}: {:?}", user_id, guild_id, e),
+            }
+        }
+        _ => {}
+    }
+}
@@ -0,0 +1,270 @@
+use crate::rest::RestClient;
+use crate::db::Database;
+use crate::antinuke::{Incident, snowflake_timestamp_ms};
+use std::collections::VecDeque;
+use std::sync::Arc;
+use serde_json::Value;
+use tokio::time::{Duration, Instant};
+use tracing::{warn, error};
+
+pub const DEFAULT_RAID_THRESHOLD: (u32, u64) = (10, 10);
+
+const SUSPICION_SCORE: u32 = 3;
+
+const RAID_COOLDOWN_SECS: u64 = 120;
+
+const RAID_REASON: &str = "Rimuru Anti-Raid: Join flood detected";
+
+const RECENT_JOIN_WINDOW_SECS: u64 = 30 * 60;
+
+pub struct JoinRecord {
+    at: Instant,
+    user_id: String,
+    name: String,
+    score: u32,
+}
+
+#[derive(Default)]
+pub struct RaidState {
+    joins: VecDeque<JoinRecord>,
+    recent: VecDeque<(Instant, String)>,
+    active_until: Option<Instant>,
+}
+
+fn prune_recent(state: &mut RaidState, now: Instant) {
+    while let Some((at, _)) = state.recent.front() {
+        if now.duration_since(*at) >= Duration::from_secs(RECENT_JOIN_WINDOW_SECS) {
+            state.recent.pop_front();
+        } else {
+            break;
+        }
+    }
+}
+
+async fn record_join(guild_id: &str, user_id: &str, db: &Database) {
+    let now = Instant::now();
+    let mut map = db.raid_tracker.write().await;
+    let state = map.entry(guild_id.to_string()).or_default();
+    prune_recent(state, now);
+    state.recent.push_back((now, user_id.to_string()));
+}
+
+pub async fn recent_joins(guild_id: &str, db: &Database) -> Vec<String> {
+    let mut map = db.raid_tracker.write().await;
+    let state = match map.get_mut(guild_id) {
+        Some(s) => s,
+        None => return Vec::new(),
+    };
+    prune_recent(state, Instant::now());
+    let mut joined: Vec<String> = state.recent.iter().map(|(_, id)| id.clone()).collect();
+    joined.sort();
+    joined.dedup();
+    joined
+}
+
+fn normalize_name(name: &str) -> String {
+    name.to_lowercase().chars().filter(|c| c.is_alphabetic()).collect()
+}
+
+fn similar_names(a: &str, b: &str) -> bool {
+    if a.len() < 3 || b.len() < 3 { return false; }
+    if a == b { return true; }
+    a.len() >= 5 && b.len() >= 5 && a.chars().take(5).eq(b.chars().take(5))
+}
+
+fn score_member(user: &Value, name: &str, recent: &VecDeque<JoinRecord>, burst: bool) -> (u32, Vec<&'static str>) {
+    let mut score = 0;
+    let mut reasons = Vec::new();
+
+    let age_days = user["id"].as_str()
+        .and_then(snowflake_timestamp_ms)
+        .map(|ts| (chrono::Utc::now().timestamp_millis() - ts) / 86_400_000);
+    match age_days {
+        Some(d) if d < 1 => { score += 3; reasons.push("account < 1 day old"); }
+        Some(d) if d < 7 => { score += 2; reasons.push("account < 7 days old"); }
+        Some(d) if d < 30 => { score += 1; reasons.push("account < 30 days old"); }
+        _ => {}
+    }
+
+    if user["avatar"].is_null() {
+        score += 1;
+        reasons.push("default avatar");
+    }
+
+    if recent.iter().any(|j| similar_names(&j.name, name)) {
+        score += 2;
+        reasons.push("similar username");
+    }
+
+    if burst {
+        score += 1;
+        reasons.push("burst join");
+    }
+
+    (score, reasons)
+}
+
+pub async fn handle_event(
+    event_type: &str,
+    data: Value,
+    rest: Arc<RestClient>,
+    db: Arc<Database>,
+) {
+    let guild_id = data.get("guild_id").and_then(|v| v.as_str()).unwrap_or("");
+    if event_type == "GUILD_MEMBER_ADD" && !data["user"]["bot"].as_bool().unwrap_or(false) {
+        if let Some(user_id) = data["user"]["id"].as_str() {
+            record_join(guild_id, user_id, &db).await;
+        }
+    }
+
+    let settings = match db.get_antinuke_settings(guild_id).await {
+        Ok(s) => s,
+        Err(e) => {
+            error!("Failed to fetch antinuke settings for guild {}: {:?}", guild_id, e);
+            return;
+        }
+    };
+
+    if event_type == "GUILD_MEMBER_ADD" && *settings.get("anti_raid").unwrap_or(&false) {
+        handle_member_add(guild_id, &data, &rest, &db).await;
+    }
+}
+
+async fn handle_member_add(guild_id: &str, data: &Value, rest: &RestClient, db: &Database) {
+    let user = &data["user"];
+    let user_id = user["id"].as_str().unwrap_or("");
+    if user_id.is_empty() || user["bot"].as_bool().unwrap_or(false) { return; }
+    if db.is_whitelisted(guild_id, user_id, "anti_raid").await.unwrap_or(false) { return; }
+
+    let (max_joins, window_secs) = db.get_threshold_or(guild_id, "anti_raid", DEFAULT_RAID_THRESHOLD).await;
+    let window = Duration::from_secs(window_secs);
+    let name = normalize_name(user["username"].as_str().unwrap_or(""));
+    let now = Instant::now();
+
+    let (cohort, ongoing, reasons) = {
+        let mut map = db.raid_tracker.write().await;
+        let state = map.entry(guild_id.to_string()).or_default();
+
+        while let Some(front) = state.joins.front() {
+            if now.duration_since(front.at) >= window {
+                state.joins.pop_front();
+            } else {
+                break;
+            }
+        }
+
+        let burst = state.joins.len() + 1 >= (max_joins as usize / 2).max(2);
+        let (score, reasons) = score_member(user, &name, &state.joins, burst);
+        state.joins.push_back(JoinRecord { at: now, user_id: user_id.to_string(), name, score });
+
+        let ongoing = state.active_until.map(|t| now < t).unwrap_or(false);
+        if ongoing {
+            if score < SUSPICION_SCORE { return; }
+            state.active_until = Some(now + Duration::from_secs(RAID_COOLDOWN_SECS));
+            state.joins.pop_back();
+            (vec![user_id.to_string()], true, reasons)
+        } else {
+            let suspicious = state.joins.iter().filter(|j| j.score >= SUSPICION_SCORE).count();
+            if suspicious < max_joins as usize { return; }
+
+            state.active_until = Some(now + Duration::from_secs(RAID_COOLDOWN_SECS));
+            let cohort: Vec<String> = state.joins.iter()
+                .filter(|j| j.score >= SUSPICION_SCORE)
+                .map(|j| j.user_id.clone())
+                .collect();
+            state.joins.retain(|j| j.score < SUSPICION_SCORE);
+            (cohort, false, reasons)
+        }
+    };
+
+    let action = db.get_raid_action(guild_id).await;
+    let monitor = db.is_monitor_mode(guild_id, "anti_raid").await;
+
+    if ongoing {
+        warn!("ANTI-RAID: Suspicious joiner {} during active raid in {} ({}).", user_id, guild_id, reasons.join(", "));
+        let mut incident = Incident::new(
+            guild_id,
+            "anti_raid",
+            "",
+            format!("Suspicious join during an active raid ({})", reasons.join(", ")),
+        );
+        incident.targets = cohort.clone();
+        incident.monitor = monitor;
+
+        if monitor {
+            incident.would(format!("{} <@{}>", action, user_id));
+            incident.punishment = format!("Would {} (monitor mode)", action);
+        } else {
+            let (done, failed) = apply_action(guild_id, &action, &cohort, rest, db).await;
+            incident.punishment = format!("{} {} member(s) ({} failed)", action_label(&action), done, failed);
+        }
+        incident.report(rest, db).await;
+        return;
+    }
+
+    warn!("ANTI-RAID: Raid detected in server {} — {} suspicious joins within {}s.", guild_id, cohort.len(), window_secs);
+
+    let mut incident = Incident::new(
+        guild_id,
+        "anti_raid",
+        "",
+        format!("Join raid: {} suspicious accounts within {}s", cohort.len(), window_secs),
+    );
+    incident.targets = cohort.clone();
+    incident.monitor = monitor;
+
+    if monitor {
+        incident.would(format!("{} {} member(s)", action, cohort.len()));
+        incident.punishment = format!("Would {} (monitor mode)", action);
+    } else if action == "lockdown" {
+        match crate::antinuke::lockdown::engage(guild_id, RAID_REASON, None, rest, db).await {
+            Ok(summary) => {
+                incident.punishment = "Lockdown".to_string();
+                incident.recovery.push(format!("Lockdown engaged, {} recent joiners timed out", summary.members_timed_out));
+            }
+            Err(e) => {
+                error!("Anti-raid lockdown failed for guild {}: {:?}", guild_id, e);
+                incident.punishment = format!("Lockdown failed: {}", e);
+            }
+        }
+    } else {
+        let (done, failed) = apply_action(guild_id, &action, &cohort, rest, db).await;
+        incident.punishment = format!("{} {} member(s) ({} failed)", action_label(&action), done, failed);
+    }
+
+    incident.report(rest, db).await;
+}
+
+fn action_label(action: &str) -> &'static str {
+    match action {
+        "ban" => "Banned",
+        "timeout" => "Timed out",
+        "quarantine" => "Quarantined",
+        "lockdown" => "Locked down",
+        _ => "Kicked",
+    }
+}
+
+async fn apply_action(guild_id: &str, action: &str, cohort: &[String], rest: &RestClient, db: &Database) -> (usize, usize) {
+    let until = (chrono::Utc::now() + chrono::Duration::days(1)).to_rfc3339();
+    let mut done = 0;
+    let mut failed = 0;
+
+    for user_id in cohort {
+        let result = match action {
+            "ban" => rest.ban_user(guild_id, user_id, RAID_REASON).await,
+            "timeout" | "lockdown" => rest.timeout_member(guild_id, user_id, Some(&until), RAID_REASON).await,
+            "quarantine" => crate::antinuke::quarantine::apply(guild_id, user_id, RAID_REASON, "", rest, db).await.map(|_| ()),
+            _ => rest.kick_user(guild_id, user_id, RAID_REASON).await,
+        };
+        match result {
+            Ok(_) => done += 1,
+            Err(e) => {
+                error!("Anti-raid failed to act on {}: {:?}", user_id, e);
+                failed += 1;
+            }
+        }
+    }
+
+    (done, failed)
+}
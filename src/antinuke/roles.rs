@@ -1,5 +1,8 @@
 use crate::rest::RestClient;
 use crate::db::Database;
+use crate::models::audit_action;
+use crate::constants::permissions;
+use crate::antinuke::Incident;
 use std::sync::Arc;
 use serde_json::Value;
 use tracing::{warn, error};
@@ -24,27 +27,102 @@ pub async fn handle_event(
     match event_type {
         "GUILD_ROLE_CREATE" => {
             if *settings.get("anti_role_create").unwrap_or(&false) {
-                if !executor_id.is_empty() && (db.is_whitelisted(executor_id).await.unwrap_or(false) || db.is_admin(executor_id).await.unwrap_or(false)) {
+                if crate::antinuke::is_trusted(guild_id, executor_id, "anti_role_create", &rest, &db).await {
                     return;
                 }
                 warn!("ANTINUKE: Unauthorized role creation in server {}.", guild_id);
-                crate::antinuke::punish_offender(guild_id, 30, rest.clone(), db.clone()).await;
+                crate::antinuke::punish_offender(guild_id, 30, "anti_role_create", rest.clone(), db.clone()).await;
             }
         }
         "GUILD_ROLE_DELETE" => {
             if *settings.get("anti_role_delete").unwrap_or(&false) {
-                if !executor_id.is_empty() && (db.is_whitelisted(executor_id).await.unwrap_or(false) || db.is_admin(executor_id).await.unwrap_or(false)) {
+                if crate::antinuke::is_trusted(guild_id, executor_id, "anti_role_delete", &rest, &db).await {
                     return;
                 }
                 warn!("ANTINUKE: Role deletion detected in server {}.", guild_id);
-                crate::antinuke::punish_offender(guild_id, 32, rest.clone(), db.clone()).await;
+                crate::antinuke::punish_offender(guild_id, 32, "anti_role_delete", rest.clone(), db.clone()).await;
             }
         }
         "GUILD_MEMBER_UPDATE" => {
             if *settings.get("anti_member_role_update").unwrap_or(&false) {
-
+                handle_member_role_update(guild_id, &data, &rest, &db).await;
             }
         }
         _ => {}
     }
 }
+
+async fn handle_member_role_update(guild_id: &str, data: &Value, rest: &RestClient, db: &Database) {
+    let member_id = data.get("user").and_then(|u| u.get("id")).and_then(|v| v.as_str()).unwrap_or("");
+    if member_id.is_empty() { return; }
+
+    let current_roles: Vec<&str> = data.get("roles")
+        .and_then(|v| v.as_array())
+        .map(|arr| arr.iter().filter_map(|v| v.as_str()).collect())
+        .unwrap_or_default();
+
+    let entry = match crate::antinuke::find_audit_entry(guild_id, audit_action::MEMBER_ROLE_UPDATE, Some(member_id), rest).await {
+        Some(e) => e,
+        None => return,
+    };
+
+    let executor_id = entry.get("user_id").and_then(|v| v.as_str()).unwrap_or("");
+    if executor_id.is_empty() || crate::antinuke::is_trusted(guild_id, executor_id, "anti_member_role_update", rest, db).await {
+        return;
+    }
+
+    let added_roles: Vec<&str> = entry.get("changes")
+        .and_then(|v| v.as_array())
+        .map(|changes| {
+            changes.iter()
+                .filter(|c| c.get("key").and_then(|k| k.as_str()) == Some("$add"))
+                .filter_map(|c| c.get("new_value").and_then(|v| v.as_array()))
+                .flatten()
+                .filter_map(|r| r.get("id").and_then(|v| v.as_str()))
+                .filter(|id| current_roles.contains(id))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    if added_roles.is_empty() { return; }
+
+    let guild_roles = match rest.get_guild_roles(guild_id).await {
+        Ok(r) => r,
+        Err(e) => {
+            error!("Failed to fetch roles for guild {}: {:?}", guild_id, e);
+            return;
+        }
+    };
+
+    let dangerous_roles: Vec<&str> = added_roles.into_iter()
+        .filter(|id| {
+            guild_roles.iter()
+                .find(|r| r["id"].as_str() == Some(*id))
+                .map(|r| crate::antinuke::role_permissions(r) & permissions::DANGEROUS != 0)
+                .unwrap_or(false)
+        })
+        .collect();
+
+    if dangerous_roles.is_empty() { return; }
+
+    warn!("ANTINUKE: Dangerous role grant to {} by {} in server {}.", member_id, executor_id, guild_id);
+
+    let mut incident = Incident::new(guild_id, "anti_member_role_update", executor_id, "Granted dangerous role(s) to a member")
+        .with_audit_entry(&entry)
+        .monitored(db).await;
+    incident.targets.push(member_id.to_string());
+
+    for role_id in dangerous_roles {
+        if incident.monitor {
+            incident.would(format!("remove role {} from {}", role_id, member_id));
+            continue;
+        }
+        match rest.remove_member_role(guild_id, member_id, role_id, "Rimuru Antinuke: Unauthorized Dangerous Role Grant").await {
+            Ok(_) => incident.recovery.push(format!("Removed role {} from {}", role_id, member_id)),
+            Err(e) => incident.recovery.push(format!("Failed to remove role {}: {}", role_id, e)),
+        }
+    }
+
+    incident.punishment = crate::antinuke::punish(guild_id, executor_id, "Rimuru Antinuke: Unauthorized Dangerous Role Grant", incident.monitor, rest, db).await;
+    incident.report(rest, db).await;
+}
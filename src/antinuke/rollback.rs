@@ -0,0 +1,223 @@
+use crate::rest::RestClient;
+use crate::db::Database;
+use crate::constants::{emojis, colors};
+use crate::models::audit_action;
+use super::{Incident, snowflake_timestamp_ms};
+use serde_json::{json, Value};
+use tracing::{info, warn, error};
+
+const ROLLBACK_REASON: &str = "Rimuru Antinuke: Restoring members banned during a nuke";
+
+const MIN_WINDOW_SECS: u64 = 60;
+
+fn id_list(value: &Value) -> Vec<String> {
+    value.as_array()
+        .map(|arr| arr.iter().filter_map(|v| v.as_str().map(String::from)).collect())
+        .unwrap_or_default()
+}
+
+fn mentions(ids: &[String]) -> String {
+    let mut text = ids.iter().map(|id| format!("<@{}>", id)).collect::<Vec<_>>().join(", ");
+    if text.len() > 3500 {
+        text.truncate(3500);
+        text.push_str(" …");
+    }
+    text
+}
+
+pub async fn ban_victims(guild_id: &str, executor_id: &str, rest: &RestClient, db: &Database) -> Vec<String> {
+    let (_, window_secs) = db.get_threshold(guild_id, "anti_ban").await;
+    let cutoff = chrono::Utc::now().timestamp_millis() - (window_secs.max(MIN_WINDOW_SECS) * 1000) as i64;
+
+    let logs = match rest.get_audit_logs(guild_id, audit_action::MEMBER_BAN_ADD, 100).await {
+        Ok(l) => l,
+        Err(e) => {
+            error!("Failed to fetch ban audit logs for rollback in guild {}: {:?}", guild_id, e);
+            return Vec::new();
+        }
+    };
+
+    let mut victims: Vec<String> = Vec::new();
+    for entry in logs.as_array().into_iter().flatten() {
+        if entry["user_id"].as_str() != Some(executor_id) { continue; }
+        let recent = entry["id"].as_str()
+            .and_then(snowflake_timestamp_ms)
+            .map(|ts| ts >= cutoff)
+            .unwrap_or(false);
+        if let (true, Some(target)) = (recent, entry["target_id"].as_str()) {
+            if !victims.iter().any(|v| v == target) {
+                victims.push(target.to_string());
+            }
+        }
+    }
+    victims
+}
+
+async fn restore(guild_id: &str, victims: &[String], rest: &RestClient) -> (Vec<String>, Vec<String>) {
+    let mut restored = Vec::new();
+    let mut failed = Vec::new();
+
+    for user_id in victims {
+        match rest.remove_guild_ban(guild_id, user_id, ROLLBACK_REASON).await {
+            Ok(_) => restored.push(user_id.clone()),
+            Err(e) => {
+                error!("Ban rollback failed to unban {} in guild {}: {:?}", user_id, guild_id, e);
+                failed.push(user_id.clone());
+            }
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(250)).await;
+    }
+
+    (restored, failed)
+}
+
+fn recovery_lines(restored: &[String], failed: &[String], suffix: &str) -> Vec<String> {
+    let mut lines = Vec::new();
+    if !restored.is_empty() {
+        lines.push(format!("Unbanned {} member(s){}: {}", restored.len(), suffix, restored.join(", ")));
+    }
+    if !failed.is_empty() {
+        lines.push(format!("Failed to unban {} member(s): {}", failed.len(), failed.join(", ")));
+    }
+    lines
+}
+
+fn load_state(state: Option<Value>, executor_id: &str) -> Value {
+    state.unwrap_or_else(|| json!({
+        "id": executor_id,
+        "incident_id": null,
+        "victims": [],
+        "restored": [],
+        "declined": []
+    }))
+}
+
+pub async fn collect(incident: &mut Incident, rest: &RestClient, db: &Database) -> bool {
+    let guild_id = incident.guild_id.clone();
+    let executor_id = incident.executor_id.clone();
+    let mode = db.get_ban_rollback(&guild_id).await;
+    if mode == "off" { return false; }
+
+    let mut state = load_state(db.get_snapshot(&guild_id, "ban_rollback", &executor_id).await.ok().flatten(), &executor_id);
+    let queued = id_list(&state["victims"]);
+    let mut restored_all = id_list(&state["restored"]);
+    let known = [queued.clone(), restored_all.clone(), id_list(&state["declined"])].concat();
+
+    let victims: Vec<String> = ban_victims(&guild_id, &executor_id, rest, db).await
+        .into_iter()
+        .filter(|v| !known.contains(v))
+        .collect();
+    if victims.is_empty() { return false; }
+
+    incident.targets.extend(victims.iter().cloned());
+
+    if incident.monitor {
+        incident.would(format!("unban {} member(s) banned by the offender", victims.len()));
+        return false;
+    }
+
+    if mode == "auto" {
+        let (restored, failed) = restore(&guild_id, &victims, rest).await;
+        incident.recovery.extend(recovery_lines(&restored, &failed, ""));
+        info!("Ban rollback restored {} member(s) banned by {} in guild {}.", restored.len(), executor_id, guild_id);
+
+        restored_all.extend(restored);
+        state["restored"] = json!(restored_all);
+        let _ = db.save_snapshot(&guild_id, "ban_rollback", &executor_id, &state).await;
+        return false;
+    }
+
+    let pending = !queued.is_empty();
+    state["victims"] = json!([queued, victims.clone()].concat());
+    let _ = db.save_snapshot(&guild_id, "ban_rollback", &executor_id, &state).await;
+
+    incident.recovery.push(format!("{} member(s) banned by the offender are awaiting owner confirmation to be unbanned", victims.len()));
+    !pending
+}
+
+pub async fn prompt(guild_id: &str, executor_id: &str, incident_id: Option<i64>, rest: &RestClient, db: &Database) {
+    let mut state = match db.get_snapshot(guild_id, "ban_rollback", executor_id).await.ok().flatten() {
+        Some(s) => s,
+        None => return,
+    };
+    state["incident_id"] = json!(incident_id);
+    let _ = db.save_snapshot(guild_id, "ban_rollback", executor_id, &state).await;
+
+    let victims = id_list(&state["victims"]);
+    let (log_channel, _) = db.get_alert_options(guild_id).await;
+    let channel_id = match log_channel {
+        Some(c) => c,
+        None => match rest.get_guild(guild_id).await.ok().and_then(|g| g["system_channel_id"].as_str().map(String::from)) {
+            Some(c) => c,
+            None => {
+                warn!("No channel available for ban rollback prompt in guild {}.", guild_id);
+                return;
+            }
+        },
+    };
+
+    let embed = json!({
+        "title": format!("{} MASS BAN ROLLBACK", emojis::HAMMER),
+        "description": format!(
+            "<@{}> banned **{}** member(s) before being stopped.\n\n{}\n\nThe **Server Owner** can restore them below.",
+            executor_id, victims.len(), mentions(&victims)
+        ),
+        "color": colors::RED,
+        "footer": { "text": match incident_id {
+            Some(id) => format!("Incident #{}", id),
+            None => "Rimuru Advanced Security".to_string(),
+        } }
+    });
+    let components = vec![json!({
+        "type": 1,
+        "components": [
+            { "type": 2, "style": 3, "label": "Unban Victims", "custom_id": format!("antinuke_rollback:confirm:{}", executor_id) },
+            { "type": 2, "style": 2, "label": "Keep Banned", "custom_id": format!("antinuke_rollback:dismiss:{}", executor_id) }
+        ]
+    })];
+
+    if let Err(e) = rest.send_complex_message(&channel_id, "", vec![embed], components).await {
+        error!("Failed to post ban rollback prompt in {}: {:?}", channel_id, e);
+    }
+}
+
+pub async fn confirm(guild_id: &str, executor_id: &str, rest: &RestClient, db: &Database) -> anyhow::Result<(usize, usize)> {
+    let mut state = match db.get_snapshot(guild_id, "ban_rollback", executor_id).await? {
+        Some(s) if !id_list(&s["victims"]).is_empty() => s,
+        _ => anyhow::bail!("This rollback is no longer pending"),
+    };
+
+    let (restored, failed) = restore(guild_id, &id_list(&state["victims"]), rest).await;
+
+    if let Some(incident_id) = state["incident_id"].as_i64() {
+        let lines = recovery_lines(&restored, &failed, " after owner confirmation");
+        if let Err(e) = db.append_incident_recovery(guild_id, incident_id, &lines).await {
+            error!("Failed to record ban rollback on incident {}: {:?}", incident_id, e);
+        }
+    }
+
+    let restored_count = restored.len();
+    let failed_count = failed.len();
+    state["restored"] = json!([id_list(&state["restored"]), restored].concat());
+    state["victims"] = json!([]);
+    db.save_snapshot(guild_id, "ban_rollback", executor_id, &state).await?;
+
+    Ok((restored_count, failed_count))
+}
+
+pub async fn dismiss(guild_id: &str, executor_id: &str, db: &Database) -> anyhow::Result<()> {
+    let mut state = match db.get_snapshot(guild_id, "ban_rollback", executor_id).await? {
+        Some(s) if !id_list(&s["victims"]).is_empty() => s,
+        _ => anyhow::bail!("This rollback is no longer pending"),
+    };
+
+    let victims = id_list(&state["victims"]);
+    if let Some(incident_id) = state["incident_id"].as_i64() {
+        let line = format!("Owner declined to unban {} member(s)", victims.len());
+        db.append_incident_recovery(guild_id, incident_id, &[line]).await?;
+    }
+
+    state["declined"] = json!([id_list(&state["declined"]), victims].concat());
+    state["victims"] = json!([]);
+    db.save_snapshot(guild_id, "ban_rollback", executor_id, &state).await
+}
@@ -0,0 +1,63 @@
+use crate::rest::RestClient;
+use crate::db::Database;
+use crate::models::audit_action;
+use crate::antinuke::Incident;
+use std::sync::Arc;
+use serde_json::Value;
+use tracing::{warn, error};
+
+pub async fn handle_event(
+    event_type: &str,
+    data: Value,
+    rest: Arc<RestClient>,
+    db: Arc<Database>,
+) {
+    let guild_id = data.get("guild_id").and_then(|v| v.as_str()).unwrap_or("");
+    let settings = match db.get_antinuke_settings(guild_id).await {
+        Ok(s) => s,
+        Err(e) => {
+            error!("Failed to fetch antinuke settings for guild {}: {:?}", guild_id, e);
+            return;
+        }
+    };
+
+    let (setting, action_type) = match event_type {
+        "GUILD_SCHEDULED_EVENT_CREATE" => ("anti_guild_event_create", audit_action::GUILD_SCHEDULED_EVENT_CREATE),
+        "GUILD_SCHEDULED_EVENT_UPDATE" => ("anti_guild_event_update", audit_action::GUILD_SCHEDULED_EVENT_UPDATE),
+        "GUILD_SCHEDULED_EVENT_DELETE" => ("anti_guild_event_delete", audit_action::GUILD_SCHEDULED_EVENT_DELETE),
+        _ => return,
+    };
+
+    if !*settings.get(setting).unwrap_or(&false) { return; }
+
+    let event_id = data.get("id").and_then(|v| v.as_str()).unwrap_or("");
+    let entry = match crate::antinuke::find_audit_entry(guild_id, action_type, Some(event_id), &rest).await {
+        Some(e) => e,
+        None => return,
+    };
+
+    let executor_id = entry.get("user_id").and_then(|v| v.as_str()).unwrap_or("");
+    if executor_id.is_empty() || crate::antinuke::is_trusted(guild_id, executor_id, setting, &rest, &db).await {
+        return;
+    }
+
+    let event_name = data.get("name").and_then(|v| v.as_str()).unwrap_or("unknown");
+    warn!("ANTINUKE: Unauthorized scheduled event change ({}) by {} in server {}.", event_type, executor_id, guild_id);
+
+    let mut incident = Incident::new(guild_id, setting, executor_id, format!("Modified scheduled event '{}'", event_name))
+        .with_audit_entry(&entry)
+        .monitored(&db).await;
+    incident.targets.push(format!("scheduled_event:{}", event_id));
+
+    if event_type == "GUILD_SCHEDULED_EVENT_CREATE" && incident.monitor {
+        incident.would(format!("delete scheduled event '{}'", event_name));
+    } else if event_type == "GUILD_SCHEDULED_EVENT_CREATE" {
+        match rest.delete_scheduled_event(guild_id, event_id, "Rimuru Antinuke: Unauthorized Scheduled Event").await {
+            Ok(_) => incident.recovery.push(format!("Deleted scheduled event '{}'", event_name)),
+            Err(e) => incident.recovery.push(format!("Failed to delete scheduled event: {}", e)),
+        }
+    }
+
+    incident.punishment = crate::antinuke::punish(guild_id, executor_id, "Rimuru Antinuke: Unauthorized Scheduled Event Change", incident.monitor, &rest, &db).await;
+    incident.report(&rest, &db).await;
+}
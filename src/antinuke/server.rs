@@ -1,16 +1,27 @@
 use crate::rest::RestClient;
 use crate::db::Database;
+use crate::models::audit_action;
+use crate::antinuke::Incident;
 use std::sync::Arc;
-use serde_json::Value;
-use tracing::{warn, error};
+use serde_json::{json, Value};
+use tracing::{info, warn, error};
+
+const GUILD_FIELDS: [&str; 9] = [
+    "name", "icon", "banner", "vanity_url_code", "verification_level",
+    "explicit_content_filter", "system_channel_id", "rules_channel_id",
+    "default_message_notifications",
+];
 
 pub async fn handle_event(
     event_type: &str,
     data: Value,
-    _rest: Arc<RestClient>,
+    rest: Arc<RestClient>,
     db: Arc<Database>,
 ) {
-    let guild_id = data.get("guild_id").and_then(|v| v.as_str()).unwrap_or("");
+    let guild_id = match event_type {
+        "GUILD_CREATE" | "GUILD_UPDATE" => data.get("id").and_then(|v| v.as_str()).unwrap_or(""),
+        _ => data.get("guild_id").and_then(|v| v.as_str()).unwrap_or(""),
+    };
     let settings = match db.get_antinuke_settings(guild_id).await {
         Ok(s) => s,
         Err(e) => {
@@ -20,25 +31,171 @@ pub async fn handle_event(
     };
 
     match event_type {
+        "GUILD_CREATE" => {
+            let previous = db.get_snapshot(guild_id, "guild", guild_id).await.ok().flatten();
+            snapshot_guild(guild_id, &data, previous.as_ref(), &rest, &db).await;
+        }
         "GUILD_UPDATE" => {
-            if *settings.get("anti_server_update").unwrap_or(&false) {
-                warn!("Antinuke: Server settings update detected in {}.", guild_id);
+            let previous = db.get_snapshot(guild_id, "guild", guild_id).await.ok().flatten();
+            match previous {
+                Some(snapshot) if *settings.get("anti_server_update").unwrap_or(&false) => {
+                    handle_guild_update(guild_id, &data, &snapshot, &rest, &db).await;
+                }
+                _ => snapshot_guild(guild_id, &data, previous.as_ref(), &rest, &db).await,
             }
         }
         "MESSAGE_CREATE" => {
             if *settings.get("anti_everyone_ping").unwrap_or(&false) {
-                let content = data.get("content").and_then(|v| v.as_str()).unwrap_or("");
-                if content.contains("@everyone") || content.contains("@here") {
-                    let _channel_id = data.get("channel_id").and_then(|v| v.as_str()).unwrap_or("");
-                    let author_id = data.get("author").and_then(|v| v.get("id")).and_then(|v| v.as_str()).unwrap_or("");
+                let pinged = data.get("mention_everyone").and_then(|v| v.as_bool()).unwrap_or(false);
+                if pinged {
+                    handle_everyone_ping(guild_id, &data, &rest, &db).await;
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+async fn snapshot_guild(guild_id: &str, guild: &Value, previous: Option<&Value>, rest: &RestClient, db: &Database) {
+    let mut snapshot = json!({});
+    for field in GUILD_FIELDS {
+        snapshot[field] = guild.get(field).cloned().unwrap_or(Value::Null);
+    }
+
+    for (field, cdn_path) in [("icon", "icons"), ("banner", "banners")] {
+        let data_field = format!("{}_data", field);
+        let hash = match guild.get(field).and_then(|v| v.as_str()) {
+            Some(h) => h,
+            None => continue,
+        };
+
+        let archived = previous
+            .filter(|p| p[field].as_str() == Some(hash))
+            .and_then(|p| p.get(&data_field))
+            .filter(|v| v.is_string());
+        if let Some(data) = archived {
+            snapshot[&data_field] = data.clone();
+            continue;
+        }
+
+        let ext = if hash.starts_with("a_") { "gif" } else { "png" };
+        let url = format!("https://cdn.discordapp.com/{}/{}/{}.{}", cdn_path, guild_id, hash, ext);
+        match rest.download_image(&url).await {
+            Ok((content_type, bytes)) => snapshot[&data_field] = json!(crate::rest::image_data_uri(&content_type, &bytes)),
+            Err(e) => warn!("Failed to archive guild {} for {}: {:?}", field, guild_id, e),
+        }
+    }
+
+    if let Err(e) = db.save_snapshot(guild_id, "guild", guild_id, &snapshot).await {
+        error!("Failed to save guild snapshot for {}: {:?}", guild_id, e);
+    } else {
+        info!("Guild settings snapshot stored for {}.", guild_id);
+    }
+}
+
+async fn handle_guild_update(guild_id: &str, guild: &Value, snapshot: &Value, rest: &RestClient, db: &Database) {
+    let changed: Vec<&str> = GUILD_FIELDS.iter()
+        .copied()
+        .filter(|f| guild.get(*f).unwrap_or(&Value::Null) != &snapshot[*f])
+        .collect();
+
+    if changed.is_empty() { return; }
+
+    let entry = crate::antinuke::find_audit_entry(guild_id, audit_action::GUILD_UPDATE, Some(guild_id), rest).await;
+    let executor_id = entry.as_ref().and_then(|e| e.get("user_id")).and_then(|v| v.as_str()).unwrap_or("");
+
+    if executor_id.is_empty() || crate::antinuke::is_trusted(guild_id, executor_id, "anti_server_update", rest, db).await {
+        snapshot_guild(guild_id, guild, Some(snapshot), rest, db).await;
+        return;
+    }
 
-                    if !db.is_whitelisted(author_id).await.unwrap_or(false) {
-                        warn!("Antinuke: Unauthorized @everyone ping from {}.", author_id);
+    warn!("ANTINUKE: Unauthorized server update ({}) by {} in server {}.", changed.join(", "), executor_id, guild_id);
 
-                    }
+    let mut incident = Incident::new(guild_id, "anti_server_update", executor_id, format!("Changed server settings: {}", changed.join(", ")))
+        .with_audit_entry(entry.as_ref().unwrap_or(&Value::Null))
+        .monitored(db).await;
+    let reason = "Rimuru Antinuke: Reverting Unauthorized Server Update";
+
+    if incident.monitor {
+        incident.would(format!("restore {} from snapshot", changed.join(", ")));
+        incident.punishment = crate::antinuke::punish(guild_id, executor_id, "Rimuru Antinuke: Unauthorized Server Update", incident.monitor, rest, db).await;
+        incident.report(rest, db).await;
+        snapshot_guild(guild_id, guild, Some(snapshot), rest, db).await;
+        return;
+    }
+
+    let mut body = json!({});
+    for field in changed.iter().filter(|f| **f != "vanity_url_code") {
+        match *field {
+            "icon" | "banner" => {
+                let data = &snapshot[format!("{}_data", field)];
+                if snapshot[*field].is_string() && !data.is_string() {
+                    incident.recovery.push(format!("No archived {} to restore", field));
+                    continue;
                 }
+                body[*field] = data.clone();
             }
+            _ => body[*field] = snapshot[*field].clone(),
         }
-        _ => {}
     }
+
+    if body.as_object().map(|o| !o.is_empty()).unwrap_or(false) {
+        match rest.modify_guild(guild_id, body, reason).await {
+            Ok(_) => incident.recovery.push("Restored server settings from snapshot".to_string()),
+            Err(e) => incident.recovery.push(format!("Failed to restore server settings: {}", e)),
+        }
+    }
+
+    if changed.contains(&"vanity_url_code") {
+        let code = snapshot["vanity_url_code"].as_str();
+        match rest.modify_guild_vanity(guild_id, code, reason).await {
+            Ok(_) => incident.recovery.push(format!("Restored vanity URL to {}", code.unwrap_or("none"))),
+            Err(e) => incident.recovery.push(format!("Failed to restore vanity URL: {}", e)),
+        }
+    }
+
+    incident.punishment = crate::antinuke::punish(guild_id, executor_id, "Rimuru Antinuke: Unauthorized Server Update", incident.monitor, rest, db).await;
+    incident.report(rest, db).await;
+}
+
+async fn handle_everyone_ping(guild_id: &str, message: &Value, rest: &RestClient, db: &Database) {
+    let channel_id = message.get("channel_id").and_then(|v| v.as_str()).unwrap_or("");
+    let message_id = message.get("id").and_then(|v| v.as_str()).unwrap_or("");
+    let author_id = message.get("author").and_then(|v| v.get("id")).and_then(|v| v.as_str()).unwrap_or("");
+    let webhook_id = message.get("webhook_id").and_then(|v| v.as_str());
+
+    if webhook_id.is_none() && crate::antinuke::is_trusted(guild_id, author_id, "anti_everyone_ping", rest, db).await {
+        return;
+    }
+
+    warn!("Antinuke: Unauthorized @everyone ping from {} in channel {}.", author_id, channel_id);
+
+    let mut incident = Incident::new(guild_id, "anti_everyone_ping", author_id, format!("Pinged @everyone/@here in <#{}>", channel_id))
+        .monitored(db).await;
+    incident.targets.push(format!("channel:{}", channel_id));
+
+    if incident.monitor {
+        incident.would(format!("delete message {}", message_id));
+    } else {
+        match rest.delete_message(channel_id, message_id, "Rimuru Antinuke: Unauthorized @everyone Ping").await {
+            Ok(_) => incident.recovery.push(format!("Deleted message {}", message_id)),
+            Err(e) => incident.recovery.push(format!("Failed to delete message {}: {}", message_id, e)),
+        }
+    }
+
+    if webhook_id.is_some() {
+        incident.action = format!("Webhook pinged @everyone/@here in <#{}>", channel_id);
+        incident.report(rest, db).await;
+        return;
+    }
+
+    let (max_pings, window_secs) = db.get_threshold(guild_id, "anti_everyone_ping").await;
+    let count = db.track_action(guild_id, author_id, "anti_everyone_ping", window_secs).await;
+
+    if count >= max_pings as usize {
+        incident.punishment = crate::antinuke::punish(guild_id, author_id, "Rimuru Antinuke: Unauthorized @everyone Ping", incident.monitor, rest, db).await;
+    } else {
+        incident.punishment = format!("None (ping {}/{} within {}s)", count, max_pings, window_secs);
+    }
+    incident.report(rest, db).await;
 }
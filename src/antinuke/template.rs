@@ -0,0 +1,502 @@
+use crate::rest::RestClient;
+use crate::db::Database;
+use crate::antinuke::quarantine;
+use std::collections::{HashMap, HashSet};
+use serde_json::{json, Map, Value};
+use tracing::info;
+
+pub const TEMPLATE_VERSION: u64 = 1;
+
+pub const TEMPLATE_KIND: &str = "rimuru_antinuke_template";
+
+pub const MAX_TEMPLATE_BYTES: u64 = 256 * 1024;
+
+const PUNISHMENTS: [&str; 5] = ["ban", "kick", "timeout", "strip", "quarantine"];
+
+const RAID_ACTIONS: [&str; 5] = ["kick", "ban", "timeout", "quarantine", "lockdown"];
+
+const ROLLBACK_MODES: [&str; 3] = ["auto", "confirm", "off"];
+
+const OPTION_FIELDS: [&str; 5] = ["punishment", "raid_action", "ban_rollback", "log_channel", "owner_dm"];
+
+fn strings(value: &Value) -> Vec<String> {
+    value.as_array()
+        .map(|arr| arr.iter().filter_map(|v| v.as_str().map(String::from)).collect())
+        .unwrap_or_default()
+}
+
+fn is_snowflake(id: &str) -> bool {
+    !id.is_empty() && id.len() <= 20 && id.chars().all(|c| c.is_ascii_digit())
+}
+
+fn threshold_label(value: &Value) -> String {
+    format!("{}/{}s", value["max_actions"], value["window_secs"])
+}
+
+pub async fn export(guild_id: &str, db: &Database) -> anyhow::Result<Value> {
+    let settings = db.get_antinuke_settings(guild_id).await?;
+    let mut modules = Map::new();
+    for (key, enabled) in settings.iter() {
+        modules.insert(key.clone(), json!(enabled));
+    }
+
+    let mut monitor: Vec<String> = db.list_monitor_modules(guild_id).await?.into_iter().collect();
+    monitor.sort();
+
+    let mut thresholds = Map::new();
+    for (module, max_actions, window_secs) in db.list_thresholds(guild_id).await? {
+        thresholds.insert(module, json!({ "max_actions": max_actions, "window_secs": window_secs }));
+    }
+
+    let whitelist: Vec<Value> = db.list_whitelist(guild_id).await?.iter().map(|e| json!({
+        "id": e.target_id,
+        "name": e.name,
+        "kind": e.kind,
+        "modules": e.modules
+    })).collect();
+
+    let mut trusted_bots = db.list_trusted_bots(guild_id).await?;
+    trusted_bots.sort();
+
+    let (log_channel, owner_dm) = db.get_alert_options(guild_id).await;
+
+    Ok(json!({
+        "kind": TEMPLATE_KIND,
+        "version": TEMPLATE_VERSION,
+        "source_guild": guild_id,
+        "exported_at": chrono::Utc::now().to_rfc3339(),
+        "modules": modules,
+        "monitor": monitor,
+        "thresholds": thresholds,
+        "punishment": db.get_punishment(guild_id).await,
+        "raid_action": db.get_raid_action(guild_id).await,
+        "ban_rollback": db.get_ban_rollback(guild_id).await,
+        "log_channel": log_channel,
+        "owner_dm": owner_dm,
+        "whitelist": whitelist,
+        "trusted_bots": trusted_bots
+    }))
+}
+
+pub fn validate(doc: &Value, known: &HashMap<String, bool>) -> anyhow::Result<()> {
+    if doc["kind"].as_str() != Some(TEMPLATE_KIND) {
+        anyhow::bail!("This file is not an antinuke template");
+    }
+    match doc["version"].as_u64() {
+        Some(v) if v <= TEMPLATE_VERSION => {}
+        _ => anyhow::bail!("Unsupported template version (expected {} or lower)", TEMPLATE_VERSION),
+    }
+    let is_module = |m: &str| known.contains_key(m);
+
+    if let Some(modules) = doc.get("modules") {
+        let modules = modules.as_object().ok_or_else(|| anyhow::anyhow!("`modules` must be an object of module toggles"))?;
+        for (key, value) in modules {
+            if !is_module(key) { anyhow::bail!("Unknown module `{}`", key); }
+            if !value.is_boolean() { anyhow::bail!("Module `{}` must be true or false", key); }
+        }
+    }
+
+    if let Some(monitor) = doc.get("monitor") {
+        let list = monitor.as_array().ok_or_else(|| anyhow::anyhow!("`monitor` must be a list of modules"))?;
+        for module in list {
+            match module.as_str() {
+                Some(m) if m == "*" || is_module(m) => {}
+                _ => anyhow::bail!("Invalid monitor module {}", module),
+            }
+        }
+    }
+
+    if let Some(thresholds) = doc.get("thresholds") {
+        let thresholds = thresholds.as_object().ok_or_else(|| anyhow::anyhow!("`thresholds` must be an object"))?;
+        for (module, value) in thresholds {
+            if !is_module(module) { anyhow::bail!("Unknown threshold module `{}`", module); }
+            let count = value["max_actions"].as_u64().filter(|c| *c > 0 && *c <= u32::MAX as u64);
+            let window = value["window_secs"].as_u64().filter(|w| *w > 0);
+            if count.is_none() || window.is_none() {
+                anyhow::bail!("Threshold for `{}` needs positive `max_actions` and `window_secs`", module);
+            }
+        }
+    }
+
+    for (field, allowed) in [("punishment", &PUNISHMENTS[..]), ("raid_action", &RAID_ACTIONS[..]), ("ban_rollback", &ROLLBACK_MODES[..])] {
+        if let Some(value) = doc.get(field) {
+            if !value.as_str().map(|v| allowed.contains(&v)).unwrap_or(false) {
+                anyhow::bail!("`{}` must be one of: {}", field, allowed.join(", "));
+            }
+        }
+    }
+
+    if let Some(channel) = doc.get("log_channel") {
+        if !channel.is_null() && !channel.as_str().map(is_snowflake).unwrap_or(false) {
+            anyhow::bail!("`log_channel` must be a channel ID or null");
+        }
+    }
+    if let Some(owner_dm) = doc.get("owner_dm") {
+        if !owner_dm.is_boolean() { anyhow::bail!("`owner_dm` must be true or false"); }
+    }
+
+    if let Some(whitelist) = doc.get("whitelist") {
+        let entries = whitelist.as_array().ok_or_else(|| anyhow::anyhow!("`whitelist` must be a list"))?;
+        for entry in entries {
+            let id = entry["id"].as_str().unwrap_or("");
+            if !is_snowflake(id) { anyhow::bail!("Whitelist entry {} has an invalid ID", entry["id"]); }
+            if !matches!(entry["kind"].as_str(), Some("user") | Some("role")) {
+                anyhow::bail!("Whitelist entry `{}` must be of kind `user` or `role`", id);
+            }
+            let modules = entry["modules"].as_array().ok_or_else(|| anyhow::anyhow!("Whitelist entry `{}` needs a `modules` list", id))?;
+            if modules.iter().any(|m| !m.as_str().map(|m| m == "*" || is_module(m)).unwrap_or(false)) {
+                anyhow::bail!("Whitelist entry `{}` references an unknown module", id);
+            }
+        }
+    }
+
+    if let Some(bots) = doc.get("trusted_bots") {
+        let bots = bots.as_array().ok_or_else(|| anyhow::anyhow!("`trusted_bots` must be a list of bot IDs"))?;
+        if bots.iter().any(|b| !b.as_str().map(is_snowflake).unwrap_or(false)) {
+            anyhow::bail!("`trusted_bots` contains an invalid bot ID");
+        }
+    }
+
+    Ok(())
+}
+
+#[derive(Default)]
+pub struct TemplateDiff {
+    pub enabled: Vec<String>,
+    pub disabled: Vec<String>,
+    pub monitor_added: Vec<String>,
+    pub monitor_removed: Vec<String>,
+    pub thresholds_changed: Vec<(String, String, String)>,
+    pub options_changed: Vec<(&'static str, String, String)>,
+    pub whitelist_added: Vec<String>,
+    pub whitelist_removed: Vec<String>,
+    pub whitelist_changed: Vec<String>,
+    pub bots_added: Vec<String>,
+    pub bots_removed: Vec<String>,
+}
+
+impl TemplateDiff {
+    pub fn is_empty(&self) -> bool {
+        self.enabled.is_empty() && self.disabled.is_empty()
+            && self.monitor_added.is_empty() && self.monitor_removed.is_empty()
+            && self.thresholds_changed.is_empty() && self.options_changed.is_empty()
+            && self.whitelist_added.is_empty() && self.whitelist_removed.is_empty() && self.whitelist_changed.is_empty()
+            && self.bots_added.is_empty() && self.bots_removed.is_empty()
+    }
+}
+
+fn set_diff(wanted: &[String], current: &[String]) -> (Vec<String>, Vec<String>) {
+    let wanted_set: HashSet<&String> = wanted.iter().collect();
+    let current_set: HashSet<&String> = current.iter().collect();
+    let mut added: Vec<String> = wanted.iter().filter(|w| !current_set.contains(w)).cloned().collect();
+    let mut removed: Vec<String> = current.iter().filter(|c| !wanted_set.contains(c)).cloned().collect();
+    added.sort();
+    removed.sort();
+    (added, removed)
+}
+
+pub fn diff(template: &Value, current: &Value) -> TemplateDiff {
+    let mut diff = TemplateDiff::default();
+
+    if let Some(modules) = template["modules"].as_object() {
+        let mut keys: Vec<&String> = modules.keys().collect();
+        keys.sort();
+        for key in keys {
+            let wanted = modules[key].as_bool().unwrap_or(false);
+            if current["modules"][key].as_bool().unwrap_or(false) == wanted { continue; }
+            if wanted { diff.enabled.push(key.clone()); } else { diff.disabled.push(key.clone()); }
+        }
+    }
+
+    if template.get("monitor").is_some() {
+        (diff.monitor_added, diff.monitor_removed) = set_diff(&strings(&template["monitor"]), &strings(&current["monitor"]));
+    }
+
+    if let Some(thresholds) = template["thresholds"].as_object() {
+        let current_thresholds = current["thresholds"].as_object().cloned().unwrap_or_default();
+        let mut modules: Vec<&String> = thresholds.keys().chain(current_thresholds.keys()).collect();
+        modules.sort();
+        modules.dedup();
+        for module in modules {
+            let before = current_thresholds.get(module).map(threshold_label).unwrap_or_else(|| "default".to_string());
+            let after = thresholds.get(module).map(threshold_label).unwrap_or_else(|| "default".to_string());
+            if before != after {
+                diff.thresholds_changed.push((module.clone(), before, after));
+            }
+        }
+    }
+
+    for field in OPTION_FIELDS {
+        let wanted = match template.get(field) {
+            Some(v) => v,
+            None => continue,
+        };
+        if *wanted != current[field] {
+            diff.options_changed.push((field, current[field].to_string(), wanted.to_string()));
+        }
+    }
+
+    if let Some(entries) = template["whitelist"].as_array() {
+        let current_entries: HashMap<&str, &Value> = current["whitelist"].as_array().into_iter().flatten()
+            .filter_map(|e| e["id"].as_str().map(|id| (id, e)))
+            .collect();
+        let wanted: HashSet<&str> = entries.iter().filter_map(|e| e["id"].as_str()).collect();
+        for entry in entries {
+            let id = entry["id"].as_str().unwrap_or("");
+            match current_entries.get(id) {
+                None => diff.whitelist_added.push(id.to_string()),
+                Some(existing) if existing["kind"] != entry["kind"] || existing["modules"] != entry["modules"] => {
+                    diff.whitelist_changed.push(id.to_string());
+                }
+                _ => {}
+            }
+        }
+        let mut removed: Vec<String> = current_entries.keys().filter(|id| !wanted.contains(*id)).map(|id| id.to_string()).collect();
+        removed.sort();
+        diff.whitelist_removed = removed;
+    }
+
+    if template.get("trusted_bots").is_some() {
+        (diff.bots_added, diff.bots_removed) = set_diff(&strings(&template["trusted_bots"]), &strings(&current["trusted_bots"]));
+    }
+
+    diff
+}
+
+pub async fn apply(guild_id: &str, template: &Value, rest: &RestClient, db: &Database) -> anyhow::Result<Vec<String>> {
+    let mut warnings = Vec::new();
+
+    let needs_quarantine = ["punishment", "raid_action"].iter().any(|f| template[*f].as_str() == Some("quarantine"));
+    if needs_quarantine {
+        quarantine::ensure_role(guild_id, rest, db).await?;
+    }
+
+    let settings = db.get_antinuke_settings(guild_id).await?;
+    if let Some(modules) = template["modules"].as_object() {
+        for (key, value) in modules {
+            let enabled = value.as_bool().unwrap_or(false);
+            if settings.get(key).cloned() != Some(enabled) {
+                db.update_antinuke_setting(guild_id, key, enabled).await?;
+            }
+        }
+    }
+
+    if template.get("monitor").is_some() {
+        let wanted = strings(&template["monitor"]);
+        for module in db.list_monitor_modules(guild_id).await? {
+            if !wanted.contains(&module) {
+                db.set_monitor_mode(guild_id, &module, false).await?;
+            }
+        }
+        for module in &wanted {
+            db.set_monitor_mode(guild_id, module, true).await?;
+        }
+    }
+
+    if let Some(thresholds) = template["thresholds"].as_object() {
+        for (module, _, _) in db.list_thresholds(guild_id).await? {
+            if !thresholds.contains_key(&module) {
+                db.remove_threshold(guild_id, &module).await?;
+            }
+        }
+        for (module, value) in thresholds {
+            let count = value["max_actions"].as_u64().unwrap_or(1) as u32;
+            let window = value["window_secs"].as_u64().unwrap_or(10);
+            db.set_threshold(guild_id, module, count, window).await?;
+        }
+    }
+
+    if let Some(punishment) = template["punishment"].as_str() {
+        db.set_punishment(guild_id, punishment).await?;
+    }
+    if let Some(action) = template["raid_action"].as_str() {
+        db.set_raid_action(guild_id, action).await?;
+    }
+    if let Some(mode) = template["ban_rollback"].as_str() {
+        db.set_ban_rollback(guild_id, mode).await?;
+    }
+    if let Some(owner_dm) = template["owner_dm"].as_bool() {
+        db.set_owner_dm(guild_id, owner_dm).await?;
+    }
+
+    match template.get("log_channel") {
+        Some(Value::String(channel_id)) => {
+            let exists = rest.get_guild_channels(guild_id).await?.as_array().into_iter().flatten()
+                .any(|c| c["id"].as_str() == Some(channel_id.as_str()));
+            if exists {
+                db.set_log_channel(guild_id, Some(channel_id)).await?;
+            } else {
+                warnings.push(format!("Log channel `{}` does not exist in this server; the current log channel was kept.", channel_id));
+            }
+        }
+        Some(Value::Null) => db.set_log_channel(guild_id, None).await?,
+        _ => {}
+    }
+
+    if let Some(entries) = template["whitelist"].as_array() {
+        let roles: HashSet<String> = rest.get_guild_roles(guild_id).await?.iter()
+            .filter_map(|r| r["id"].as_str().map(String::from))
+            .collect();
+        let wanted: HashSet<&str> = entries.iter().filter_map(|e| e["id"].as_str()).collect();
+
+        for existing in db.list_whitelist(guild_id).await? {
+            if !wanted.contains(existing.target_id.as_str()) {
+                db.remove_whitelist(guild_id, &existing.target_id).await?;
+            }
+        }
+        for entry in entries {
+            let id = entry["id"].as_str().unwrap_or("");
+            let kind = entry["kind"].as_str().unwrap_or("user");
+            if kind == "role" && !roles.contains(id) {
+                warnings.push(format!("Whitelisted role `{}` does not exist in this server and was skipped.", id));
+                continue;
+            }
+            db.add_whitelist(guild_id, id, kind, entry["name"].as_str().unwrap_or(""), &strings(&entry["modules"])).await?;
+        }
+    }
+
+    if template.get("trusted_bots").is_some() {
+        let wanted = strings(&template["trusted_bots"]);
+        for bot_id in db.list_trusted_bots(guild_id).await? {
+            if !wanted.contains(&bot_id) {
+                db.remove_trusted_bot(guild_id, &bot_id).await?;
+            }
+        }
+        for bot_id in &wanted {
+            db.add_trusted_bot(guild_id, bot_id).await?;
+        }
+    }
+
+    info!("Applied antinuke template from guild {} to guild {} ({} warning(s)).", template["source_guild"].as_str().unwrap_or("unknown"), guild_id, warnings.len());
+    Ok(warnings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn known() -> HashMap<String, bool> {
+        ["anti_ban", "anti_kick", "anti_raid"].iter().map(|k| (k.to_string(), false)).collect()
+    }
+
+    fn template(body: Value) -> Value {
+        let mut doc = json!({ "kind": TEMPLATE_KIND, "version": TEMPLATE_VERSION });
+        for (key, value) in body.as_object().cloned().unwrap_or_default() {
+            doc[key] = value;
+        }
+        doc
+    }
+
+    fn entry(id: &str, kind: &str, modules: &[&str]) -> Value {
+        json!({ "id": id, "kind": kind, "modules": modules })
+    }
+
+    #[test]
+    fn validate_accepts_exported_shape() {
+        let doc = template(json!({
+            "modules": { "anti_ban": true, "anti_raid": false },
+            "monitor": ["*"],
+            "thresholds": { "anti_ban": { "max_actions": 3, "window_secs": 10 } },
+            "punishment": "ban",
+            "log_channel": null,
+            "owner_dm": true,
+            "whitelist": [entry("123", "user", &["anti_ban"])],
+            "trusted_bots": ["456"]
+        }));
+        assert!(validate(&doc, &known()).is_ok());
+    }
+
+    #[test]
+    fn validate_rejects_unknown_modules() {
+        let known = known();
+        let cases = [
+            json!({ "modules": { "anti_ban = 1; --": true } }),
+            json!({ "monitor": ["anti_everything"] }),
+            json!({ "thresholds": { "anti_everything": { "max_actions": 3, "window_secs": 10 } } }),
+            json!({ "whitelist": [entry("123", "user", &["anti_everything"])] }),
+        ];
+        for case in cases {
+            assert!(validate(&template(case.clone()), &known).is_err(), "accepted {}", case);
+        }
+    }
+
+    #[test]
+    fn validate_rejects_malformed_fields() {
+        let known = known();
+        assert!(validate(&json!({ "kind": "something_else", "version": 1 }), &known).is_err());
+        assert!(validate(&template(json!({ "version": TEMPLATE_VERSION + 1 })), &known).is_err());
+        assert!(validate(&template(json!({ "punishment": "explode" })), &known).is_err());
+        assert!(validate(&template(json!({ "log_channel": "general" })), &known).is_err());
+        assert!(validate(&template(json!({ "thresholds": { "anti_ban": { "max_actions": 0, "window_secs": 10 } } })), &known).is_err());
+        assert!(validate(&template(json!({ "whitelist": [entry("abc", "user", &["*"])] })), &known).is_err());
+        assert!(validate(&template(json!({ "whitelist": [entry("123", "channel", &["*"])] })), &known).is_err());
+        assert!(validate(&template(json!({ "trusted_bots": [123] })), &known).is_err());
+    }
+
+    #[test]
+    fn whitelist_diff_reports_added_changed_and_removed() {
+        let wanted = template(json!({
+            "whitelist": [
+                entry("1", "user", &["*"]),
+                entry("2", "role", &["anti_ban", "anti_kick"]),
+                entry("3", "user", &["anti_raid"])
+            ]
+        }));
+        let current = json!({
+            "whitelist": [
+                entry("2", "role", &["anti_ban"]),
+                entry("3", "user", &["anti_raid"]),
+                entry("5", "user", &["*"]),
+                entry("4", "user", &["*"])
+            ]
+        });
+
+        let diff = diff(&wanted, &current);
+        assert_eq!(diff.whitelist_added, vec!["1"]);
+        assert_eq!(diff.whitelist_changed, vec!["2"]);
+        assert_eq!(diff.whitelist_removed, vec!["4", "5"]);
+        assert!(!diff.is_empty());
+    }
+
+    #[test]
+    fn diff_reports_modules_thresholds_and_bots() {
+        let wanted = template(json!({
+            "modules": { "anti_ban": true, "anti_kick": false, "anti_raid": true },
+            "monitor": ["anti_raid"],
+            "thresholds": { "anti_ban": { "max_actions": 3, "window_secs": 10 } },
+            "owner_dm": false,
+            "trusted_bots": ["7", "8"]
+        }));
+        let current = json!({
+            "modules": { "anti_ban": false, "anti_kick": true, "anti_raid": true },
+            "monitor": [],
+            "thresholds": { "anti_kick": { "max_actions": 5, "window_secs": 60 } },
+            "owner_dm": true,
+            "trusted_bots": ["8", "9"]
+        });
+
+        let diff = diff(&wanted, &current);
+        assert_eq!(diff.enabled, vec!["anti_ban"]);
+        assert_eq!(diff.disabled, vec!["anti_kick"]);
+        assert_eq!(diff.monitor_added, vec!["anti_raid"]);
+        assert_eq!(diff.thresholds_changed, vec![
+            ("anti_ban".to_string(), "default".to_string(), "3/10s".to_string()),
+            ("anti_kick".to_string(), "5/60s".to_string(), "default".to_string()),
+        ]);
+        assert_eq!(diff.options_changed, vec![("owner_dm", "true".to_string(), "false".to_string())]);
+        assert_eq!(diff.bots_added, vec!["7"]);
+        assert_eq!(diff.bots_removed, vec!["9"]);
+    }
+
+    #[test]
+    fn matching_template_has_empty_diff() {
+        let doc = template(json!({
+            "modules": { "anti_ban": true },
+            "monitor": ["*"],
+            "thresholds": {},
+            "whitelist": [entry("1", "user", &["*"])],
+            "trusted_bots": ["7"]
+        }));
+        assert!(diff(&doc, &doc).is_empty());
+    }
+}
@@ -0,0 +1,160 @@
+use crate::db::Database;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tokio::sync::RwLock;
+use tokio::time::{interval, Duration};
+use tracing::{info, error};
+
+const EVICTION_INTERVAL_SECS: u64 = 60;
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct TrackerKey {
+    pub guild_id: String,
+    pub executor_id: String,
+    pub module: String,
+}
+
+impl TrackerKey {
+    pub fn new(guild_id: &str, executor_id: &str, module: &str) -> Self {
+        Self {
+            guild_id: guild_id.to_string(),
+            executor_id: executor_id.to_string(),
+            module: module.to_string(),
+        }
+    }
+}
+
+pub struct TrackedWindow {
+    pub window_secs: u64,
+    pub hits: VecDeque<i64>,
+}
+
+impl TrackedWindow {
+    fn prune(&mut self, now_ms: i64) {
+        let cutoff = now_ms - (self.window_secs * 1000) as i64;
+        while let Some(&t) = self.hits.front() {
+            if t <= cutoff {
+                self.hits.pop_front();
+            } else {
+                break;
+            }
+        }
+    }
+
+    pub fn expires_at(&self) -> i64 {
+        self.hits.back().copied().unwrap_or(0) + (self.window_secs * 1000) as i64
+    }
+}
+
+#[derive(Default)]
+pub struct RateTracker {
+    windows: RwLock<HashMap<TrackerKey, TrackedWindow>>,
+    persist: AtomicBool,
+}
+
+impl RateTracker {
+    pub fn is_persistent(&self) -> bool {
+        self.persist.load(Ordering::Relaxed)
+    }
+
+    pub fn enable_persistence(&self) {
+        self.persist.store(true, Ordering::Relaxed);
+    }
+
+    pub async fn hit(&self, key: &TrackerKey, window_secs: u64, now_ms: i64) -> (usize, i64, Vec<i64>) {
+        let mut windows = self.windows.write().await;
+        let window = windows.entry(key.clone()).or_insert_with(|| TrackedWindow { window_secs, hits: VecDeque::new() });
+        window.window_secs = window_secs;
+        window.prune(now_ms);
+        window.hits.push_back(now_ms);
+        (window.hits.len(), window.expires_at(), window.hits.iter().copied().collect())
+    }
+
+    pub async fn evict(&self, now_ms: i64) -> usize {
+        let mut windows = self.windows.write().await;
+        let before = windows.len();
+        windows.retain(|_, window| {
+            window.prune(now_ms);
+            !window.hits.is_empty()
+        });
+        before - windows.len()
+    }
+
+    pub async fn load(&self, entries: Vec<(TrackerKey, TrackedWindow)>) {
+        let mut windows = self.windows.write().await;
+        for (key, window) in entries {
+            windows.insert(key, window);
+        }
+    }
+}
+
+pub async fn run_eviction(db: Arc<Database>) {
+    let mut ticker = interval(Duration::from_secs(EVICTION_INTERVAL_SECS));
+    loop {
+        ticker.tick().await;
+        match db.evict_rate_tracker().await {
+            Ok(0) => {}
+            Ok(evicted) => info!("Rate tracker evicted {} idle window(s).", evicted),
+            Err(e) => error!("Failed to evict rate tracker windows: {:?}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(executor_id: &str) -> TrackerKey {
+        TrackerKey::new("1", executor_id, "anti_ban")
+    }
+
+    #[tokio::test]
+    async fn hits_inside_the_window_accumulate() {
+        let tracker = RateTracker::default();
+        assert_eq!(tracker.hit(&key("a"), 10, 1_000).await.0, 1);
+        assert_eq!(tracker.hit(&key("a"), 10, 5_000).await.0, 2);
+
+        let (count, expires_at, hits) = tracker.hit(&key("a"), 10, 9_000).await;
+        assert_eq!(count, 3);
+        assert_eq!(expires_at, 19_000);
+        assert_eq!(hits, vec![1_000, 5_000, 9_000]);
+
+        assert_eq!(tracker.hit(&key("b"), 10, 9_000).await.0, 1);
+    }
+
+    #[tokio::test]
+    async fn hits_older_than_the_window_are_dropped() {
+        let tracker = RateTracker::default();
+        tracker.hit(&key("a"), 10, 1_000).await;
+        tracker.hit(&key("a"), 10, 5_000).await;
+
+        let (count, _, hits) = tracker.hit(&key("a"), 10, 11_000).await;
+        assert_eq!(count, 2);
+        assert_eq!(hits, vec![5_000, 11_000]);
+
+        let (count, _, _) = tracker.hit(&key("a"), 2, 12_000).await;
+        assert_eq!(count, 2);
+    }
+
+    #[tokio::test]
+    async fn evict_removes_only_idle_windows() {
+        let tracker = RateTracker::default();
+        tracker.hit(&key("a"), 10, 1_000).await;
+        tracker.hit(&key("b"), 10, 8_000).await;
+
+        assert_eq!(tracker.evict(5_000).await, 0);
+        assert_eq!(tracker.evict(11_000).await, 1);
+        assert_eq!(tracker.hit(&key("b"), 10, 12_000).await.0, 2);
+        assert_eq!(tracker.evict(22_000).await, 1);
+        assert_eq!(tracker.hit(&key("a"), 10, 22_000).await.0, 1);
+    }
+
+    #[tokio::test]
+    async fn loaded_windows_continue_counting() {
+        let tracker = RateTracker::default();
+        tracker.load(vec![(key("a"), TrackedWindow { window_secs: 10, hits: VecDeque::from([1_000, 2_000]) })]).await;
+        assert_eq!(tracker.hit(&key("a"), 10, 3_000).await.0, 3);
+        assert!(!tracker.is_persistent());
+    }
+}
@@ -139,7 +139,7 @@ pub async fn handle_help_interaction(rest: &RestClient, interaction: &crate::mod
             let (title, content) = match category {
                 "help_cat_general" => (format!("{} General Commands", emojis::WRENCH), "`!ping`, `!info`, `!help`".to_string()),
                 "help_cat_fun" => (format!("{} Fun Commands", emojis::DICE), "`!8ball`, `!roll`, `!coinflip`".to_string()),
-                "help_cat_security" => (format!("{} Security Commands", emojis::SHIELD), "`!security`, `!whitelist`, `!admin`".to_string()),
+                "help_cat_security" => (format!("{} Security Commands", emojis::SHIELD), "`!security`, `!whitelist`, `!admin`, `!nightmode`, `!backup`".to_string()),
                 "help_cat_moderation" => (
                     format!("{} Moderation Commands", emojis::HAMMER),
                     "**Commands:**\n\
@@ -162,6 +162,9 @@ pub async fn handle_help_interaction(rest: &RestClient, interaction: &crate::mod
                     `!unmute` (`untimeout`) - Remove timeout from a member\n\
                     `!unmuteall` - Unmute all muted members in the server\n\
                     `!lockall` - Lock all channels in the server\n\
+                    `!lockdown` - Emergency server lockdown (`on [reason]`, `off`, `auto`)\n\
+                    `!quarantine` (`qt`) - Strip a member's roles and hide every channel from them (`list`, `sync`)\n\
+                    `!unquarantine` (`unqt`) - Release a member and restore their saved roles\n\
                     `!unban` - Unban a user from the server\n\
                     `!unlockall` - Unlock all channels in the server\n\n\
                     **🔧 Commands with Subcommands:**\n\
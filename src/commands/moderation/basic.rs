@@ -26,7 +26,7 @@ pub async fn handle_basic(
 
     if required_perm > 0 {
         let has_perm = rest.has_permission(guild_id, &msg.author.id, required_perm).await.unwrap_or(false);
-        let is_bot_admin = _db.is_admin(&msg.author.id).await.unwrap_or(false);
+        let is_bot_admin = _db.is_admin(guild_id, &msg.author.id).await.unwrap_or(false);
 
         if !has_perm && !is_bot_admin {
             rest.send_message(&msg.channel_id, &format!("{} Permission Denied: You do not have the required Discord server permissions to run this command.", emojis::ERROR)).await?;
@@ -206,7 +206,7 @@ pub async fn handle_basic(
         }
         "slowmode" => {
             let limit: u16 = target_id.parse().unwrap_or(0); 
-            if let Err(e) = rest.modify_channel(&msg.channel_id, limit).await {
+            if let Err(e) = rest.modify_channel(&msg.channel_id, json!({ "rate_limit_per_user": limit }), &format!("Requested by {}", msg.author.username)).await {
                 rest.send_message(&msg.channel_id, &format!("{} Failed to set slowmode: {:?}", emojis::ERROR, e)).await?;
             } else {
                 if limit == 0 {
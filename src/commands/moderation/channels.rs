@@ -16,7 +16,7 @@ pub async fn handle_channels(
 
     let required_perm: u64 = 1 << 4;
     let has_perm = rest.has_permission(guild_id, &msg.author.id, required_perm).await.unwrap_or(false);
-    let is_bot_admin = db.is_admin(&msg.author.id).await.unwrap_or(false);
+    let is_bot_admin = db.is_admin(guild_id, &msg.author.id).await.unwrap_or(false);
 
     if !has_perm && !is_bot_admin {
         rest.send_message(&msg.channel_id, &format!("{} Permission Denied: You need the `Manage Channels` permission to use this command.", emojis::ERROR)).await?;
@@ -0,0 +1,100 @@
+use crate::models::Message;
+use crate::rest::RestClient;
+use crate::db::Database;
+use crate::constants::{emojis, permissions};
+use crate::antinuke::lockdown;
+use std::sync::Arc;
+use serde_json::json;
+
+pub async fn handle_lockdown(
+    rest: &RestClient,
+    msg: &Message,
+    db: Arc<Database>,
+    args: &str,
+) -> anyhow::Result<()> {
+    let guild_id = msg.guild_id.as_deref().unwrap_or("");
+    if guild_id.is_empty() { return Ok(()); }
+
+    let has_perm = rest.has_permission(guild_id, &msg.author.id, permissions::ADMINISTRATOR).await.unwrap_or(false);
+    let is_bot_admin = db.is_admin(guild_id, &msg.author.id).await.unwrap_or(false);
+
+    if !has_perm && !is_bot_admin {
+        rest.send_message(&msg.channel_id, &format!("{} Permission Denied: You need the `Administrator` permission to use this command.", emojis::ERROR)).await?;
+        return Ok(());
+    }
+
+    let parts: Vec<&str> = args.split_whitespace().collect();
+    let sub_cmd = parts.first().map(|s| s.to_lowercase()).unwrap_or_default();
+
+    match sub_cmd.as_str() {
+        "on" | "start" => {
+            if lockdown::is_active(guild_id, &db).await {
+                rest.send_message(&msg.channel_id, &format!("{} Lockdown is already active. Use `!lockdown off` to lift it.", emojis::INFO)).await?;
+                return Ok(());
+            }
+
+            let reason = if parts.len() > 1 { parts[1..].join(" ") } else { format!("Requested by {}", msg.author.username) };
+            rest.send_message(&msg.channel_id, &format!("{} Engaging lockdown. This may take a minute...", emojis::LOADING)).await?;
+
+            if let Err(e) = lockdown::engage(guild_id, &reason, Some(&msg.channel_id), rest, &db).await {
+                rest.send_message(&msg.channel_id, &format!("{} Failed to engage lockdown: {}", emojis::ERROR, e)).await?;
+            }
+        }
+        "off" | "end" | "lift" => {
+            if !lockdown::is_active(guild_id, &db).await {
+                rest.send_message(&msg.channel_id, &format!("{} Lockdown is not active.", emojis::INFO)).await?;
+                return Ok(());
+            }
+
+            if let Err(e) = lockdown::lift(guild_id, Some(&msg.channel_id), rest, &db).await {
+                rest.send_message(&msg.channel_id, &format!("{} Failed to lift lockdown: {}", emojis::ERROR, e)).await?;
+            }
+        }
+        "auto" => {
+            let enable = match parts.get(1).map(|s| s.to_lowercase()).as_deref() {
+                Some("on") | Some("enable") => true,
+                Some("off") | Some("disable") => false,
+                _ => {
+                    rest.send_message(&msg.channel_id, &format!("{} Usage: `!lockdown auto <on|off>`", emojis::ERROR)).await?;
+                    return Ok(());
+                }
+            };
+            db.update_antinuke_setting(guild_id, "auto_lockdown", enable).await?;
+            rest.send_message(&msg.channel_id, &format!(
+                "{} Automatic lockdown on severe antinuke incidents is now **{}**.",
+                emojis::SUCCESS,
+                if enable { "enabled" } else { "disabled" }
+            )).await?;
+        }
+        _ => {
+            let state = db.get_snapshot(guild_id, "lockdown", "state").await.ok().flatten();
+            let auto = db.get_antinuke_settings(guild_id).await
+                .map(|s| *s.get("auto_lockdown").unwrap_or(&false))
+                .unwrap_or(false);
+
+            let status = match &state {
+                Some(s) => format!(
+                    "**Active** since {}\n**Reason:** {}",
+                    s["started_at"].as_str().unwrap_or("unknown"),
+                    s["reason"].as_str().unwrap_or("None")
+                ),
+                None => "Inactive".to_string(),
+            };
+
+            rest.send_embed(&msg.channel_id, json!({
+                "title": format!("{} Server Lockdown", emojis::LOCK),
+                "description": format!(
+                    "**Status:** {}\n**Auto Lockdown:** {}\n\n\
+                    `!lockdown on [reason]` - Lock channels, raise verification, pause invites and timeout recent joiners\n\
+                    `!lockdown off` - Restore the pre-lockdown state\n\
+                    `!lockdown auto <on|off>` - Engage automatically on severe antinuke incidents",
+                    status,
+                    if auto { "ON" } else { "OFF" }
+                ),
+                "color": 0x5865F2
+            })).await?;
+        }
+    }
+
+    Ok(())
+}
@@ -1,6 +1,8 @@
 pub mod basic;
 pub mod channels;
+pub mod lockdown;
 pub mod purge;
+pub mod quarantine;
 pub mod roles_lists;
 pub mod system;
 
@@ -26,6 +28,14 @@ pub async fn handle_command(
             channels::handle_channels(rest, msg, db, cmd, args).await
         }
 
+        "lockdown" => {
+            lockdown::handle_lockdown(rest, msg, db, args).await
+        }
+
+        "quarantine" | "qt" | "unquarantine" | "unqt" => {
+            quarantine::handle_quarantine(rest, msg, db, cmd, args).await
+        }
+
         "purge" | "clear" | "p" | "c" => {
             purge::handle_purge(rest, msg, db, cmd, args).await
         }
@@ -0,0 +1,118 @@
+use crate::models::Message;
+use crate::rest::RestClient;
+use crate::db::Database;
+use crate::constants::{colors, emojis, permissions};
+use crate::antinuke::quarantine;
+use std::sync::Arc;
+use serde_json::json;
+
+pub async fn handle_quarantine(
+    rest: &RestClient,
+    msg: &Message,
+    db: Arc<Database>,
+    cmd: &str,
+    args: &str,
+) -> anyhow::Result<()> {
+    let guild_id = msg.guild_id.as_deref().unwrap_or("");
+    if guild_id.is_empty() { return Ok(()); }
+
+    let has_perm = rest.has_permission(guild_id, &msg.author.id, permissions::MANAGE_ROLES).await.unwrap_or(false);
+    let is_bot_admin = db.is_admin(guild_id, &msg.author.id).await.unwrap_or(false);
+    if !has_perm && !is_bot_admin {
+        rest.send_message(&msg.channel_id, &format!("{} Permission Denied: You need the `Manage Roles` permission to use this command.", emojis::ERROR)).await?;
+        return Ok(());
+    }
+
+    let parts: Vec<&str> = args.split_whitespace().collect();
+    let first = parts.first().copied().unwrap_or("");
+    let target_id = first.trim_matches(&['<', '@', '>', '!'][..]);
+    let reason = if parts.len() > 1 { parts[1..].join(" ") } else { "No reason provided".to_string() };
+
+    if cmd == "unquarantine" || cmd == "unqt" {
+        if target_id.is_empty() {
+            rest.send_message(&msg.channel_id, &format!("{} Usage: `!unquarantine @user`", emojis::ERROR)).await?;
+            return Ok(());
+        }
+        match quarantine::release(guild_id, target_id, rest, &db).await {
+            Ok(restored) => {
+                rest.send_embed(&msg.channel_id, json!({
+                    "title": format!("{} Member Released", emojis::UNLOCK),
+                    "description": format!("**Target:** <@{}>\n**Roles Restored:** {}\n**Moderator:** <@{}>", target_id, restored, msg.author.id),
+                    "color": colors::GREEN
+                })).await?;
+            }
+            Err(e) => {
+                rest.send_message(&msg.channel_id, &format!("{} Failed to release <@{}>: {}", emojis::ERROR, target_id, e)).await?;
+            }
+        }
+        return Ok(());
+    }
+
+    match first.to_lowercase().as_str() {
+        "" => {
+            rest.send_message(&msg.channel_id, &format!("{} Usage: `!quarantine @user [reason]`, `!quarantine list` or `!quarantine sync`", emojis::ERROR)).await?;
+        }
+        "list" => {
+            let members = db.list_quarantined(guild_id).await?;
+            let mut description = match db.get_quarantine_role(guild_id).await {
+                Some(role_id) => format!("**Quarantine Role:** <@&{}>\n\n", role_id),
+                None => "**Quarantine Role:** Not created yet\n\n".to_string(),
+            };
+            if members.is_empty() {
+                description.push_str("No members are quarantined.");
+            }
+            for (user_id, reason, at) in members.iter().take(25) {
+                let when = chrono::DateTime::parse_from_rfc3339(at)
+                    .map(|t| format!("<t:{}:R>", t.timestamp()))
+                    .unwrap_or_default();
+                description.push_str(&format!("<@{}> {} — {}\n", user_id, when, reason));
+            }
+            rest.send_embed(&msg.channel_id, json!({
+                "title": format!("{} Quarantined Members", emojis::LOCK),
+                "description": description,
+                "color": colors::MAIN
+            })).await?;
+        }
+        "sync" => {
+            let role_id = quarantine::ensure_role(guild_id, rest, &db).await?;
+            let (updated, failed) = quarantine::sync_channels(guild_id, &role_id, rest).await?;
+            let mut text = format!("{} Quarantine role <@&{}> is hidden from every channel ({} updated).", emojis::SUCCESS, role_id, updated);
+            if failed > 0 {
+                text.push_str(&format!("\n{} **{}** channel(s) could not be updated. Check my permissions there.", emojis::WARNING, failed));
+            }
+            rest.send_message(&msg.channel_id, &text).await?;
+        }
+        _ => {
+            let is_owner = rest.get_guild(guild_id).await
+                .map(|g| g["owner_id"].as_str() == Some(target_id))
+                .unwrap_or(false);
+            if is_owner || target_id == msg.author.id {
+                rest.send_message(&msg.channel_id, &format!("{} You cannot quarantine that member.", emojis::ERROR)).await?;
+                return Ok(());
+            }
+
+            match quarantine::apply(guild_id, target_id, &reason, &msg.author.id, rest, &db).await {
+                Ok(saved) => {
+                    rest.send_embed(&msg.channel_id, json!({
+                        "title": format!("{} Member Quarantined", emojis::LOCK),
+                        "description": format!(
+                            "**Target:** <@{}>\n**Reason:** {}\n**Roles Saved:** {}\n**Moderator:** <@{}>\n\nUse `!unquarantine <@{}>` to restore their roles.",
+                            target_id, reason, saved, msg.author.id, target_id
+                        ),
+                        "color": colors::MAIN
+                    })).await?;
+                }
+                Err(e) => {
+                    let err_str = e.to_string();
+                    if err_str.contains("403") {
+                        rest.send_message(&msg.channel_id, &format!("{} **Failed to quarantine:** I cannot edit this member's roles. Ensure my role is higher than theirs.", emojis::ERROR)).await?;
+                    } else {
+                        rest.send_message(&msg.channel_id, &format!("{} Failed to quarantine user: {}", emojis::ERROR, e)).await?;
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -26,7 +26,7 @@ pub async fn handle_system(
 
     if required_perm > 0 {
         let has_perm = rest.has_permission(guild_id, &msg.author.id, required_perm).await.unwrap_or(false);
-        let is_bot_admin = db.is_admin(&msg.author.id).await.unwrap_or(false);
+        let is_bot_admin = db.is_admin(guild_id, &msg.author.id).await.unwrap_or(false);
 
         if !has_perm && !is_bot_admin {
             let perm_name = if required_perm == (1 << 40) { "Timeout Members" } else { "Manage Server" };
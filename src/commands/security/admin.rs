@@ -13,14 +13,17 @@ pub async fn handle_admin(
     args: &str,
 ) -> anyhow::Result<()> {
 
-    let is_bot_admin = db.is_admin(&msg.author.id).await.unwrap_or(false);
+    let guild_id = match msg.guild_id.as_deref() {
+        Some(id) => id,
+        None => return Ok(()),
+    };
+
+    let is_bot_admin = db.is_admin(guild_id, &msg.author.id).await.unwrap_or(false);
     let mut is_owner = false;
 
-    if let Some(guild_id) = &msg.guild_id {
-        if let Ok(guild) = rest.get_guild(guild_id).await {
-            if let Some(owner_id) = guild["owner_id"].as_str() {
-                is_owner = owner_id == msg.author.id;
-            }
+    if let Ok(guild) = rest.get_guild(guild_id).await {
+        if let Some(owner_id) = guild["owner_id"].as_str() {
+            is_owner = owner_id == msg.author.id;
         }
     }
 
@@ -44,8 +47,8 @@ pub async fn handle_admin(
                 rest.send_message(&msg.channel_id, &format!("{} Please mention a valid user to add as admin.", emojis::ERROR)).await?;
                 return Ok(());
             }
-            db.add_admin(user_id, target).await?;
-            rest.send_message(&msg.channel_id, &format!("{} Added <@{}> to the admin list.", emojis::SUCCESS, user_id)).await?;
+            db.add_admin(guild_id, user_id, target).await?;
+            rest.send_message(&msg.channel_id, &format!("{} Added <@{}> to the admin list for this server.", emojis::SUCCESS, user_id)).await?;
         }
         "remove" => {
             if !is_owner {
@@ -62,13 +65,13 @@ pub async fn handle_admin(
                 rest.send_message(&msg.channel_id, &format!("{} You cannot remove yourself from the admin list.", emojis::ERROR)).await?;
                 return Ok(());
             }
-            db.remove_admin(user_id).await?;
-            rest.send_message(&msg.channel_id, &format!("{} Removed <@{}> from the admin list.", emojis::SUCCESS, user_id)).await?;
+            db.remove_admin(guild_id, user_id).await?;
+            rest.send_message(&msg.channel_id, &format!("{} Removed <@{}> from the admin list for this server.", emojis::SUCCESS, user_id)).await?;
         }
         "list" => {
-            let list = db.list_admins().await?;
+            let list = db.list_admins(guild_id).await?;
             if list.is_empty() {
-                rest.send_message(&msg.channel_id, &format!("{} No admins configured.", emojis::INFO)).await?;
+                rest.send_message(&msg.channel_id, &format!("{} No admins configured for this server.", emojis::INFO)).await?;
             } else {
                 let mut content = format!("{} **Admin Users:**\n", emojis::WRENCH);
                 for (id, name) in list {
@@ -2,8 +2,9 @@ use crate::models::{Interaction, Message};
 use crate::rest::RestClient;
 use crate::db::Database;
 use crate::constants::{emojis, colors};
+use crate::commands::security::whitelist::extract_id;
 use std::sync::Arc;
-use serde_json::json;
+use serde_json::{json, Value};
 
 pub async fn handle_antinuke(
     rest: &RestClient,
@@ -13,14 +14,17 @@ pub async fn handle_antinuke(
     args: &str,
 ) -> anyhow::Result<()> {
 
-    let is_bot_admin = db.is_admin(&msg.author.id).await.unwrap_or(false);
+    let guild_id = match msg.guild_id.as_deref() {
+        Some(id) => id,
+        None => return Ok(()),
+    };
+
+    let is_bot_admin = db.is_admin(guild_id, &msg.author.id).await.unwrap_or(false);
     let mut is_owner = false;
 
-    if let Some(guild_id) = &msg.guild_id {
-        if let Ok(guild) = rest.get_guild(guild_id).await {
-            if let Some(owner_id) = guild["owner_id"].as_str() {
-                is_owner = owner_id == msg.author.id;
-            }
+    if let Ok(guild) = rest.get_guild(guild_id).await {
+        if let Some(owner_id) = guild["owner_id"].as_str() {
+            is_owner = owner_id == msg.author.id;
         }
     }
 
@@ -40,7 +44,7 @@ pub async fn handle_antinuke(
             show_config_menu(rest, msg, &bot_avatar).await
         }
         "enable" | "activate" | "on" => {
-            show_enable_sequence(rest, msg, db, &bot_avatar).await
+            super::setup::show_setup_wizard(rest, msg, db).await
         }
         "disable" | "deactivate" | "off" => {
             show_disable_sequence(rest, msg, db, &bot_avatar).await
@@ -48,121 +52,320 @@ pub async fn handle_antinuke(
         "settings" | "status" | "info" => {
             show_settings(rest, msg, db, &bot_avatar).await
         }
+        "bots" | "trustedbots" => {
+            manage_trusted_bots(rest, msg, db, &parts[1..]).await
+        }
+        "punishment" | "punish" => {
+            set_punishment(rest, msg, db, &parts[1..]).await
+        }
+        "threshold" | "limit" => {
+            set_threshold(rest, msg, db, &parts[1..]).await
+        }
+        "raid" | "antiraid" => {
+            set_raid_action(rest, msg, db, &parts[1..]).await
+        }
+        "incidents" | "incident" | "history" => {
+            super::incidents::handle_incidents(rest, msg, db, &parts[1..]).await
+        }
+        "rollback" | "unbanback" => {
+            set_ban_rollback(rest, msg, db, &parts[1..]).await
+        }
+        "log" | "logs" | "logchannel" => {
+            set_log_channel(rest, msg, db, &parts[1..]).await
+        }
+        "audit" | "health" | "check" => {
+            show_audit(rest, msg, db).await
+        }
+        "export" => {
+            super::template::export_config(rest, msg, db).await
+        }
+        "import" => {
+            super::template::import_config(rest, msg, db).await
+        }
+        "ownerdm" | "dm" => {
+            set_owner_dm(rest, msg, db, &parts[1..]).await
+        }
         "" => {
             show_dashboard(rest, msg, &bot_avatar).await
         }
         _ => {
-            rest.send_message(&msg.channel_id, &format!("{} Unknown subcommand. Try `config`, `enable`, `settings`.", emojis::ERROR)).await?;
+            rest.send_message(&msg.channel_id, &format!("{} Unknown subcommand. Try `config`, `enable`, `settings`, `bots`, `punishment`, `threshold`, `raid`, `log`, `ownerdm`, `incidents`, `rollback`, `audit`, `export`, `import`.", emojis::ERROR)).await?;
             Ok(())
         }
     }
 }
 
-async fn show_enable_sequence(rest: &RestClient, msg: &Message, db: Arc<Database>, bot_avatar: &str) -> anyhow::Result<()> {
+async fn manage_trusted_bots(rest: &RestClient, msg: &Message, db: Arc<Database>, parts: &[&str]) -> anyhow::Result<()> {
     let guild_id = msg.guild_id.as_deref().unwrap_or("");
+    if guild_id.is_empty() { return Ok(()); }
+
+    let action = parts.first().map(|s| s.to_lowercase()).unwrap_or_default();
+    let target = parts.get(1).unwrap_or(&"");
+    let bot_id = match extract_id(target) {
+        "" if target.chars().all(|c| c.is_ascii_digit()) => *target,
+        id => id,
+    };
+
+    match action.as_str() {
+        "add" => {
+            if bot_id.is_empty() {
+                rest.send_message(&msg.channel_id, &format!("{} Usage: `!antinuke bots add <bot_id>`", emojis::ERROR)).await?;
+                return Ok(());
+            }
+            db.add_trusted_bot(guild_id, bot_id).await?;
+            rest.send_message(&msg.channel_id, &format!("{} Bot `{}` can now be added without triggering Anti Bot.", emojis::SUCCESS, bot_id)).await?;
+        }
+        "remove" => {
+            if bot_id.is_empty() {
+                rest.send_message(&msg.channel_id, &format!("{} Usage: `!antinuke bots remove <bot_id>`", emojis::ERROR)).await?;
+                return Ok(());
+            }
+            if db.remove_trusted_bot(guild_id, bot_id).await? > 0 {
+                rest.send_message(&msg.channel_id, &format!("{} Bot `{}` removed from the trusted bot list.", emojis::SUCCESS, bot_id)).await?;
+            } else {
+                rest.send_message(&msg.channel_id, &format!("{} Bot `{}` is not on the trusted bot list.", emojis::ERROR, bot_id)).await?;
+            }
+        }
+        "list" | "" => {
+            let list = db.list_trusted_bots(guild_id).await?;
+            if list.is_empty() {
+                rest.send_message(&msg.channel_id, &format!("{} No trusted bots configured. Use `!antinuke bots add <bot_id>`.", emojis::INFO)).await?;
+            } else {
+                let mut content = format!("{} **Trusted Bots:**\n", emojis::ROBOT);
+                for id in list {
+                    content.push_str(&format!("• <@{}> (`{}`)\n", id, id));
+                }
+                rest.send_message(&msg.channel_id, &content).await?;
+            }
+        }
+        _ => {
+            rest.send_message(&msg.channel_id, &format!("{} Unknown action. Try `add`, `remove`, `list`.", emojis::ERROR)).await?;
+        }
+    }
+    Ok(())
+}
 
-    let mut lines = vec!["✅ | Initializing Quick Setup!"];
-    let mut embed = json!({
-        "description": lines.join("\n"),
-        "color": colors::MAIN
-    });
-    let initial_msg = rest.send_complex_message(&msg.channel_id, "", vec![embed], vec![]).await?;
-    let msg_id = initial_msg["id"].as_str().unwrap_or("");
-
-    tokio::time::sleep(std::time::Duration::from_millis(800)).await;
+async fn prepare_quarantine(rest: &RestClient, msg: &Message, db: &Database) -> anyhow::Result<bool> {
+    let guild_id = msg.guild_id.as_deref().unwrap_or("");
+    match crate::antinuke::quarantine::ensure_role(guild_id, rest, db).await {
+        Ok(_) => Ok(true),
+        Err(e) => {
+            rest.send_message(&msg.channel_id, &format!("{} Could not set up the quarantine role: {}", emojis::ERROR, e)).await?;
+            Ok(false)
+        }
+    }
+}
 
-    lines.push("✅ | **INITIALIZING** Permission Verification Protocol...");
-    embed = json!({ "description": lines.join("\n"), "color": colors::MAIN });
-    rest.edit_message(&msg.channel_id, msg_id, "", vec![embed], vec![]).await?;
+async fn set_punishment(rest: &RestClient, msg: &Message, db: Arc<Database>, parts: &[&str]) -> anyhow::Result<()> {
+    let guild_id = msg.guild_id.as_deref().unwrap_or("");
+    if guild_id.is_empty() { return Ok(()); }
 
-    tokio::time::sleep(std::time::Duration::from_millis(800)).await;
+    let choice = parts.first().map(|s| s.to_lowercase()).unwrap_or_default();
+    match choice.as_str() {
+        "ban" | "kick" | "timeout" | "strip" | "quarantine" => {
+            if choice == "quarantine" && !prepare_quarantine(rest, msg, &db).await? {
+                return Ok(());
+            }
+            db.set_punishment(guild_id, &choice).await?;
+            rest.send_message(&msg.channel_id, &format!("{} Antinuke punishment set to **{}**.", emojis::SUCCESS, choice)).await?;
+        }
+        "" => {
+            let current = db.get_punishment(guild_id).await;
+            rest.send_message(&msg.channel_id, &format!("{} Current punishment: **{}**\nUse `!antinuke punishment <ban|kick|timeout|strip|quarantine>` to change it.", emojis::INFO, current)).await?;
+        }
+        _ => {
+            rest.send_message(&msg.channel_id, &format!("{} Invalid punishment. Choose `ban`, `kick`, `timeout`, `strip` or `quarantine`.", emojis::ERROR)).await?;
+        }
+    }
+    Ok(())
+}
 
-    lines.push("✅ | **ANALYZING** Role Hierarchy Configuration...");
-    embed = json!({ "description": lines.join("\n"), "color": colors::MAIN });
-    rest.edit_message(&msg.channel_id, msg_id, "", vec![embed], vec![]).await?;
+async fn set_threshold(rest: &RestClient, msg: &Message, db: Arc<Database>, parts: &[&str]) -> anyhow::Result<()> {
+    let guild_id = msg.guild_id.as_deref().unwrap_or("");
+    if guild_id.is_empty() { return Ok(()); }
 
-    tokio::time::sleep(std::time::Duration::from_millis(800)).await;
+    let module = parts.first().map(|s| s.to_lowercase()).unwrap_or_default();
+    let module = if module.starts_with("anti_") || module.is_empty() { module } else { format!("anti_{}", module) };
+    let settings = db.get_antinuke_settings(guild_id).await?;
 
-    lines.push("✅ | **ENGINEERING** Rimuru Impenetrable Power Role...");
-    embed = json!({ "description": lines.join("\n"), "color": colors::MAIN });
-    rest.edit_message(&msg.channel_id, msg_id, "", vec![embed], vec![]).await?;
+    if !settings.contains_key(&module) {
+        rest.send_message(&msg.channel_id, &format!("{} Usage: `!antinuke threshold <module> <count> [window_secs]` (e.g. `!antinuke threshold everyone_ping 3 10`)", emojis::ERROR)).await?;
+        return Ok(());
+    }
 
-    if let Ok(role) = rest.create_role(guild_id, "Rimuru Absolute Authority", 0x57F287, true, "8").await {
-        if let Some(role_id) = role.get("id").and_then(|v| v.as_str()) {
-            if let Ok(bot) = rest.validate_token().await {
+    let count = parts.get(1).and_then(|s| s.parse::<u32>().ok());
+    let window = parts.get(2).and_then(|s| s.parse::<u64>().ok());
 
-                let _ = rest.add_member_role(guild_id, &bot.id, role_id).await;
+    match count {
+        Some(c) if c > 0 => {
+            let (_, current_window) = current_threshold(guild_id, &module, &db).await;
+            let window = window.filter(|w| *w > 0).unwrap_or(current_window);
+            db.set_threshold(guild_id, &module, c, window).await?;
+            rest.send_message(&msg.channel_id, &format!("{} `{}` now triggers after **{}** action(s) within **{}s**.", emojis::SUCCESS, module, c, window)).await?;
+        }
+        _ => {
+            let (c, w) = current_threshold(guild_id, &module, &db).await;
+            rest.send_message(&msg.channel_id, &format!("{} `{}` triggers after **{}** action(s) within **{}s**.", emojis::INFO, module, c, w)).await?;
+        }
+    }
+    Ok(())
+}
 
-                if let Ok(roles) = rest.get_guild_roles(guild_id).await {
-                    if let Ok(bot_member) = rest.get_guild_member(guild_id, &bot.id).await {
-                        let bot_role_ids: Vec<&str> = bot_member["roles"]
-                            .as_array()
-                            .map(|arr| arr.iter().filter_map(|v| v.as_str()).collect())
-                            .unwrap_or_default();
+async fn current_threshold(guild_id: &str, module: &str, db: &Database) -> (u32, u64) {
+    if module == "anti_raid" {
+        db.get_threshold_or(guild_id, module, crate::antinuke::raid::DEFAULT_RAID_THRESHOLD).await
+    } else {
+        db.get_threshold(guild_id, module).await
+    }
+}
 
-                        let mut max_bot_pos = 0;
-                        for r in &roles {
-                            if let (Some(id), Some(pos)) = (r["id"].as_str(), r["position"].as_u64()) {
-                                if bot_role_ids.contains(&id) && pos > max_bot_pos {
-                                    max_bot_pos = pos;
-                                }
-                            }
-                        }
+async fn set_raid_action(rest: &RestClient, msg: &Message, db: Arc<Database>, parts: &[&str]) -> anyhow::Result<()> {
+    let guild_id = msg.guild_id.as_deref().unwrap_or("");
+    if guild_id.is_empty() { return Ok(()); }
 
-                        let target_pos = if max_bot_pos > 0 { max_bot_pos - 1 } else { 1 };
-                        let _ = rest.modify_role_positions(guild_id, role_id, target_pos).await;
-                    }
-                }
+    let choice = parts.first().map(|s| s.to_lowercase()).unwrap_or_default();
+    match choice.as_str() {
+        "kick" | "ban" | "timeout" | "quarantine" | "lockdown" => {
+            if choice == "quarantine" && !prepare_quarantine(rest, msg, &db).await? {
+                return Ok(());
             }
+            db.set_raid_action(guild_id, &choice).await?;
+            rest.send_message(&msg.channel_id, &format!("{} Raid cohorts will now be handled with **{}**.", emojis::SUCCESS, choice)).await?;
+        }
+        "" => {
+            let current = db.get_raid_action(guild_id).await;
+            let (count, window) = current_threshold(guild_id, "anti_raid", &db).await;
+            rest.send_message(&msg.channel_id, &format!(
+                "{} Raid response: **{}** after **{}** suspicious join(s) within **{}s**. Usage: `!antinuke raid <kick|ban|timeout|quarantine|lockdown>`",
+                emojis::INFO, current, count, window
+            )).await?;
+        }
+        _ => {
+            rest.send_message(&msg.channel_id, &format!("{} Usage: `!antinuke raid <kick|ban|timeout|quarantine|lockdown>`", emojis::ERROR)).await?;
         }
     }
+    Ok(())
+}
 
-    tokio::time::sleep(std::time::Duration::from_millis(1200)).await;
+async fn set_ban_rollback(rest: &RestClient, msg: &Message, db: Arc<Database>, parts: &[&str]) -> anyhow::Result<()> {
+    let guild_id = msg.guild_id.as_deref().unwrap_or("");
+    if guild_id.is_empty() { return Ok(()); }
+
+    let choice = parts.first().map(|s| s.to_lowercase()).unwrap_or_default();
+    match choice.as_str() {
+        "auto" | "confirm" | "off" => {
+            db.set_ban_rollback(guild_id, &choice).await?;
+            let description = match choice.as_str() {
+                "auto" => "Members banned by a nuker will be **unbanned automatically**.",
+                "confirm" => "Members banned by a nuker will be unbanned **after owner confirmation**.",
+                _ => "Mass-ban rollback is **disabled**.",
+            };
+            rest.send_message(&msg.channel_id, &format!("{} {}", emojis::SUCCESS, description)).await?;
+        }
+        "" => {
+            let current = db.get_ban_rollback(guild_id).await;
+            rest.send_message(&msg.channel_id, &format!(
+                "{} Mass-ban rollback: **{}**. Usage: `!antinuke rollback <auto|confirm|off>`",
+                emojis::INFO, current
+            )).await?;
+        }
+        _ => {
+            rest.send_message(&msg.channel_id, &format!("{} Usage: `!antinuke rollback <auto|confirm|off>`", emojis::ERROR)).await?;
+        }
+    }
+    Ok(())
+}
 
-    db.bulk_update_antinuke(guild_id, true).await?;
+async fn show_audit(rest: &RestClient, msg: &Message, db: Arc<Database>) -> anyhow::Result<()> {
+    let guild_id = msg.guild_id.as_deref().unwrap_or("");
+    if guild_id.is_empty() { return Ok(()); }
 
-    let guild_name = "Whiskey's server"; 
-    let final_embed = json!({
-        "title": "🛡️ RIMURU Security",
-        "description": format!(
-            "**Security Settings For {} 🛡️**\n\n\
-            Note: For maximum security efficiency, ensure my role maintains the highest position in the role hierarchy.\n\n\
-            **Modules Enabled 🛡️**\n\
-            Anti Ban: ✅\n\
-            Anti Unban: ✅\n\
-            Anti Kick: ✅\n\
-            Anti Bot: ✅\n\
-            Anti Channel Create: ✅\n\
-            Anti Channel Delete: ✅\n\
-            Anti Channel Update: ✅\n\
-            Anti Emoji/Sticker Create: ✅\n\
-            Anti Emoji/Sticker Delete: ✅\n\
-            Anti Emoji/Sticker Update: ✅\n\
-            Anti Everyone/Here Ping: ✅\n\
-            Anti Link Role: ✅\n\
-            Anti Role Create: ✅\n\
-            Anti Role Delete: ✅\n\
-            Anti Role Update: ✅\n\
-            Anti Role Ping: ✅\n\
-            Anti Member Update: ✅\n\
-            Anti Integration: ✅\n\
-            Anti Server Update: ✅\n\
-            Anti Automod Rule Create: ✅\n\
-            Anti Automod Rule Update: ✅\n\
-            Anti Automod Rule Delete: ✅\n\
-            Anti Guild Event Create: ✅\n\
-            Anti Guild Event Update: ✅\n\
-            Anti Guild Event Delete: ✅\n\
-            Anti Webhook: ✅\n\n\
-            Anti Prune: ✅",
-            guild_name
-        ),
-        "color": colors::MAIN,
-        "thumbnail": { "url": bot_avatar },
-        "footer": { "text": "Punishment Type: Ban" }
-    });
+    let findings = crate::antinuke::audit::run(guild_id, rest, &db).await?;
+    let issues = findings.iter().filter(|f| !f.ok).count();
 
-    rest.edit_message(&msg.channel_id, msg_id, "", vec![final_embed], vec![]).await?;
+    let fields: Vec<Value> = findings.iter().map(|f| {
+        let mut value = if f.details.is_empty() { "No issues found.".to_string() } else { f.details.join("\n") };
+        if let Some(fix) = &f.fix {
+            value.push_str(&format!("\n**Fix:** {}", fix));
+        }
+        if value.chars().count() > 1024 {
+            value = value.chars().take(1023).collect::<String>() + "…";
+        }
+        json!({
+            "name": format!("{} {}", if f.ok { emojis::SUCCESS } else { emojis::WARNING }, f.check),
+            "value": value,
+            "inline": false
+        })
+    }).collect();
+
+    rest.send_embed(&msg.channel_id, json!({
+        "title": format!("{} Security Audit", emojis::SHIELD),
+        "description": if issues == 0 {
+            "Every check passed. This server is in good shape.".to_string()
+        } else {
+            format!("Found issues in **{}** of **{}** checks. Work through the fixes below.", issues, findings.len())
+        },
+        "color": if issues == 0 { colors::GREEN } else { colors::YELLOW },
+        "fields": fields,
+        "footer": { "text": "Rimuru Advanced Security | Re-run !antinuke audit after making changes" }
+    })).await?;
+    Ok(())
+}
+
+async fn set_log_channel(rest: &RestClient, msg: &Message, db: Arc<Database>, parts: &[&str]) -> anyhow::Result<()> {
+    let guild_id = msg.guild_id.as_deref().unwrap_or("");
+    if guild_id.is_empty() { return Ok(()); }
+
+    let choice = parts.first().copied().unwrap_or("");
+    match choice.to_lowercase().as_str() {
+        "off" | "disable" | "none" => {
+            db.set_log_channel(guild_id, None).await?;
+            rest.send_message(&msg.channel_id, &format!("{} Security log channel cleared. Alerts will fall back to the system channel.", emojis::SUCCESS)).await?;
+        }
+        "" => {
+            let (channel, owner_dm) = db.get_alert_options(guild_id).await;
+            rest.send_message(&msg.channel_id, &format!(
+                "{} Security log: **{}** | Owner DM: **{}**. Usage: `!antinuke log <#channel|off>`",
+                emojis::INFO,
+                channel.map(|c| format!("<#{}>", c)).unwrap_or_else(|| "Not set".to_string()),
+                if owner_dm { "ON" } else { "OFF" }
+            )).await?;
+        }
+        _ => {
+            let channel_id = choice.trim_matches(&['<', '#', '>'][..]);
+            let valid = rest.get_guild_channels(guild_id).await.ok()
+                .and_then(|v| v.as_array().cloned())
+                .map(|arr| arr.iter().any(|c| c["id"].as_str() == Some(channel_id)))
+                .unwrap_or(false);
+            if !valid {
+                rest.send_message(&msg.channel_id, &format!("{} That channel does not belong to this server. Usage: `!antinuke log <#channel|off>`", emojis::ERROR)).await?;
+                return Ok(());
+            }
+            db.set_log_channel(guild_id, Some(channel_id)).await?;
+            rest.send_message(&msg.channel_id, &format!("{} Antinuke incidents will now be logged to <#{}>.", emojis::SUCCESS, channel_id)).await?;
+        }
+    }
+    Ok(())
+}
+
+async fn set_owner_dm(rest: &RestClient, msg: &Message, db: Arc<Database>, parts: &[&str]) -> anyhow::Result<()> {
+    let guild_id = msg.guild_id.as_deref().unwrap_or("");
+    if guild_id.is_empty() { return Ok(()); }
+
+    let enable = match parts.first().map(|s| s.to_lowercase()).as_deref() {
+        Some("on") | Some("enable") => true,
+        Some("off") | Some("disable") => false,
+        _ => {
+            rest.send_message(&msg.channel_id, &format!("{} Usage: `!antinuke ownerdm <on|off>`", emojis::ERROR)).await?;
+            return Ok(());
+        }
+    };
+    db.set_owner_dm(guild_id, enable).await?;
+    rest.send_message(&msg.channel_id, &format!(
+        "{} Owner DM alerts are now **{}**.",
+        emojis::SUCCESS,
+        if enable { "enabled" } else { "disabled" }
+    )).await?;
     Ok(())
 }
 
@@ -198,6 +401,7 @@ async fn show_disable_sequence(rest: &RestClient, msg: &Message, db: Arc<Databas
 async fn show_settings(rest: &RestClient, msg: &Message, db: Arc<Database>, bot_avatar: &str) -> anyhow::Result<()> {
     let guild_id = msg.guild_id.as_deref().unwrap_or("");
     let settings = db.get_antinuke_settings(guild_id).await?;
+    let monitored = db.list_monitor_modules(guild_id).await.unwrap_or_default();
 
     let mut enabled_list = String::new();
     let mut active_count = 0;
@@ -206,7 +410,7 @@ async fn show_settings(rest: &RestClient, msg: &Message, db: Arc<Database>, bot_
     keys.sort();
 
     for key in keys {
-        if key == "auto_recovery" { continue; } 
+        if key == "auto_recovery" || key == "auto_lockdown" { continue; } 
         if settings.get(key).cloned().unwrap_or(false) {
             let label = key.replace("anti_", "").replace("_", " ");
             let capitalized = label.split_whitespace()
@@ -220,7 +424,8 @@ async fn show_settings(rest: &RestClient, msg: &Message, db: Arc<Database>, bot_
                 .collect::<Vec<String>>()
                 .join(" ");
 
-            enabled_list.push_str(&format!("{} Anti {}\n", emojis::SUCCESS, capitalized));
+            let mode = if monitored.contains("*") || monitored.contains(key.as_str()) { " (monitor)" } else { "" };
+            enabled_list.push_str(&format!("{} Anti {}{}\n", emojis::SUCCESS, capitalized, mode));
             active_count += 1;
         }
     }
@@ -260,10 +465,10 @@ pub async fn handle_interaction(
 ) -> anyhow::Result<()> {
 
     let user_id = interaction.member.as_ref().and_then(|m| m.user.as_ref()).map(|u| u.id.clone()).unwrap_or_default();
-    let is_bot_admin = db.is_admin(&user_id).await.unwrap_or(false);
+    let guild_id = interaction.guild_id.as_deref().unwrap_or("");
+    let is_bot_admin = db.is_admin(guild_id, &user_id).await.unwrap_or(false);
     let mut is_owner = false;
 
-    let guild_id = interaction.guild_id.as_deref().unwrap_or("");
     if !guild_id.is_empty() {
         if let Ok(guild) = rest.get_guild(guild_id).await {
             if let Some(owner_id) = guild["owner_id"].as_str() {
@@ -301,6 +506,25 @@ pub async fn handle_interaction(
         "antinuke_config_menu" => {
             show_full_config(rest, &interaction, db).await?;
         }
+        "antinuke_monitor_menu" => {
+            show_monitor_config(rest, &interaction, db, 0).await?;
+        }
+        id if id.starts_with("antinuke_mon_page:") => {
+            let page = id["antinuke_mon_page:".len()..].parse().unwrap_or(0);
+            show_monitor_config(rest, &interaction, db, page).await?;
+        }
+        id if id.starts_with("antinuke_rollback:") => {
+            handle_rollback(rest, &interaction, db, is_owner, &id["antinuke_rollback:".len()..]).await?;
+        }
+        id if id.starts_with("antinuke_setup:") => {
+            super::setup::handle_interaction(rest, &interaction, db, is_owner, &id["antinuke_setup:".len()..]).await?;
+        }
+        id if id.starts_with("antinuke_import:") => {
+            super::template::handle_interaction(rest, &interaction, db, is_owner, &id["antinuke_import:".len()..]).await?;
+        }
+        id if id.starts_with("antinuke_mon:") => {
+            toggle_monitor(rest, &interaction, db, &id["antinuke_mon:".len()..]).await?;
+        }
         "antinuke_sel_menu" => {
             show_select_config(rest, &interaction, db).await?;
         }
@@ -339,7 +563,7 @@ async fn toggle_setting_and_refresh_select(rest: &RestClient, interaction: &Inte
 async fn show_dashboard(rest: &RestClient, msg: &Message, bot_avatar: &str) -> anyhow::Result<()> {
     let embed = json!({
         "title": "RIMURU ADVANCED SECURITY",
-        "description": "Welcome to the Rimuru Advanced Security System - A military-grade solution engineered to protect your server against sophisticated nuking attempts. Deploy and manage elite security protocols through our advanced command interface.\n\n**`!antinuke enable`**\nDeploy the advanced security system with maximum protection protocols.\n\n**`!antinuke disable`**\nDeactivate the security system and its protection modules.\n\n**`!antinuke config`**\nConfigure elite security modules and their operational parameters.\n\n**`!antinuke settings`**\nAccess current security status and module operational data.",
+        "description": "Welcome to the Rimuru Advanced Security System - A military-grade solution engineered to protect your server against sophisticated nuking attempts. Deploy and manage elite security protocols through our advanced command interface.\n\n**`!antinuke enable`**\nLaunch the guided setup wizard to pick a protection preset, punishment and log channel.\n\n**`!antinuke disable`**\nDeactivate the security system and its protection modules.\n\n**`!antinuke config`**\nConfigure elite security modules and their operational parameters.\n\n**`!antinuke settings`**\nAccess current security status and module operational data.",
         "color": colors::MAIN,
         "thumbnail": { "url": bot_avatar },
         "footer": { "text": "Use '!antinuke enable' to activate the security system • Today" }
@@ -384,35 +608,37 @@ async fn show_config_menu(rest: &RestClient, msg: &Message, bot_avatar: &str) ->
     Ok(())
 }
 
+const BUTTON_MODULES: [(&str, &str); 21] = [
+    ("BAN", "anti_ban"), ("UNBAN", "anti_unban"), ("KICK", "anti_kick"), ("BOT", "anti_bot"), ("PRUNE", "anti_prune"),
+    ("CH-ADD", "anti_channel_create"), ("CH-UP", "anti_channel_update"), ("CH-DEL", "anti_channel_delete"), ("ROLE-ADD", "anti_role_create"), ("ROLE-UP", "anti_role_update"),
+    ("ROLE-DEL", "anti_role_delete"), ("JOIN-R", "anti_member_role_update"), ("PING", "anti_everyone_ping"), ("SRV-UP", "anti_server_update"), ("LOCK", "thread_lock_enabled"),
+    ("EMO-ADD", "anti_emoji_create"), ("STK-ADD", "anti_sticker_create"), ("WB-ADD", "anti_webhook_create"), ("WB-UP", "anti_webhook_update"), ("WB-DEL", "anti_webhook_delete"),
+    ("RAID", "anti_raid")
+];
+
+fn button_rows(buttons: Vec<Value>) -> Vec<Value> {
+    buttons.chunks(5)
+        .take(5)
+        .map(|row| json!({ "type": 1, "components": row }))
+        .collect()
+}
+
 async fn show_full_config(rest: &RestClient, interaction: &Interaction, db: Arc<Database>) -> anyhow::Result<()> {
     let guild_id = interaction.guild_id.as_deref().unwrap_or("");
     let settings = db.get_antinuke_settings(guild_id).await?;
 
-    let button_modules = [
-        ("BAN", "anti_ban"), ("UNBAN", "anti_unban"), ("KICK", "anti_kick"), ("BOT", "anti_bot"), ("PRUNE", "anti_prune"),
-        ("CH-ADD", "anti_channel_create"), ("CH-UP", "anti_channel_update"), ("CH-DEL", "anti_channel_delete"), ("ROLE-ADD", "anti_role_create"), ("ROLE-UP", "anti_role_update"),
-        ("ROLE-DEL", "anti_role_delete"), ("JOIN-R", "anti_member_role_update"), ("PING", "anti_everyone_ping"), ("SRV-UP", "anti_server_update"), ("LOCK", "thread_lock_enabled"),
-        ("EMO-ADD", "anti_emoji_create"), ("STK-ADD", "anti_sticker_create"), ("WB-ADD", "anti_webhook_create"), ("WB-UP", "anti_webhook_update"), ("WB-DEL", "anti_webhook_delete")
-    ];
-
-    let mut components = Vec::new();
-
-    let mut row = Vec::new();
-    for (label, key) in button_modules {
+    let mut buttons = Vec::new();
+    for (label, key) in BUTTON_MODULES {
         let enabled = settings.get(key).cloned().unwrap_or(false);
-        row.push(json!({
+        buttons.push(json!({
             "type": 2,
             "style": if enabled { 3 } else { 4 },
             "label": format!("{}: {}", label, if enabled { "ON" } else { "OFF" }),
             "custom_id": format!("toggle_{}", key)
         }));
-
-        if row.len() == 5 {
-            components.push(json!({ "type": 1, "components": row.clone() }));
-            row.clear();
-            if components.len() == 5 { break; } 
-        }
     }
+    buttons.push(json!({ "type": 2, "style": 1, "label": "Monitor / Enforce", "custom_id": "antinuke_monitor_menu" }));
+    let components = button_rows(buttons);
 
     rest.interaction_callback(&interaction.id, &interaction.token, json!({
         "type": 7, 
@@ -426,6 +652,119 @@ async fn show_full_config(rest: &RestClient, interaction: &Interaction, db: Arc<
     Ok(())
 }
 
+const MONITOR_PAGE_SIZE: usize = 20;
+
+async fn show_monitor_config(rest: &RestClient, interaction: &Interaction, db: Arc<Database>, page: usize) -> anyhow::Result<()> {
+    let guild_id = interaction.guild_id.as_deref().unwrap_or("");
+    let settings = db.get_antinuke_settings(guild_id).await?;
+    let monitored = db.list_monitor_modules(guild_id).await?;
+    let guild_wide = monitored.contains("*");
+
+    let mut modules: Vec<&String> = settings.keys()
+        .filter(|k| *k != "auto_recovery" && *k != "auto_lockdown")
+        .collect();
+    modules.sort();
+    let pages = modules.len().div_ceil(MONITOR_PAGE_SIZE).max(1);
+    let page = page.min(pages - 1);
+
+    let mut buttons = Vec::new();
+    for key in modules.iter().skip(page * MONITOR_PAGE_SIZE).take(MONITOR_PAGE_SIZE) {
+        let monitor = guild_wide || monitored.contains(key.as_str());
+        buttons.push(json!({
+            "type": 2,
+            "style": if monitor { 1 } else { 2 },
+            "label": format!("{}: {}", key.trim_start_matches("anti_").replace('_', " ").to_uppercase(), if monitor { "MONITOR" } else { "ENFORCE" }),
+            "custom_id": format!("antinuke_mon:{}:{}", page, key),
+            "disabled": guild_wide
+        }));
+    }
+    let mut rows = button_rows(buttons);
+    rows.push(json!({
+        "type": 1,
+        "components": [
+            {
+                "type": 2,
+                "style": if guild_wide { 1 } else { 2 },
+                "label": format!("ALL: {}", if guild_wide { "MONITOR" } else { "PER MODULE" }),
+                "custom_id": format!("antinuke_mon:{}:*", page)
+            },
+            { "type": 2, "style": 2, "label": "Previous", "custom_id": format!("antinuke_mon_page:{}", page.saturating_sub(1)), "disabled": page == 0 },
+            { "type": 2, "style": 2, "label": "Next", "custom_id": format!("antinuke_mon_page:{}", page + 1), "disabled": page + 1 >= pages },
+            { "type": 2, "style": 2, "label": "Back", "custom_id": "antinuke_config_menu" }
+        ]
+    }));
+
+    rest.interaction_callback(&interaction.id, &interaction.token, json!({
+        "type": 7,
+        "data": {
+            "content": format!(
+                "**👁️ Antinuke Monitor Mode** (page {}/{})\nModules in **MONITOR** run detection and attribution but only log what they would have done. Switch a module to **ENFORCE** to let it act.",
+                page + 1, pages
+            ),
+            "components": rows,
+            "flags": 64
+        }
+    })).await?;
+
+    Ok(())
+}
+
+async fn toggle_monitor(rest: &RestClient, interaction: &Interaction, db: Arc<Database>, args: &str) -> anyhow::Result<()> {
+    let guild_id = interaction.guild_id.as_deref().unwrap_or("");
+    let (page, module) = args.split_once(':').unwrap_or(("0", args));
+    let current = db.list_monitor_modules(guild_id).await?.contains(module);
+
+    db.set_monitor_mode(guild_id, module, !current).await?;
+
+    show_monitor_config(rest, interaction, db, page.parse().unwrap_or(0)).await
+}
+
+async fn handle_rollback(rest: &RestClient, interaction: &Interaction, db: Arc<Database>, is_owner: bool, args: &str) -> anyhow::Result<()> {
+    let guild_id = interaction.guild_id.as_deref().unwrap_or("");
+
+    if !is_owner {
+        rest.interaction_callback(&interaction.id, &interaction.token, json!({
+            "type": 4,
+            "data": { "content": format!("{} Only the **Server Owner** can decide on a ban rollback.", emojis::ERROR), "flags": 64 }
+        })).await?;
+        return Ok(());
+    }
+
+    let (decision, executor_id) = args.split_once(':').unwrap_or(("", ""));
+
+    if decision != "confirm" {
+        let content = match crate::antinuke::rollback::dismiss(guild_id, executor_id, &db).await {
+            Ok(_) => format!("{} Members banned by <@{}> will stay banned.", emojis::INFO, executor_id),
+            Err(e) => format!("{} {}", emojis::ERROR, e),
+        };
+        rest.interaction_callback(&interaction.id, &interaction.token, json!({
+            "type": 7,
+            "data": { "content": content, "components": [] }
+        })).await?;
+        return Ok(());
+    }
+
+    rest.interaction_callback(&interaction.id, &interaction.token, json!({
+        "type": 7,
+        "data": { "content": format!("{} Restoring members banned by <@{}>...", emojis::LOADING, executor_id), "components": [] }
+    })).await?;
+
+    let channel_id = interaction.channel_id.as_deref().unwrap_or("");
+    let content = match crate::antinuke::rollback::confirm(guild_id, executor_id, rest, &db).await {
+        Ok((restored, failed)) => {
+            let mut text = format!("{} Unbanned **{}** member(s) banned by <@{}>.", emojis::SUCCESS, restored, executor_id);
+            if failed > 0 {
+                text.push_str(&format!("\n{} **{}** member(s) could not be unbanned.", emojis::WARNING, failed));
+            }
+            text
+        }
+        Err(e) => format!("{} {}", emojis::ERROR, e),
+    };
+    rest.send_message(channel_id, &content).await?;
+
+    Ok(())
+}
+
 async fn show_select_config(rest: &RestClient, interaction: &Interaction, db: Arc<Database>) -> anyhow::Result<()> {
     let guild_id = interaction.guild_id.as_deref().unwrap_or("");
     let settings = db.get_antinuke_settings(guild_id).await?;
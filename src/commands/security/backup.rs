@@ -0,0 +1,345 @@
+use crate::models::{Interaction, Message};
+use crate::rest::RestClient;
+use crate::db::Database;
+use crate::constants::{emojis, colors};
+use crate::antinuke::backup::{self, BackupDiff, SCOPES};
+use std::sync::Arc;
+use serde_json::{json, Value};
+
+const DEFAULT_KEEP: usize = 5;
+
+fn timestamp(rfc3339: &str) -> String {
+    chrono::DateTime::parse_from_rfc3339(rfc3339)
+        .map(|t| format!("<t:{}:f>", t.timestamp()))
+        .unwrap_or_else(|_| rfc3339.to_string())
+}
+
+fn parse_scope(arg: Option<&str>) -> Option<&'static str> {
+    match arg.map(|s| s.to_lowercase()).as_deref() {
+        None | Some("all") => Some("all"),
+        Some(scope) => SCOPES.iter().copied().find(|s| *s == scope),
+    }
+}
+
+fn scope_list(scope: &str) -> Vec<&'static str> {
+    if scope == "all" { SCOPES.to_vec() } else { SCOPES.iter().copied().filter(|s| *s == scope).collect() }
+}
+
+fn names(values: &[Value], prefix: &str) -> String {
+    let mut list: Vec<String> = values.iter().take(10).map(|v| format!("{}{}", prefix, v["name"].as_str().unwrap_or("?"))).collect();
+    if values.len() > 10 {
+        list.push(format!("…and {} more", values.len() - 10));
+    }
+    list.join(", ")
+}
+
+fn changed_names(changed: &[(String, Value, Vec<&'static str>)], prefix: &str) -> String {
+    let mut list: Vec<String> = changed.iter().take(10)
+        .map(|(_, v, fields)| format!("{}{} ({})", prefix, v["name"].as_str().unwrap_or("?"), fields.join(", ")))
+        .collect();
+    if changed.len() > 10 {
+        list.push(format!("…and {} more", changed.len() - 10));
+    }
+    list.join("\n")
+}
+
+fn preview_embed(backup_id: i64, scope: &str, diff: &BackupDiff) -> Value {
+    let scopes = scope_list(scope);
+    let mut fields = Vec::new();
+
+    if scopes.contains(&"roles") {
+        if !diff.roles_missing.is_empty() {
+            fields.push(json!({ "name": format!("Roles to create ({})", diff.roles_missing.len()), "value": names(&diff.roles_missing, "@"), "inline": false }));
+        }
+        if !diff.roles_changed.is_empty() {
+            fields.push(json!({ "name": format!("Roles to update ({})", diff.roles_changed.len()), "value": changed_names(&diff.roles_changed, "@"), "inline": false }));
+        }
+        if !diff.role_order.is_empty() {
+            fields.push(json!({ "name": "Role order", "value": format!("{} role(s) will be moved back to their saved position.", diff.role_order.len()), "inline": false }));
+        }
+    }
+    if scopes.contains(&"channels") {
+        if !diff.channels_missing.is_empty() {
+            fields.push(json!({ "name": format!("Channels to create ({})", diff.channels_missing.len()), "value": names(&diff.channels_missing, "#"), "inline": false }));
+        }
+        if !diff.channels_changed.is_empty() {
+            fields.push(json!({ "name": format!("Channels to update ({})", diff.channels_changed.len()), "value": changed_names(&diff.channels_changed, "#"), "inline": false }));
+        }
+    }
+    if scopes.contains(&"settings") && !diff.settings_changed.is_empty() {
+        fields.push(json!({ "name": "Server settings to restore", "value": diff.settings_changed.join(", "), "inline": false }));
+    }
+    if scopes.contains(&"emojis") && !diff.emojis_missing.is_empty() {
+        fields.push(json!({ "name": format!("Emojis to restore ({})", diff.emojis_missing.len()), "value": names(&diff.emojis_missing, ":"), "inline": false }));
+    }
+
+    let description = if fields.is_empty() {
+        "The server already matches this backup. Nothing would change.".to_string()
+    } else {
+        "The following changes will be applied. Nothing is deleted: extra roles and channels are left untouched.".to_string()
+    };
+
+    json!({
+        "title": format!("{} Restore Backup #{} ({})", emojis::FOLDER, backup_id, scope),
+        "description": description,
+        "color": if fields.is_empty() { colors::GREEN } else { colors::YELLOW },
+        "fields": fields,
+        "footer": { "text": "Only the Server Owner can confirm a restore" }
+    })
+}
+
+pub async fn handle_backup(
+    rest: &RestClient,
+    msg: &Message,
+    db: Arc<Database>,
+    args: &str,
+) -> anyhow::Result<()> {
+    let guild_id = match msg.guild_id.as_deref() {
+        Some(id) => id,
+        None => return Ok(()),
+    };
+
+    let is_bot_admin = db.is_admin(guild_id, &msg.author.id).await.unwrap_or(false);
+    let is_owner = rest.get_guild(guild_id).await
+        .map(|g| g["owner_id"].as_str() == Some(msg.author.id.as_str()))
+        .unwrap_or(false);
+
+    if !is_bot_admin && !is_owner {
+        rest.send_message(&msg.channel_id, &format!("{} This command is restricted to **Server Owners** and **Bot Admins** only.", emojis::ERROR)).await?;
+        return Ok(());
+    }
+
+    let parts: Vec<&str> = args.split_whitespace().collect();
+    let sub_cmd = parts.first().map(|s| s.to_lowercase()).unwrap_or_default();
+    let backup_id = parts.get(1).and_then(|s| s.trim_start_matches('#').parse::<i64>().ok());
+
+    match sub_cmd.as_str() {
+        "create" | "new" => {
+            let label = if parts.len() > 1 { parts[1..].join(" ") } else { "Manual backup".to_string() };
+            let doc = backup::capture(guild_id, rest).await?;
+            let id = db.save_backup(guild_id, &label, &msg.author.id, false, &doc).await?;
+            rest.send_embed(&msg.channel_id, json!({
+                "title": format!("{} Backup #{} Created", emojis::SUCCESS, id),
+                "description": format!(
+                    "**Label:** {}\n**Roles:** {}\n**Channels:** {}\n**Emojis:** {}\n\nRestore it with `!backup restore {}`.",
+                    label,
+                    doc["roles"].as_array().map(|a| a.len()).unwrap_or(0),
+                    doc["channels"].as_array().map(|a| a.len()).unwrap_or(0),
+                    doc["emojis"].as_array().map(|a| a.len()).unwrap_or(0),
+                    id
+                ),
+                "color": colors::MAIN
+            })).await?;
+        }
+        "info" | "view" => {
+            let doc = match backup_id {
+                Some(id) => db.get_backup(guild_id, id).await?,
+                None => None,
+            };
+            let (id, doc) = match (backup_id, doc) {
+                (Some(id), Some(doc)) => (id, doc),
+                _ => {
+                    rest.send_message(&msg.channel_id, &format!("{} Usage: `!backup info <id>` with an existing backup ID.", emojis::ERROR)).await?;
+                    return Ok(());
+                }
+            };
+            let current = backup::capture(guild_id, rest).await?;
+            let diff = backup::diff(&doc, &current);
+            let mut embed = preview_embed(id, "all", &diff);
+            embed["title"] = json!(format!("{} Backup #{}", emojis::FOLDER, id));
+            embed["description"] = json!(format!(
+                "**Server:** {}\n**Taken:** {}\n**Roles:** {} | **Channels:** {} | **Emojis:** {}\n\n{}",
+                doc["settings"]["name"].as_str().unwrap_or("Unknown"),
+                timestamp(doc["created_at"].as_str().unwrap_or("")),
+                doc["roles"].as_array().map(|a| a.len()).unwrap_or(0),
+                doc["channels"].as_array().map(|a| a.len()).unwrap_or(0),
+                doc["emojis"].as_array().map(|a| a.len()).unwrap_or(0),
+                if diff.is_empty() { "The server currently matches this backup." } else { "Differences from the current server:" }
+            ));
+            embed["footer"] = json!({ "text": format!("!backup restore {} [roles|channels|settings|emojis|all]", id) });
+            rest.send_embed(&msg.channel_id, embed).await?;
+        }
+        "delete" | "remove" => {
+            if !is_owner {
+                rest.send_message(&msg.channel_id, &format!("{} Only the **Server Owner** can delete backups.", emojis::ERROR)).await?;
+                return Ok(());
+            }
+            let removed = match backup_id {
+                Some(id) => db.delete_backup(guild_id, id).await?,
+                None => 0,
+            };
+            if removed == 0 {
+                rest.send_message(&msg.channel_id, &format!("{} Usage: `!backup delete <id>` with an existing backup ID.", emojis::ERROR)).await?;
+            } else {
+                rest.send_message(&msg.channel_id, &format!("{} Deleted backup **#{}**.", emojis::SUCCESS, backup_id.unwrap_or_default())).await?;
+            }
+        }
+        "restore" | "load" => {
+            if !is_owner {
+                rest.send_message(&msg.channel_id, &format!("{} Only the **Server Owner** can restore backups.", emojis::ERROR)).await?;
+                return Ok(());
+            }
+            let scope = match parse_scope(parts.get(2).copied()) {
+                Some(s) => s,
+                None => {
+                    rest.send_message(&msg.channel_id, &format!("{} Unknown scope. Use `roles`, `channels`, `settings`, `emojis` or `all`.", emojis::ERROR)).await?;
+                    return Ok(());
+                }
+            };
+            let doc = match backup_id {
+                Some(id) => db.get_backup(guild_id, id).await?,
+                None => None,
+            };
+            let (id, doc) = match (backup_id, doc) {
+                (Some(id), Some(doc)) => (id, doc),
+                _ => {
+                    rest.send_message(&msg.channel_id, &format!("{} Usage: `!backup restore <id> [roles|channels|settings|emojis|all]`", emojis::ERROR)).await?;
+                    return Ok(());
+                }
+            };
+            if let Err(e) = backup::validate(&doc) {
+                rest.send_message(&msg.channel_id, &format!("{} This backup cannot be restored: {}", emojis::ERROR, e)).await?;
+                return Ok(());
+            }
+
+            let current = backup::capture(guild_id, rest).await?;
+            let diff = backup::diff(&doc, &current);
+            let components = vec![json!({
+                "type": 1,
+                "components": [
+                    { "type": 2, "style": 4, "label": "Restore", "custom_id": format!("backup_restore:{}:{}", id, scope), "disabled": diff.is_empty() },
+                    { "type": 2, "style": 2, "label": "Cancel", "custom_id": "backup_cancel" }
+                ]
+            })];
+            rest.send_complex_message(&msg.channel_id, "", vec![preview_embed(id, scope, &diff)], components).await?;
+        }
+        "schedule" | "auto" => {
+            let first = parts.get(1).map(|s| s.to_lowercase()).unwrap_or_default();
+            if first == "off" || first == "disable" {
+                db.set_backup_schedule(guild_id, None).await?;
+                rest.send_message(&msg.channel_id, &format!("{} Automatic backups disabled.", emojis::SUCCESS)).await?;
+                return Ok(());
+            }
+
+            let hours = first.trim_end_matches('h').parse::<u64>().ok().filter(|h| (1..=720).contains(h));
+            let keep = match parts.get(2) {
+                Some(k) => k.parse::<usize>().ok().filter(|k| (1..=25).contains(k)),
+                None => Some(DEFAULT_KEEP),
+            };
+            match (hours, keep) {
+                (Some(hours), Some(keep)) => {
+                    db.set_backup_schedule(guild_id, Some((hours, keep))).await?;
+                    rest.send_message(&msg.channel_id, &format!(
+                        "{} A backup will be taken every **{}h**, keeping the latest **{}** automatic backup(s).",
+                        emojis::SUCCESS, hours, keep
+                    )).await?;
+                }
+                _ => {
+                    rest.send_message(&msg.channel_id, &format!("{} Usage: `!backup schedule <hours 1-720> [keep 1-25]` or `!backup schedule off`", emojis::ERROR)).await?;
+                }
+            }
+        }
+        "list" | "" => {
+            let backups = db.list_backups(guild_id).await?;
+            let schedule = match db.get_backup_schedule(guild_id).await {
+                Some((hours, keep)) => format!("Every {}h, keeping {}", hours, keep),
+                None => "Off".to_string(),
+            };
+
+            let mut description = format!("**Automatic Backups:** {}\n\n", schedule);
+            if backups.is_empty() {
+                description.push_str("No backups yet. Create one with `!backup create [label]`.");
+            }
+            for (id, label, created_by, created_at, automatic) in backups.iter().take(20) {
+                description.push_str(&format!(
+                    "**#{}** {} — {} ({})\n",
+                    id,
+                    timestamp(created_at),
+                    label,
+                    if *automatic { "scheduled".to_string() } else { format!("by <@{}>", created_by) }
+                ));
+            }
+
+            rest.send_embed(&msg.channel_id, json!({
+                "title": format!("{} Server Backups", emojis::FOLDER),
+                "description": description,
+                "color": colors::MAIN,
+                "footer": { "text": "!backup create | info | restore | delete | schedule" }
+            })).await?;
+        }
+        _ => {
+            rest.send_message(&msg.channel_id, &format!("{} Unknown subcommand. Try `create`, `list`, `info`, `restore`, `delete`, `schedule`.", emojis::ERROR)).await?;
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn handle_interaction(
+    rest: &RestClient,
+    interaction: Interaction,
+    db: Arc<Database>,
+) -> anyhow::Result<()> {
+    let user_id = interaction.member.as_ref().and_then(|m| m.user.as_ref()).map(|u| u.id.clone()).unwrap_or_default();
+    let guild_id = interaction.guild_id.as_deref().unwrap_or("");
+    let is_owner = rest.get_guild(guild_id).await
+        .map(|g| g["owner_id"].as_str() == Some(user_id.as_str()))
+        .unwrap_or(false);
+
+    if !is_owner {
+        rest.interaction_callback(&interaction.id, &interaction.token, json!({
+            "type": 4,
+            "data": { "content": format!("{} Only the **Server Owner** can restore backups.", emojis::ERROR), "flags": 64 }
+        })).await?;
+        return Ok(());
+    }
+
+    let custom_id = interaction.data.as_ref().and_then(|d| d.custom_id.as_deref()).unwrap_or("");
+    if custom_id == "backup_cancel" {
+        rest.interaction_callback(&interaction.id, &interaction.token, json!({
+            "type": 7,
+            "data": { "content": format!("{} Restore cancelled.", emojis::INFO), "embeds": [], "components": [] }
+        })).await?;
+        return Ok(());
+    }
+
+    let mut segments = custom_id.split(':');
+    if segments.next() != Some("backup_restore") { return Ok(()); }
+    let backup_id = segments.next().and_then(|s| s.parse::<i64>().ok()).unwrap_or_default();
+    let scope = parse_scope(segments.next()).unwrap_or("all");
+
+    let doc = match db.get_backup(guild_id, backup_id).await? {
+        Some(d) => d,
+        None => {
+            rest.interaction_callback(&interaction.id, &interaction.token, json!({
+                "type": 7,
+                "data": { "content": format!("{} Backup #{} no longer exists.", emojis::ERROR, backup_id), "embeds": [], "components": [] }
+            })).await?;
+            return Ok(());
+        }
+    };
+
+    rest.interaction_callback(&interaction.id, &interaction.token, json!({
+        "type": 7,
+        "data": { "content": format!("{} Restoring backup **#{}** ({})...", emojis::LOADING, backup_id, scope), "components": [] }
+    })).await?;
+
+    let content = match backup::restore(guild_id, &doc, &scope_list(scope), rest, &db).await {
+        Ok(summary) => {
+            let mut text = format!(
+                "{} Backup **#{}** restored: **{}** created, **{}** updated.",
+                emojis::SUCCESS, backup_id, summary.created, summary.updated
+            );
+            if !summary.failed.is_empty() {
+                text.push_str(&format!("\n{} Failed: {}", emojis::WARNING, summary.failed.join(", ")));
+            }
+            text
+        }
+        Err(e) => format!("{} Restore failed: {}", emojis::ERROR, e),
+    };
+    if let Some(channel_id) = interaction.channel_id.as_deref() {
+        rest.send_message(channel_id, &content).await?;
+    }
+
+    Ok(())
+}
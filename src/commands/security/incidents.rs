@@ -0,0 +1,235 @@
+use crate::models::{Interaction, Message};
+use crate::rest::RestClient;
+use crate::db::{Database, IncidentRecord};
+use crate::constants::{emojis, colors};
+use std::sync::Arc;
+use serde_json::{json, Value};
+
+const PAGE_SIZE: usize = 10;
+
+fn module_label(module: &str) -> String {
+    module.replace("anti_", "").replace('_', " ").to_uppercase()
+}
+
+fn timestamp(rfc3339: &str) -> String {
+    chrono::DateTime::parse_from_rfc3339(rfc3339)
+        .map(|t| format!("<t:{}:f>", t.timestamp()))
+        .unwrap_or_else(|_| rfc3339.to_string())
+}
+
+fn executor_label(executor_id: &str) -> String {
+    if executor_id.is_empty() { "Unknown".to_string() } else { format!("<@{}>", executor_id) }
+}
+
+fn clip(text: &str, max: usize) -> String {
+    if text.chars().count() <= max { return text.to_string(); }
+    let mut out: String = text.chars().take(max - 1).collect();
+    out.push('…');
+    out
+}
+
+fn filter_token(value: Option<&str>) -> &str {
+    value.unwrap_or("-")
+}
+
+fn parse_filter_token(token: Option<&str>) -> Option<String> {
+    token.filter(|t| !t.is_empty() && *t != "-").map(String::from)
+}
+
+async fn list_page(db: &Database, guild_id: &str, page: usize, module: Option<&str>, executor_id: Option<&str>) -> anyhow::Result<(Value, Vec<Value>)> {
+    let (records, total) = db.list_incidents(guild_id, module, executor_id, PAGE_SIZE, page * PAGE_SIZE).await?;
+    let pages = total.div_ceil(PAGE_SIZE).max(1);
+
+    let mut description = String::new();
+    let mut filters = Vec::new();
+    if let Some(m) = module { filters.push(format!("Module: **{}**", module_label(m))); }
+    if let Some(e) = executor_id { filters.push(format!("Executor: <@{}>", e)); }
+    if !filters.is_empty() {
+        description.push_str(&format!("{}\n\n", filters.join(" | ")));
+    }
+
+    if records.is_empty() {
+        description.push_str("No incidents recorded.");
+    }
+    for record in &records {
+        description.push_str(&format!(
+            "{} **#{}** {} — **{}** by {}\n{}\n",
+            if record.succeeded { emojis::SUCCESS } else { emojis::WARNING },
+            record.id,
+            timestamp(&record.detected_at),
+            module_label(&record.module),
+            executor_label(&record.executor_id),
+            clip(&record.action, 120)
+        ));
+    }
+
+    let embed = json!({
+        "title": format!("{} Antinuke Incidents", emojis::SHIELD),
+        "description": description,
+        "color": colors::MAIN,
+        "footer": { "text": format!("Page {}/{} | {} incident(s) | !antinuke incidents view <id>", page + 1, pages, total) }
+    });
+
+    let filter = format!("{}:{}", filter_token(module), filter_token(executor_id));
+    let components = vec![json!({
+        "type": 1,
+        "components": [
+            {
+                "type": 2,
+                "style": 2,
+                "label": "Previous",
+                "custom_id": format!("incidents_page:{}:{}", page.saturating_sub(1), filter),
+                "disabled": page == 0
+            },
+            {
+                "type": 2,
+                "style": 2,
+                "label": "Next",
+                "custom_id": format!("incidents_page:{}:{}", page + 1, filter),
+                "disabled": page + 1 >= pages
+            }
+        ]
+    })];
+
+    Ok((embed, components))
+}
+
+fn detail_embed(record: &IncidentRecord) -> Value {
+    let targets = if record.targets.is_empty() { "None".to_string() } else { record.targets.join(", ") };
+    let recovery = if record.recovery.is_empty() { "None".to_string() } else { record.recovery.join("\n") };
+
+    json!({
+        "title": format!("{} Incident #{}: {}", emojis::SHIELD, record.id, module_label(&record.module)),
+        "description": clip(&record.action, 4000),
+        "color": if record.succeeded { colors::GREEN } else { colors::RED },
+        "fields": [
+            { "name": "Executor", "value": if record.executor_id.is_empty() { "Unknown".to_string() } else { format!("<@{}> (`{}`)", record.executor_id, record.executor_id) }, "inline": true },
+            { "name": "Outcome", "value": if record.succeeded { "Handled" } else { "Partially failed" }, "inline": true },
+            { "name": "Punishment", "value": clip(&record.punishment, 1024), "inline": false },
+            { "name": "Targets", "value": clip(&targets, 1024), "inline": false },
+            { "name": "Recovery", "value": clip(&recovery, 1024), "inline": false },
+            { "name": "Detected", "value": timestamp(&record.detected_at), "inline": true },
+            { "name": "Resolved", "value": timestamp(&record.resolved_at), "inline": true },
+            { "name": "Audit Log Entry", "value": record.audit_entry_id.as_deref().map(|id| format!("`{}`", id)).unwrap_or_else(|| "None".to_string()), "inline": true }
+        ],
+        "footer": { "text": format!("!antinuke incidents export {} to download this record", record.id) }
+    })
+}
+
+pub async fn handle_incidents(
+    rest: &RestClient,
+    msg: &Message,
+    db: Arc<Database>,
+    parts: &[&str],
+) -> anyhow::Result<()> {
+    let guild_id = msg.guild_id.as_deref().unwrap_or("");
+    if guild_id.is_empty() { return Ok(()); }
+
+    let sub_cmd = parts.first().map(|s| s.to_lowercase()).unwrap_or_default();
+    match sub_cmd.as_str() {
+        "view" | "info" | "export" => {
+            let record = match parts.get(1).and_then(|s| s.trim_start_matches('#').parse::<i64>().ok()) {
+                Some(id) => db.get_incident(guild_id, id).await?,
+                None => {
+                    rest.send_message(&msg.channel_id, &format!("{} Usage: `!antinuke incidents {} <id>`", emojis::ERROR, sub_cmd)).await?;
+                    return Ok(());
+                }
+            };
+            let record = match record {
+                Some(r) => r,
+                None => {
+                    rest.send_message(&msg.channel_id, &format!("{} No incident with that ID exists for this server.", emojis::ERROR)).await?;
+                    return Ok(());
+                }
+            };
+
+            if sub_cmd != "export" {
+                rest.send_embed(&msg.channel_id, detail_embed(&record)).await?;
+                return Ok(());
+            }
+
+            let is_owner = rest.get_guild(guild_id).await
+                .map(|g| g["owner_id"].as_str() == Some(msg.author.id.as_str()))
+                .unwrap_or(false);
+            if !is_owner {
+                rest.send_message(&msg.channel_id, &format!("{} Only the **Server Owner** can export incident records.", emojis::ERROR)).await?;
+                return Ok(());
+            }
+
+            let data = serde_json::to_vec_pretty(&record)?;
+            rest.send_file(
+                &msg.channel_id,
+                &format!("{} Incident #{} export. Attach this file when reporting to Discord Trust & Safety.", emojis::FOLDER, record.id),
+                &format!("incident-{}-{}.json", guild_id, record.id),
+                &data,
+            ).await?;
+        }
+        _ => {
+            let settings = db.get_antinuke_settings(guild_id).await?;
+            let mut module = None;
+            let mut executor_id = None;
+
+            for arg in parts {
+                let trimmed = arg.trim_matches(&['<', '@', '!', '>'][..]);
+                if !trimmed.is_empty() && trimmed.chars().all(|c| c.is_ascii_digit()) {
+                    executor_id = Some(trimmed.to_string());
+                } else if arg.to_lowercase() != "list" {
+                    let lower = arg.to_lowercase();
+                    let prefixed = format!("anti_{}", lower);
+                    module = Some(if settings.contains_key(&lower) { lower } else { prefixed });
+                }
+            }
+
+            let (embed, components) = list_page(&db, guild_id, 0, module.as_deref(), executor_id.as_deref()).await?;
+            rest.send_complex_message(&msg.channel_id, "", vec![embed], components).await?;
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn handle_interaction(
+    rest: &RestClient,
+    interaction: Interaction,
+    db: Arc<Database>,
+) -> anyhow::Result<()> {
+
+    let user_id = interaction.member.as_ref().and_then(|m| m.user.as_ref()).map(|u| u.id.clone()).unwrap_or_default();
+    let guild_id = interaction.guild_id.as_deref().unwrap_or("");
+    let is_bot_admin = db.is_admin(guild_id, &user_id).await.unwrap_or(false);
+    let mut is_owner = false;
+
+    if !guild_id.is_empty() {
+        if let Ok(guild) = rest.get_guild(guild_id).await {
+            if let Some(owner_id) = guild["owner_id"].as_str() {
+                is_owner = owner_id == user_id;
+            }
+        }
+    }
+
+    if !is_bot_admin && !is_owner {
+        rest.interaction_callback(&interaction.id, &interaction.token, json!({
+            "type": 4,
+            "data": {
+                "content": format!("{} Interaction Denied: You must be the **Server Owner** or a **Bot Admin** to browse incidents.", emojis::ERROR),
+                "flags": 64
+            }
+        })).await?;
+        return Ok(());
+    }
+
+    let custom_id = interaction.data.as_ref().and_then(|d| d.custom_id.as_deref()).unwrap_or("");
+    let mut segments = custom_id.split(':');
+    if segments.next() != Some("incidents_page") { return Ok(()); }
+    let page: usize = segments.next().and_then(|p| p.parse().ok()).unwrap_or(0);
+    let module = parse_filter_token(segments.next());
+    let executor_id = parse_filter_token(segments.next());
+
+    let (embed, components) = list_page(&db, guild_id, page, module.as_deref(), executor_id.as_deref()).await?;
+    rest.interaction_callback(&interaction.id, &interaction.token, json!({
+        "type": 7,
+        "data": { "embeds": [embed], "components": components }
+    })).await?;
+
+    Ok(())
+}
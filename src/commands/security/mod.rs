@@ -1,6 +1,11 @@
 pub mod admin;
 pub mod whitelist;
 pub mod antinuke;
+pub mod nightmode;
+pub mod incidents;
+pub mod backup;
+pub mod setup;
+pub mod template;
 
 use crate::models::{Interaction, Message};
 use crate::rest::RestClient;
@@ -21,9 +26,15 @@ pub async fn security_cmd(
         "admin" | "adm" | "extraowner" | "eo" => {
             admin::handle_admin(rest, msg, db, cmd, args).await
         }
-        "security" | "s" | "sec" | "antinuke" | "nightmode" | "nm" => {
+        "security" | "s" | "sec" | "antinuke" => {
             antinuke::handle_antinuke(rest, msg, db, cmd, args).await
         }
+        "nightmode" | "nm" => {
+            nightmode::handle_nightmode(rest, msg, db, args).await
+        }
+        "backup" | "backups" => {
+            backup::handle_backup(rest, msg, db, args).await
+        }
         _ => Ok(()),
     }
 }
@@ -39,6 +50,10 @@ pub async fn handle_interaction(
         antinuke::handle_interaction(rest, interaction, db).await
     } else if custom_id.starts_with("whitelist_") {
         whitelist::handle_interaction(rest, interaction, db).await
+    } else if custom_id.starts_with("incidents_") {
+        incidents::handle_interaction(rest, interaction, db).await
+    } else if custom_id.starts_with("backup_") {
+        backup::handle_interaction(rest, interaction, db).await
     } else if custom_id.starts_with("admin_") {
         admin::handle_interaction(rest, interaction, db).await
     } else {
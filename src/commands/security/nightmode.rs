@@ -0,0 +1,116 @@
+use crate::models::Message;
+use crate::rest::RestClient;
+use crate::db::Database;
+use crate::constants::{emojis, colors};
+use crate::antinuke::nightmode;
+use std::sync::Arc;
+use serde_json::json;
+
+pub async fn handle_nightmode(
+    rest: &RestClient,
+    msg: &Message,
+    db: Arc<Database>,
+    args: &str,
+) -> anyhow::Result<()> {
+    let guild_id = match msg.guild_id.as_deref() {
+        Some(id) => id,
+        None => return Ok(()),
+    };
+
+    let is_bot_admin = db.is_admin(guild_id, &msg.author.id).await.unwrap_or(false);
+    let is_owner = rest.get_guild(guild_id).await
+        .map(|g| g["owner_id"].as_str() == Some(msg.author.id.as_str()))
+        .unwrap_or(false);
+
+    if !is_bot_admin && !is_owner {
+        rest.send_message(&msg.channel_id, &format!("{} This command is restricted to **Server Owners** and **Bot Admins** only.", emojis::ERROR)).await?;
+        return Ok(());
+    }
+
+    let parts: Vec<&str> = args.split_whitespace().collect();
+    let sub_cmd = parts.first().map(|s| s.to_lowercase()).unwrap_or_default();
+    let (active, _, start, end) = db.get_nightmode(guild_id).await?;
+
+    match sub_cmd.as_str() {
+        "on" | "enable" => {
+            if active {
+                rest.send_message(&msg.channel_id, &format!("{} Nightmode is already active.", emojis::INFO)).await?;
+                return Ok(());
+            }
+            let stripped = nightmode::activate(guild_id, false, rest, &db).await?;
+            rest.send_embed(&msg.channel_id, json!({
+                "title": format!("{} Nightmode Enabled", emojis::SHIELD),
+                "description": format!(
+                    "Stripped dangerous permissions from **{}** role(s) below my highest role.\nUse `!nightmode off` to restore them.",
+                    stripped
+                ),
+                "color": colors::MAIN
+            })).await?;
+        }
+        "off" | "disable" => {
+            if !active {
+                rest.send_message(&msg.channel_id, &format!("{} Nightmode is not active.", emojis::INFO)).await?;
+                return Ok(());
+            }
+            let (restored, failed) = nightmode::deactivate(guild_id, rest, &db).await?;
+            let paused = failed == 0 && start.is_some();
+            if paused {
+                db.set_nightmode_override(guild_id, true).await?;
+            }
+            let mut description = format!("Restored the original permissions of **{}** role(s).", restored);
+            if failed > 0 {
+                description.push_str(&format!("\n{} **{}** role(s) could not be restored. Run `!nightmode off` again to retry.", emojis::WARNING, failed));
+            }
+            if paused {
+                description.push_str("\nThe schedule stays off for the current window and resumes with the next one.");
+            }
+            rest.send_embed(&msg.channel_id, json!({
+                "title": format!("{} Nightmode Disabled", emojis::SUCCESS),
+                "description": description,
+                "color": colors::MAIN
+            })).await?;
+        }
+        "schedule" => {
+            let first = parts.get(1).map(|s| s.to_lowercase()).unwrap_or_default();
+            if first == "off" || first == "clear" {
+                db.set_nightmode_schedule(guild_id, None, None).await?;
+                rest.send_message(&msg.channel_id, &format!("{} Nightmode schedule cleared.", emojis::SUCCESS)).await?;
+                return Ok(());
+            }
+
+            let (start_time, end_time) = match (parts.get(1).and_then(|s| nightmode::parse_time(s)), parts.get(2).and_then(|s| nightmode::parse_time(s))) {
+                (Some(s), Some(e)) if s != e => (s.format("%H:%M").to_string(), e.format("%H:%M").to_string()),
+                _ => {
+                    rest.send_message(&msg.channel_id, &format!("{} Usage: `!nightmode schedule <HH:MM> <HH:MM>` (UTC) or `!nightmode schedule off`", emojis::ERROR)).await?;
+                    return Ok(());
+                }
+            };
+
+            db.set_nightmode_schedule(guild_id, Some(&start_time), Some(&end_time)).await?;
+            rest.send_message(&msg.channel_id, &format!("{} Nightmode will run daily from **{}** to **{}** UTC.", emojis::SUCCESS, start_time, end_time)).await?;
+        }
+        "status" | "" => {
+            let saved = db.get_nightmode_roles(guild_id).await.unwrap_or_default();
+            let schedule = match (start, end) {
+                (Some(s), Some(e)) => format!("{} → {} UTC", s, e),
+                _ => "Not scheduled".to_string(),
+            };
+            rest.send_embed(&msg.channel_id, json!({
+                "title": format!("{} Nightmode", emojis::SHIELD),
+                "description": format!(
+                    "**Status:** {}\n**Roles Stripped:** {}\n**Schedule:** {}\n\n\
+                    `!nightmode on` | `!nightmode off` | `!nightmode schedule <HH:MM> <HH:MM>`",
+                    if active { "Active" } else { "Inactive" },
+                    saved.len(),
+                    schedule
+                ),
+                "color": colors::MAIN
+            })).await?;
+        }
+        _ => {
+            rest.send_message(&msg.channel_id, &format!("{} Unknown subcommand. Try `on`, `off`, `schedule`, `status`.", emojis::ERROR)).await?;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,339 @@
+use crate::models::{Interaction, Message};
+use crate::rest::RestClient;
+use crate::db::Database;
+use crate::constants::{emojis, colors};
+use crate::antinuke::{audit, quarantine};
+use std::sync::Arc;
+use serde_json::{json, Value};
+
+const PRESETS: [(&str, &str, &str); 3] = [
+    ("strict", "Strict", "Every module is enabled and punishes offenders immediately."),
+    ("balanced", "Balanced", "Only modules guarding destructive actions are enabled and enforcing; noisier modules stay off."),
+    ("monitor", "Monitor Only", "Every module is enabled but only logs what it would have done. Nothing is punished or reverted."),
+];
+
+const BALANCED_MODULES: [&str; 14] = [
+    "anti_ban", "anti_kick", "anti_bot", "anti_prune",
+    "anti_channel_delete", "anti_role_delete", "anti_role_update", "anti_member_role_update",
+    "anti_everyone_ping", "anti_webhook_create", "anti_server_update", "anti_raid",
+    "auto_recovery", "thread_lock_enabled",
+];
+
+const PUNISHMENTS: [(&str, &str); 5] = [
+    ("ban", "Ban"), ("kick", "Kick"), ("timeout", "Timeout"), ("strip", "Strip Roles"), ("quarantine", "Quarantine"),
+];
+
+const MAX_LISTED: usize = 12;
+
+struct SetupState {
+    preset: String,
+    punishment: String,
+    log_channel: Option<String>,
+}
+
+impl SetupState {
+    fn parse(parts: &[&str]) -> Self {
+        let preset = parts.first().copied().filter(|p| PRESETS.iter().any(|(k, _, _)| k == p)).unwrap_or("strict");
+        let punishment = parts.get(1).copied().filter(|p| PUNISHMENTS.iter().any(|(k, _)| k == p)).unwrap_or("ban");
+        let log_channel = parts.get(2).copied().filter(|c| !c.is_empty() && *c != "none").map(String::from);
+        Self { preset: preset.to_string(), punishment: punishment.to_string(), log_channel }
+    }
+
+    fn id(&self, action: &str) -> String {
+        format!("antinuke_setup:{}:{}:{}:{}", action, self.preset, self.punishment, self.log_channel.as_deref().unwrap_or("none"))
+    }
+
+    fn enables(&self, module: &str) -> bool {
+        self.preset != "balanced" || BALANCED_MODULES.contains(&module)
+    }
+}
+
+pub fn module_label(key: &str) -> String {
+    key.split('_')
+        .map(|word| {
+            let mut c = word.chars();
+            match c.next() {
+                None => String::new(),
+                Some(f) => f.to_uppercase().collect::<String>() + c.as_str(),
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+fn punishment_label(key: &str) -> &str {
+    PUNISHMENTS.iter().find(|(k, _)| *k == key).map(|(_, l)| *l).unwrap_or(key)
+}
+
+fn change(current: &str, new: &str) -> String {
+    if current == new { format!("{} (unchanged)", new) } else { format!("{} → **{}**", current, new) }
+}
+
+fn list_labels(keys: &[&String]) -> String {
+    let mut labels: Vec<String> = keys.iter().take(MAX_LISTED).map(|k| module_label(k)).collect();
+    if keys.len() > MAX_LISTED {
+        labels.push(format!("and {} more", keys.len() - MAX_LISTED));
+    }
+    labels.join(", ")
+}
+
+pub fn field_value(mut value: String) -> String {
+    if value.chars().count() > 1024 {
+        value = value.chars().take(1023).collect::<String>() + "…";
+    }
+    value
+}
+
+fn mode_label(monitored: &std::collections::HashSet<String>) -> &'static str {
+    if monitored.contains("*") { "Monitor Only" } else if monitored.is_empty() { "Enforce" } else { "Mixed" }
+}
+
+fn hierarchy_field(finding: &audit::Finding) -> Value {
+    let mut value = if finding.ok { "My role can act on every role with dangerous permissions.".to_string() } else { finding.details.join("\n") };
+    if let Some(fix) = &finding.fix {
+        value.push_str(&format!("\n**Fix:** {}", fix));
+    }
+    json!({
+        "name": format!("{} {}", if finding.ok { emojis::SUCCESS } else { emojis::WARNING }, finding.check),
+        "value": field_value(value),
+        "inline": false
+    })
+}
+
+async fn preview(guild_id: &str, state: &SetupState, rest: &RestClient, db: &Database) -> anyhow::Result<(Value, Vec<Value>)> {
+    let guild = rest.get_guild(guild_id).await?;
+    let guild_name = guild["name"].as_str().unwrap_or("this server");
+    let settings = db.get_antinuke_settings(guild_id).await?;
+    let monitored = db.list_monitor_modules(guild_id).await.unwrap_or_default();
+    let current_punishment = db.get_punishment(guild_id).await;
+    let (current_log, _) = db.get_alert_options(guild_id).await;
+    let roles = rest.get_guild_roles(guild_id).await?;
+    let hierarchy = audit::hierarchy(guild_id, &roles, rest).await;
+
+    let mut keys: Vec<&String> = settings.keys().collect();
+    keys.sort();
+    let enabled_after = keys.iter().filter(|k| state.enables(k)).count();
+    let turning_on: Vec<&String> = keys.iter().filter(|k| state.enables(k) && !settings.get(**k).cloned().unwrap_or(false)).copied().collect();
+    let turning_off: Vec<&String> = keys.iter().filter(|k| !state.enables(k) && settings.get(**k).cloned().unwrap_or(false)).copied().collect();
+
+    let mut modules = format!("**{}** of **{}** modules will be enabled.", enabled_after, keys.len());
+    if turning_on.is_empty() && turning_off.is_empty() {
+        modules.push_str("\nNo module changes.");
+    }
+    if !turning_on.is_empty() {
+        modules.push_str(&format!("\n**Turning on:** {}", list_labels(&turning_on)));
+    }
+    if !turning_off.is_empty() {
+        modules.push_str(&format!("\n**Turning off:** {}", list_labels(&turning_off)));
+    }
+
+    let (_, preset_name, preset_desc) = PRESETS.iter().find(|(k, _, _)| *k == state.preset).copied().unwrap_or(PRESETS[0]);
+    let new_mode = if state.preset == "monitor" { "Monitor Only" } else { "Enforce" };
+
+    let mut punishment = change(punishment_label(&current_punishment), punishment_label(&state.punishment));
+    if state.punishment == "quarantine" && db.get_quarantine_role(guild_id).await.is_none() {
+        punishment.push_str("\nA `Quarantined` role will be created and hidden from every channel.");
+    }
+
+    let fmt_channel = |c: Option<&str>| c.map(|c| format!("<#{}>", c)).unwrap_or_else(|| "Not set".to_string());
+    let log = match &state.log_channel {
+        Some(_) => change(&fmt_channel(current_log.as_deref()), &fmt_channel(state.log_channel.as_deref())),
+        None => format!("{} (unchanged)", fmt_channel(current_log.as_deref())),
+    };
+
+    let embed = json!({
+        "title": format!("{} Antinuke Setup — {}", emojis::SHIELD, guild_name),
+        "description": "Pick a preset, a punishment and a log channel below, then press **Confirm Setup**. Nothing changes until the **Server Owner** confirms.",
+        "color": colors::MAIN,
+        "fields": [
+            { "name": "Preset", "value": format!("**{}** — {}", preset_name, preset_desc), "inline": false },
+            { "name": "Modules", "value": field_value(modules), "inline": false },
+            { "name": "Mode", "value": change(mode_label(&monitored), new_mode), "inline": true },
+            { "name": "Punishment", "value": punishment, "inline": true },
+            { "name": "Log Channel", "value": log, "inline": true },
+            hierarchy_field(&hierarchy)
+        ],
+        "footer": { "text": "Rimuru Advanced Security | Setup Wizard" }
+    });
+
+    let preset_buttons: Vec<Value> = PRESETS.iter().map(|(key, label, _)| {
+        let next = SetupState { preset: key.to_string(), punishment: state.punishment.clone(), log_channel: state.log_channel.clone() };
+        json!({
+            "type": 2,
+            "style": if *key == state.preset { 1 } else { 2 },
+            "label": label,
+            "custom_id": next.id("preset")
+        })
+    }).collect();
+
+    let punishment_options: Vec<Value> = PUNISHMENTS.iter().map(|(key, label)| json!({
+        "label": label,
+        "value": key,
+        "default": *key == state.punishment
+    })).collect();
+
+    let mut channel_select = json!({
+        "type": 8,
+        "custom_id": state.id("log"),
+        "channel_types": [0, 5],
+        "placeholder": "Select the security log channel...",
+        "min_values": 1,
+        "max_values": 1
+    });
+    if let Some(channel_id) = &state.log_channel {
+        channel_select["default_values"] = json!([{ "id": channel_id, "type": "channel" }]);
+    }
+
+    let components = vec![
+        json!({ "type": 1, "components": preset_buttons }),
+        json!({ "type": 1, "components": [{
+            "type": 3,
+            "custom_id": state.id("punish"),
+            "options": punishment_options,
+            "placeholder": "Select the punishment...",
+            "min_values": 1,
+            "max_values": 1
+        }] }),
+        json!({ "type": 1, "components": [channel_select] }),
+        json!({ "type": 1, "components": [
+            { "type": 2, "style": 3, "label": "Confirm Setup", "custom_id": state.id("confirm") },
+            { "type": 2, "style": 4, "label": "Cancel", "custom_id": "antinuke_setup:cancel" }
+        ] }),
+    ];
+
+    Ok((embed, components))
+}
+
+pub async fn show_setup_wizard(rest: &RestClient, msg: &Message, db: Arc<Database>) -> anyhow::Result<()> {
+    let guild_id = msg.guild_id.as_deref().unwrap_or("");
+    if guild_id.is_empty() { return Ok(()); }
+
+    let punishment = db.get_punishment(guild_id).await;
+    let (log_channel, _) = db.get_alert_options(guild_id).await;
+    let state = SetupState::parse(&["strict", &punishment, log_channel.as_deref().unwrap_or("none")]);
+
+    let (embed, components) = preview(guild_id, &state, rest, &db).await?;
+    rest.send_complex_message(&msg.channel_id, "", vec![embed], components).await?;
+    Ok(())
+}
+
+async fn apply(guild_id: &str, state: &SetupState, rest: &RestClient, db: &Database) -> anyhow::Result<()> {
+    if state.punishment == "quarantine" {
+        quarantine::ensure_role(guild_id, rest, db).await?;
+    }
+
+    if state.preset == "balanced" {
+        db.bulk_update_antinuke(guild_id, false).await?;
+        for module in BALANCED_MODULES {
+            db.update_antinuke_setting(guild_id, module, true).await?;
+        }
+    } else {
+        db.bulk_update_antinuke(guild_id, true).await?;
+    }
+
+    for module in db.list_monitor_modules(guild_id).await? {
+        db.set_monitor_mode(guild_id, &module, false).await?;
+    }
+    if state.preset == "monitor" {
+        db.set_monitor_mode(guild_id, "*", true).await?;
+    }
+
+    db.set_punishment(guild_id, &state.punishment).await?;
+    if let Some(channel_id) = &state.log_channel {
+        db.set_log_channel(guild_id, Some(channel_id)).await?;
+    }
+    Ok(())
+}
+
+async fn summary(guild_id: &str, rest: &RestClient, db: &Database) -> anyhow::Result<Value> {
+    let guild = rest.get_guild(guild_id).await?;
+    let guild_name = guild["name"].as_str().unwrap_or("this server");
+    let settings = db.get_antinuke_settings(guild_id).await?;
+    let monitored = db.list_monitor_modules(guild_id).await.unwrap_or_default();
+    let punishment = db.get_punishment(guild_id).await;
+    let (log_channel, _) = db.get_alert_options(guild_id).await;
+    let roles = rest.get_guild_roles(guild_id).await?;
+    let hierarchy = audit::hierarchy(guild_id, &roles, rest).await;
+    let bot_avatar = rest.validate_token().await?.avatar_url();
+
+    let mut keys: Vec<&String> = settings.keys().collect();
+    keys.sort();
+    let enabled: Vec<String> = keys.iter()
+        .filter(|k| settings.get(**k).cloned().unwrap_or(false))
+        .map(|k| format!("{}: {}", module_label(k), emojis::SUCCESS))
+        .collect();
+
+    let modules = if enabled.is_empty() { "No modules enabled.".to_string() } else { enabled.join("\n") };
+
+    Ok(json!({
+        "title": "🛡️ RIMURU Security",
+        "description": format!(
+            "**Security Settings For {} 🛡️**\n\n**Modules Enabled ({}/{}) 🛡️**\n{}",
+            guild_name, enabled.len(), keys.len(), modules
+        ),
+        "color": colors::MAIN,
+        "thumbnail": { "url": bot_avatar },
+        "fields": [
+            { "name": "Mode", "value": mode_label(&monitored), "inline": true },
+            { "name": "Log Channel", "value": log_channel.map(|c| format!("<#{}>", c)).unwrap_or_else(|| "Not set".to_string()), "inline": true },
+            hierarchy_field(&hierarchy)
+        ],
+        "footer": { "text": format!("Punishment Type: {}", punishment_label(&punishment)) }
+    }))
+}
+
+pub async fn handle_interaction(rest: &RestClient, interaction: &Interaction, db: Arc<Database>, is_owner: bool, args: &str) -> anyhow::Result<()> {
+    let guild_id = interaction.guild_id.as_deref().unwrap_or("");
+    let parts: Vec<&str> = args.split(':').collect();
+    let action = parts.first().copied().unwrap_or("");
+
+    if !is_owner {
+        rest.interaction_callback(&interaction.id, &interaction.token, json!({
+            "type": 4,
+            "data": { "content": format!("{} Only the **Server Owner** can use the antinuke setup.", emojis::ERROR), "flags": 64 }
+        })).await?;
+        return Ok(());
+    }
+
+    if action == "cancel" {
+        rest.interaction_callback(&interaction.id, &interaction.token, json!({
+            "type": 7,
+            "data": { "content": format!("{} Setup cancelled. No settings were changed.", emojis::INFO), "embeds": [], "components": [] }
+        })).await?;
+        return Ok(());
+    }
+
+    let mut state = SetupState::parse(&parts[1..]);
+    let selected = interaction.data.as_ref().and_then(|d| d.values.as_ref()).and_then(|v| v.first()).cloned();
+    match (action, selected) {
+        ("punish", Some(value)) if PUNISHMENTS.iter().any(|(k, _)| *k == value) => state.punishment = value,
+        ("log", Some(value)) => state.log_channel = Some(value),
+        _ => {}
+    }
+
+    if action != "confirm" {
+        let (embed, components) = preview(guild_id, &state, rest, &db).await?;
+        rest.interaction_callback(&interaction.id, &interaction.token, json!({
+            "type": 7,
+            "data": { "embeds": [embed], "components": components }
+        })).await?;
+        return Ok(());
+    }
+
+    rest.interaction_callback(&interaction.id, &interaction.token, json!({
+        "type": 7,
+        "data": { "content": format!("{} Applying security settings...", emojis::LOADING), "components": [] }
+    })).await?;
+
+    let channel_id = interaction.channel_id.as_deref().unwrap_or("");
+    let message_id = interaction.message.as_ref().map(|m| m.id.as_str()).unwrap_or("");
+
+    if let Err(e) = apply(guild_id, &state, rest, &db).await {
+        rest.edit_message(channel_id, message_id, &format!("{} Setup failed: {}", emojis::ERROR, e), vec![], vec![]).await?;
+        return Ok(());
+    }
+
+    let embed = summary(guild_id, rest, &db).await?;
+    rest.edit_message(channel_id, message_id, "", vec![embed], vec![]).await?;
+    Ok(())
+}
@@ -0,0 +1,193 @@
+use crate::models::{Interaction, Message};
+use crate::rest::RestClient;
+use crate::db::Database;
+use crate::constants::{emojis, colors};
+use crate::antinuke::template;
+use super::setup::{field_value, module_label};
+use std::sync::Arc;
+use serde_json::{json, Value};
+
+const PENDING_KIND: &str = "antinuke_import";
+
+fn id_list(ids: &[String], mention: bool) -> String {
+    ids.iter()
+        .map(|id| if mention { format!("<@{}>", id) } else { format!("`{}`", id) })
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+fn preview_fields(diff: &template::TemplateDiff) -> Vec<Value> {
+    let mut fields = Vec::new();
+    let mut push = |name: &str, value: String| {
+        if !value.is_empty() {
+            fields.push(json!({ "name": name, "value": field_value(value), "inline": false }));
+        }
+    };
+
+    let labels = |keys: &[String]| keys.iter().map(|k| module_label(k)).collect::<Vec<String>>().join(", ");
+    let mut modules = Vec::new();
+    if !diff.enabled.is_empty() { modules.push(format!("**Turning on:** {}", labels(&diff.enabled))); }
+    if !diff.disabled.is_empty() { modules.push(format!("**Turning off:** {}", labels(&diff.disabled))); }
+    push("Modules", modules.join("\n"));
+
+    let mut monitor = Vec::new();
+    if !diff.monitor_added.is_empty() { monitor.push(format!("**Monitor:** {}", diff.monitor_added.join(", "))); }
+    if !diff.monitor_removed.is_empty() { monitor.push(format!("**Enforce:** {}", diff.monitor_removed.join(", "))); }
+    push("Monitor Mode", monitor.join("\n"));
+
+    push("Thresholds", diff.thresholds_changed.iter()
+        .map(|(module, before, after)| format!("`{}`: {} → **{}**", module, before, after))
+        .collect::<Vec<String>>()
+        .join("\n"));
+
+    push("Options", diff.options_changed.iter()
+        .map(|(field, before, after)| format!("`{}`: {} → **{}**", field, before, after))
+        .collect::<Vec<String>>()
+        .join("\n"));
+
+    let mut whitelist = Vec::new();
+    if !diff.whitelist_added.is_empty() { whitelist.push(format!("**Adding:** {}", id_list(&diff.whitelist_added, false))); }
+    if !diff.whitelist_changed.is_empty() { whitelist.push(format!("**Updating:** {}", id_list(&diff.whitelist_changed, false))); }
+    if !diff.whitelist_removed.is_empty() { whitelist.push(format!("**Removing:** {}", id_list(&diff.whitelist_removed, false))); }
+    push("Whitelist", whitelist.join("\n"));
+
+    let mut bots = Vec::new();
+    if !diff.bots_added.is_empty() { bots.push(format!("**Adding:** {}", id_list(&diff.bots_added, true))); }
+    if !diff.bots_removed.is_empty() { bots.push(format!("**Removing:** {}", id_list(&diff.bots_removed, true))); }
+    push("Trusted Bots", bots.join("\n"));
+
+    fields.truncate(25);
+    fields
+}
+
+pub async fn export_config(rest: &RestClient, msg: &Message, db: Arc<Database>) -> anyhow::Result<()> {
+    let guild_id = msg.guild_id.as_deref().unwrap_or("");
+    if guild_id.is_empty() { return Ok(()); }
+
+    let doc = template::export(guild_id, &db).await?;
+    let data = serde_json::to_vec_pretty(&doc)?;
+    rest.send_file(
+        &msg.channel_id,
+        &format!("{} Antinuke template exported. Attach this file to `!antinuke import` in another server to apply the same policy.", emojis::FOLDER),
+        &format!("antinuke-{}.json", guild_id),
+        &data,
+    ).await?;
+    Ok(())
+}
+
+pub async fn import_config(rest: &RestClient, msg: &Message, db: Arc<Database>) -> anyhow::Result<()> {
+    let guild_id = msg.guild_id.as_deref().unwrap_or("");
+    if guild_id.is_empty() { return Ok(()); }
+
+    let attachment = match msg.attachments.first() {
+        Some(a) => a,
+        None => {
+            rest.send_message(&msg.channel_id, &format!("{} Attach an exported template file: `!antinuke import` + `antinuke-<id>.json`.", emojis::ERROR)).await?;
+            return Ok(());
+        }
+    };
+    if attachment.size > template::MAX_TEMPLATE_BYTES {
+        rest.send_message(&msg.channel_id, &format!("{} `{}` is too large to be an antinuke template.", emojis::ERROR, attachment.filename)).await?;
+        return Ok(());
+    }
+
+    let data = rest.download_attachment(&attachment.url).await?;
+    let doc: Value = match serde_json::from_slice(&data) {
+        Ok(v) => v,
+        Err(e) => {
+            rest.send_message(&msg.channel_id, &format!("{} `{}` is not valid JSON: {}", emojis::ERROR, attachment.filename, e)).await?;
+            return Ok(());
+        }
+    };
+
+    let settings = db.get_antinuke_settings(guild_id).await?;
+    if let Err(e) = template::validate(&doc, &settings) {
+        rest.send_message(&msg.channel_id, &format!("{} Invalid template: {}", emojis::ERROR, e)).await?;
+        return Ok(());
+    }
+
+    let current = template::export(guild_id, &db).await?;
+    let diff = template::diff(&doc, &current);
+    if diff.is_empty() {
+        rest.send_message(&msg.channel_id, &format!("{} This server already matches the template. Nothing to import.", emojis::INFO)).await?;
+        return Ok(());
+    }
+
+    db.save_snapshot(guild_id, PENDING_KIND, &msg.id, &doc).await?;
+
+    let embed = json!({
+        "title": format!("{} Antinuke Import Preview", emojis::SHIELD),
+        "description": format!(
+            "Template `{}` exported from `{}`.\nReview the changes below. Nothing is applied until the **Server Owner** confirms.",
+            attachment.filename,
+            doc["source_guild"].as_str().unwrap_or("unknown")
+        ),
+        "color": colors::MAIN,
+        "fields": preview_fields(&diff),
+        "footer": { "text": "Rimuru Advanced Security | Template Import" }
+    });
+    let components = vec![json!({
+        "type": 1,
+        "components": [
+            { "type": 2, "style": 3, "label": "Apply Template", "custom_id": format!("antinuke_import:confirm:{}", msg.id) },
+            { "type": 2, "style": 4, "label": "Cancel", "custom_id": format!("antinuke_import:cancel:{}", msg.id) }
+        ]
+    })];
+
+    rest.send_complex_message(&msg.channel_id, "", vec![embed], components).await?;
+    Ok(())
+}
+
+pub async fn handle_interaction(rest: &RestClient, interaction: &Interaction, db: Arc<Database>, is_owner: bool, args: &str) -> anyhow::Result<()> {
+    let guild_id = interaction.guild_id.as_deref().unwrap_or("");
+
+    if !is_owner {
+        rest.interaction_callback(&interaction.id, &interaction.token, json!({
+            "type": 4,
+            "data": { "content": format!("{} Only the **Server Owner** can apply an antinuke template.", emojis::ERROR), "flags": 64 }
+        })).await?;
+        return Ok(());
+    }
+
+    let (decision, pending_id) = args.split_once(':').unwrap_or(("", ""));
+    let doc = db.get_snapshot(guild_id, PENDING_KIND, pending_id).await?;
+    db.delete_snapshot(guild_id, PENDING_KIND, pending_id).await?;
+
+    let doc = match (decision, doc) {
+        ("confirm", Some(doc)) => doc,
+        ("confirm", None) => {
+            rest.interaction_callback(&interaction.id, &interaction.token, json!({
+                "type": 7,
+                "data": { "content": format!("{} This import has already been handled. Run `!antinuke import` again.", emojis::ERROR), "embeds": [], "components": [] }
+            })).await?;
+            return Ok(());
+        }
+        _ => {
+            rest.interaction_callback(&interaction.id, &interaction.token, json!({
+                "type": 7,
+                "data": { "content": format!("{} Import cancelled. No settings were changed.", emojis::INFO), "embeds": [], "components": [] }
+            })).await?;
+            return Ok(());
+        }
+    };
+
+    rest.interaction_callback(&interaction.id, &interaction.token, json!({
+        "type": 7,
+        "data": { "content": format!("{} Applying antinuke template...", emojis::LOADING), "embeds": [], "components": [] }
+    })).await?;
+
+    let channel_id = interaction.channel_id.as_deref().unwrap_or("");
+    let message_id = interaction.message.as_ref().map(|m| m.id.as_str()).unwrap_or("");
+    let content = match template::apply(guild_id, &doc, rest, &db).await {
+        Ok(warnings) => {
+            let mut text = format!("{} Antinuke template applied. Run `!antinuke settings` to review the result.", emojis::SUCCESS);
+            for warning in warnings {
+                text.push_str(&format!("\n{} {}", emojis::WARNING, warning));
+            }
+            text.chars().take(2000).collect()
+        }
+        Err(e) => format!("{} Import failed: {}", emojis::ERROR, e),
+    };
+    rest.edit_message(channel_id, message_id, &content, vec![], vec![]).await?;
+    Ok(())
+}
@@ -1,8 +1,9 @@
 use crate::models::{Interaction, Message};
 use crate::rest::RestClient;
-use crate::db::Database;
+use crate::db::{Database, WhitelistEntry};
 use crate::constants::emojis;
 use std::sync::Arc;
+use serde_json::{json, Value};
 
 pub async fn handle_whitelist(
     rest: &RestClient,
@@ -12,14 +13,17 @@ pub async fn handle_whitelist(
     args: &str,
 ) -> anyhow::Result<()> {
 
-    let is_bot_admin = db.is_admin(&msg.author.id).await.unwrap_or(false);
+    let guild_id = match msg.guild_id.as_deref() {
+        Some(id) => id,
+        None => return Ok(()),
+    };
+
+    let is_bot_admin = db.is_admin(guild_id, &msg.author.id).await.unwrap_or(false);
     let mut is_owner = false;
 
-    if let Some(guild_id) = &msg.guild_id {
-        if let Ok(guild) = rest.get_guild(guild_id).await {
-            if let Some(owner_id) = guild["owner_id"].as_str() {
-                is_owner = owner_id == msg.author.id;
-            }
+    if let Ok(guild) = rest.get_guild(guild_id).await {
+        if let Some(owner_id) = guild["owner_id"].as_str() {
+            is_owner = owner_id == msg.author.id;
         }
     }
 
@@ -33,39 +37,63 @@ pub async fn handle_whitelist(
     match *sub_cmd {
         "add" => {
             let target = parts.get(1).unwrap_or(&"");
-            let user_id = extract_id(target);
-            if user_id.is_empty() {
-                rest.send_message(&msg.channel_id, &format!("{} Please mention a valid user to whitelist.", emojis::ERROR)).await?;
-                return Ok(());
+            let (target_id, kind) = match resolve_target(rest, guild_id, target).await {
+                Some(t) => t,
+                None => {
+                    rest.send_message(&msg.channel_id, &format!("{} Please mention a valid user or role to whitelist.", emojis::ERROR)).await?;
+                    return Ok(());
+                }
+            };
+
+            let available = antinuke_modules(&db, guild_id).await;
+            let mut modules = Vec::new();
+            for raw in &parts[2..] {
+                let raw_lower = raw.to_lowercase();
+                let module = [raw_lower.clone(), format!("anti_{}", raw_lower), format!("{}_enabled", raw_lower)]
+                    .into_iter()
+                    .find(|m| available.contains(m));
+                match module {
+                    Some(module) => modules.push(module),
+                    None => {
+                        rest.send_message(&msg.channel_id, &format!("{} Unknown module `{}`.", emojis::ERROR, raw)).await?;
+                        return Ok(());
+                    }
+                }
             }
-            db.add_whitelist(user_id, target).await?;
-            rest.send_message(&msg.channel_id, &format!("{} Added <@{}> to the whitelist.", emojis::SUCCESS, user_id)).await?;
+
+            db.add_whitelist(guild_id, &target_id, kind, target, &modules).await?;
+            let entry = db.get_whitelist_entry(guild_id, &target_id).await;
+            let content = scope_message(&target_id, kind, entry.as_ref().map(|e| e.modules.as_slice()).unwrap_or(&[]));
+            rest.send_complex_message(&msg.channel_id, &content, vec![], scope_panel(&target_id, &available, entry.as_ref())).await?;
         }
         "remove" => {
             let target = parts.get(1).unwrap_or(&"");
-            let user_id = extract_id(target);
-            if user_id.is_empty() {
-                rest.send_message(&msg.channel_id, &format!("{} Please mention a valid user to remove.", emojis::ERROR)).await?;
+            let target_id = match extract_id(target) {
+                "" if target.chars().all(|c| c.is_ascii_digit()) => *target,
+                id => id,
+            };
+            if target_id.is_empty() {
+                rest.send_message(&msg.channel_id, &format!("{} Please mention a valid user or role to remove.", emojis::ERROR)).await?;
                 return Ok(());
             }
-            db.remove_whitelist(user_id).await?;
-            rest.send_message(&msg.channel_id, &format!("{} Removed <@{}> from the whitelist.", emojis::SUCCESS, user_id)).await?;
+            db.remove_whitelist(guild_id, target_id).await?;
+            rest.send_message(&msg.channel_id, &format!("{} Removed `{}` from the whitelist for this server.", emojis::SUCCESS, target_id)).await?;
         }
         "list" => {
-            let list = db.list_whitelist().await?;
+            let list = db.list_whitelist(guild_id).await?;
             if list.is_empty() {
-                rest.send_message(&msg.channel_id, &format!("{} The whitelist is currently empty.", emojis::INFO)).await?;
+                rest.send_message(&msg.channel_id, &format!("{} The whitelist for this server is currently empty.", emojis::INFO)).await?;
             } else {
-                let mut content = format!("{} **Whitelisted Users:**\n", emojis::BOOK);
-                for (id, name) in list {
-                    content.push_str(&format!("• <@{}> ({})\n", id, name));
+                let mut content = format!("{} **Whitelisted Users & Roles:**\n", emojis::BOOK);
+                for entry in list {
+                    content.push_str(&format!("• {} — {}\n", mention(&entry.target_id, &entry.kind), describe_modules(&entry.modules)));
                 }
                 rest.send_message(&msg.channel_id, &content).await?;
             }
         }
         "" => {
 
-            rest.send_message(&msg.channel_id, &format!("{} Use `add`, `remove`, or `list` subcommands.", emojis::SHIELD)).await?;
+            rest.send_message(&msg.channel_id, &format!("{} Use `add <@user|@role> [modules...]`, `remove`, or `list` subcommands.", emojis::SHIELD)).await?;
         }
         _ => {
             rest.send_message(&msg.channel_id, &format!("{} Unknown subcommand. Try `add`, `remove`, `list`.", emojis::ERROR)).await?;
@@ -74,6 +102,87 @@ pub async fn handle_whitelist(
     Ok(())
 }
 
+async fn resolve_target(rest: &RestClient, guild_id: &str, target: &str) -> Option<(String, &'static str)> {
+    if target.starts_with("<@&") {
+        let id = extract_id(target);
+        return if id.is_empty() { None } else { Some((id.to_string(), "role")) };
+    }
+
+    let id = match extract_id(target) {
+        "" if !target.is_empty() && target.chars().all(|c| c.is_ascii_digit()) => target,
+        "" => return None,
+        id => return Some((id.to_string(), "user")),
+    };
+
+    let is_role = rest.get_guild_roles(guild_id).await
+        .map(|roles| roles.iter().any(|r| r["id"].as_str() == Some(id)))
+        .unwrap_or(false);
+    Some((id.to_string(), if is_role { "role" } else { "user" }))
+}
+
+async fn antinuke_modules(db: &Database, guild_id: &str) -> Vec<String> {
+    let settings = db.get_antinuke_settings(guild_id).await.unwrap_or_default();
+    let mut modules: Vec<String> = settings.keys()
+        .filter(|k| k.starts_with("anti_") || *k == "thread_lock_enabled")
+        .cloned()
+        .collect();
+    modules.sort();
+    modules
+}
+
+fn mention(target_id: &str, kind: &str) -> String {
+    if kind == "role" { format!("<@&{}>", target_id) } else { format!("<@{}>", target_id) }
+}
+
+fn describe_modules(modules: &[String]) -> String {
+    if modules.iter().any(|m| m == "*") {
+        "all modules".to_string()
+    } else {
+        modules.iter().map(|m| format!("`{}`", m)).collect::<Vec<_>>().join(", ")
+    }
+}
+
+fn scope_message(target_id: &str, kind: &str, modules: &[String]) -> String {
+    let mut content = format!(
+        "{} {} is whitelisted for **{}** in this server.",
+        emojis::SUCCESS, mention(target_id, kind), describe_modules(modules)
+    );
+    if kind == "role" {
+        content.push_str("\nMembers are trusted only while they hold this role.");
+    }
+    content.push_str("\nUse the menus below to choose which antinuke modules are bypassed.");
+    content
+}
+
+fn scope_pages(available: &[String]) -> Vec<Vec<String>> {
+    let mut options = vec!["*".to_string()];
+    options.extend(available.iter().cloned());
+    options.chunks(25).map(|c| c.to_vec()).collect()
+}
+
+fn scope_panel(target_id: &str, available: &[String], entry: Option<&WhitelistEntry>) -> Vec<Value> {
+    let current: Vec<String> = entry.map(|e| e.modules.clone()).unwrap_or_default();
+
+    scope_pages(available).into_iter().enumerate().map(|(page, options)| {
+        let opts: Vec<Value> = options.iter().map(|m| json!({
+            "label": if m == "*" { "ALL MODULES".to_string() } else { m.replace("anti_", "").replace('_', " ").to_uppercase() },
+            "value": m,
+            "default": current.contains(m)
+        })).collect();
+        json!({
+            "type": 1,
+            "components": [{
+                "type": 3,
+                "custom_id": format!("whitelist_scope:{}:{}", page, target_id),
+                "options": opts,
+                "placeholder": if page == 0 { "Select modules to bypass..." } else { "More modules..." },
+                "min_values": 1,
+                "max_values": options.len()
+            }]
+        })
+    }).collect()
+}
+
 pub async fn handle_interaction(
     rest: &RestClient,
     interaction: Interaction,
@@ -81,10 +190,10 @@ pub async fn handle_interaction(
 ) -> anyhow::Result<()> {
 
     let user_id = interaction.member.as_ref().and_then(|m| m.user.as_ref()).map(|u| u.id.clone()).unwrap_or_default();
-    let is_bot_admin = db.is_admin(&user_id).await.unwrap_or(false);
+    let guild_id = interaction.guild_id.as_deref().unwrap_or("");
+    let is_bot_admin = db.is_admin(guild_id, &user_id).await.unwrap_or(false);
     let mut is_owner = false;
 
-    let guild_id = interaction.guild_id.as_deref().unwrap_or("");
     if !guild_id.is_empty() {
         if let Ok(guild) = rest.get_guild(guild_id).await {
             if let Some(owner_id) = guild["owner_id"].as_str() {
@@ -95,7 +204,7 @@ pub async fn handle_interaction(
 
     if !is_bot_admin && !is_owner {
 
-        rest.interaction_callback(&interaction.id, &interaction.token, serde_json::json!({
+        rest.interaction_callback(&interaction.id, &interaction.token, json!({
             "type": 4,
             "data": {
                 "content": format!("{} Interaction Denied: You must be the Server Owner or a Bot Admin to use the Whitelist Control Panel.", emojis::ERROR),
@@ -105,6 +214,56 @@ pub async fn handle_interaction(
         return Ok(());
     }
 
+    let data = match &interaction.data {
+        Some(d) => d,
+        None => return Ok(()),
+    };
+    let custom_id = data.custom_id.as_deref().unwrap_or("");
+    let mut segments = custom_id.splitn(3, ':');
+    if segments.next() != Some("whitelist_scope") { return Ok(()); }
+    let page: usize = segments.next().and_then(|p| p.parse().ok()).unwrap_or(0);
+    let target_id = segments.next().unwrap_or("");
+
+    let entry = match db.get_whitelist_entry(guild_id, target_id).await {
+        Some(e) => e,
+        None => {
+            rest.interaction_callback(&interaction.id, &interaction.token, json!({
+                "type": 4,
+                "data": { "content": format!("{} That entry is no longer on the whitelist.", emojis::ERROR), "flags": 64 }
+            })).await?;
+            return Ok(());
+        }
+    };
+
+    let available = antinuke_modules(&db, guild_id).await;
+    let page_options = scope_pages(&available).get(page).cloned().unwrap_or_default();
+    let selected: Vec<String> = data.values.clone().unwrap_or_default()
+        .into_iter()
+        .filter(|v| page_options.contains(v))
+        .collect();
+
+    let modules: Vec<String> = if selected.iter().any(|m| m == "*") {
+        vec!["*".to_string()]
+    } else {
+        let mut kept: Vec<String> = entry.modules.iter()
+            .filter(|m| *m != "*" && !page_options.contains(m))
+            .cloned()
+            .collect();
+        kept.extend(selected);
+        kept
+    };
+
+    db.add_whitelist(guild_id, target_id, &entry.kind, &entry.name, &modules).await?;
+    let updated = db.get_whitelist_entry(guild_id, target_id).await;
+
+    rest.interaction_callback(&interaction.id, &interaction.token, json!({
+        "type": 7,
+        "data": {
+            "content": scope_message(target_id, &entry.kind, &modules),
+            "components": scope_panel(target_id, &available, updated.as_ref())
+        }
+    })).await?;
+
     Ok(())
 }
 
@@ -0,0 +1,72 @@
+
+pub const KICK_MEMBERS: u64      = 1 << 1;
+pub const BAN_MEMBERS: u64       = 1 << 2;
+pub const ADMINISTRATOR: u64     = 1 << 3;
+pub const MANAGE_CHANNELS: u64   = 1 << 4;
+pub const MANAGE_GUILD: u64      = 1 << 5;
+pub const VIEW_AUDIT_LOG: u64    = 1 << 7;
+pub const VIEW_CHANNEL: u64      = 1 << 10;
+pub const SEND_MESSAGES: u64     = 1 << 11;
+pub const MANAGE_MESSAGES: u64   = 1 << 13;
+pub const MENTION_EVERYONE: u64  = 1 << 17;
+pub const MANAGE_NICKNAMES: u64  = 1 << 27;
+pub const MANAGE_ROLES: u64      = 1 << 28;
+pub const MANAGE_WEBHOOKS: u64   = 1 << 29;
+pub const MANAGE_GUILD_EXPRESSIONS: u64 = 1 << 30;
+pub const MANAGE_EVENTS: u64     = 1 << 33;
+pub const MANAGE_THREADS: u64    = 1 << 34;
+pub const SEND_MESSAGES_IN_THREADS: u64 = 1 << 38;
+
+pub const DANGEROUS: u64 = ADMINISTRATOR
+    | BAN_MEMBERS
+    | MANAGE_GUILD
+    | MANAGE_ROLES
+    | MANAGE_CHANNELS
+    | MANAGE_WEBHOOKS
+    | MENTION_EVERYONE;
+
+pub const NIGHTMODE: u64 = ADMINISTRATOR
+    | BAN_MEMBERS
+    | KICK_MEMBERS
+    | MANAGE_CHANNELS
+    | MANAGE_GUILD
+    | MANAGE_MESSAGES
+    | MANAGE_NICKNAMES
+    | MANAGE_ROLES
+    | MANAGE_WEBHOOKS
+    | MANAGE_GUILD_EXPRESSIONS
+    | MANAGE_EVENTS
+    | MANAGE_THREADS;
+
+pub const ANTINUKE: u64 = VIEW_AUDIT_LOG
+    | BAN_MEMBERS
+    | KICK_MEMBERS
+    | MANAGE_GUILD
+    | MANAGE_ROLES
+    | MANAGE_CHANNELS
+    | MANAGE_WEBHOOKS
+    | MANAGE_MESSAGES
+    | MANAGE_GUILD_EXPRESSIONS;
+
+pub const NAMES: [(u64, &str); 16] = [
+    (ADMINISTRATOR, "Administrator"),
+    (BAN_MEMBERS, "Ban Members"),
+    (KICK_MEMBERS, "Kick Members"),
+    (MANAGE_GUILD, "Manage Server"),
+    (MANAGE_ROLES, "Manage Roles"),
+    (MANAGE_CHANNELS, "Manage Channels"),
+    (MANAGE_WEBHOOKS, "Manage Webhooks"),
+    (MENTION_EVERYONE, "Mention Everyone"),
+    (MANAGE_MESSAGES, "Manage Messages"),
+    (MANAGE_NICKNAMES, "Manage Nicknames"),
+    (MANAGE_GUILD_EXPRESSIONS, "Manage Expressions"),
+    (MANAGE_EVENTS, "Manage Events"),
+    (MANAGE_THREADS, "Manage Threads"),
+    (VIEW_AUDIT_LOG, "View Audit Log"),
+    (VIEW_CHANNEL, "View Channel"),
+    (SEND_MESSAGES, "Send Messages"),
+];
+
+pub fn names(perms: u64) -> Vec<&'static str> {
+    NAMES.iter().filter(|(bit, _)| perms & bit != 0).map(|(_, name)| *name).collect()
+}
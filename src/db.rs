@@ -3,15 +3,89 @@ use std::sync::Arc;
 use tokio::sync::{Mutex, RwLock};
 use std::collections::{HashMap, HashSet, VecDeque};
 use tokio::time::Instant;
-use tracing::{info, error};
+use std::env;
+use tracing::{info, warn, error};
+use crate::antinuke::tracker::{RateTracker, TrackerKey, TrackedWindow};
+
+fn access_key(guild_id: &str, user_id: &str) -> String {
+    format!("{}:{}", guild_id, user_id)
+}
+
+#[derive(Clone)]
+pub struct WhitelistEntry {
+    pub target_id: String,
+    pub name: String,
+    pub kind: String,
+    pub modules: Vec<String>,
+}
+
+impl WhitelistEntry {
+    fn from_row(target_id: String, name: Option<String>, kind: Option<String>, modules: Option<String>) -> Self {
+        Self {
+            target_id,
+            name: name.unwrap_or_default(),
+            kind: kind.unwrap_or_else(|| "user".to_string()),
+            modules: modules.unwrap_or_else(|| "*".to_string())
+                .split(',')
+                .map(|m| m.trim().to_string())
+                .filter(|m| !m.is_empty())
+                .collect(),
+        }
+    }
+
+    pub fn covers(&self, module: &str) -> bool {
+        self.modules.iter().any(|m| m == "*" || m == module)
+    }
+}
+
+#[derive(Clone, serde::Serialize)]
+pub struct IncidentRecord {
+    pub id: i64,
+    pub guild_id: String,
+    pub module: String,
+    pub executor_id: String,
+    pub targets: Vec<String>,
+    pub action: String,
+    pub punishment: String,
+    pub recovery: Vec<String>,
+    pub audit_entry_id: Option<String>,
+    pub succeeded: bool,
+    pub detected_at: String,
+    pub resolved_at: String,
+}
+
+impl IncidentRecord {
+    fn from_row(row: &rusqlite::Row) -> rusqlite::Result<Self> {
+        let targets: String = row.get(4)?;
+        let recovery: String = row.get(7)?;
+        Ok(Self {
+            id: row.get(0)?,
+            guild_id: row.get(1)?,
+            module: row.get(2)?,
+            executor_id: row.get(3)?,
+            targets: serde_json::from_str(&targets).unwrap_or_default(),
+            action: row.get(5)?,
+            punishment: row.get(6)?,
+            recovery: serde_json::from_str(&recovery).unwrap_or_default(),
+            audit_entry_id: row.get(8)?,
+            succeeded: row.get::<_, i64>(9)? != 0,
+            detected_at: row.get(10)?,
+            resolved_at: row.get(11)?,
+        })
+    }
+}
+
+const INCIDENT_COLUMNS: &str = "id, guild_id, module, executor_id, targets, action, punishment, recovery, audit_entry_id, succeeded, detected_at, resolved_at";
 
 pub struct Database {
     conn: Arc<Mutex<Connection>>,
-    pub ban_tracker: Arc<RwLock<HashMap<String, VecDeque<Instant>>>>,
-    pub channel_tracker: Arc<RwLock<HashMap<String, VecDeque<Instant>>>>,
+    rate_tracker: RateTracker,
+    pub prune_tracker: Arc<RwLock<HashMap<String, (Instant, String)>>>,
+    pub raid_tracker: Arc<RwLock<HashMap<String, crate::antinuke::raid::RaidState>>>,
+    pub member_roles_cache: Arc<RwLock<HashMap<String, Vec<String>>>>,
 
     pub settings_cache: Arc<RwLock<HashMap<String, Arc<HashMap<String, bool>>>>>,
-    pub whitelist_cache: Arc<RwLock<HashSet<String>>>,
+    pub whitelist_cache: Arc<RwLock<HashMap<String, Vec<WhitelistEntry>>>>,
     pub admin_cache: Arc<RwLock<HashSet<String>>>,
     pub prefix_cache: Arc<RwLock<HashMap<String, String>>>,
 }
@@ -21,10 +95,12 @@ impl Database {
         let conn = Connection::open(path)?;
         let db = Self {
             conn: Arc::new(Mutex::new(conn)),
-            ban_tracker: Arc::new(RwLock::new(HashMap::new())),
-            channel_tracker: Arc::new(RwLock::new(HashMap::new())),
+            rate_tracker: RateTracker::default(),
+            prune_tracker: Arc::new(RwLock::new(HashMap::new())),
+            raid_tracker: Arc::new(RwLock::new(HashMap::new())),
+            member_roles_cache: Arc::new(RwLock::new(HashMap::new())),
             settings_cache: Arc::new(RwLock::new(HashMap::new())),
-            whitelist_cache: Arc::new(RwLock::new(HashSet::new())),
+            whitelist_cache: Arc::new(RwLock::new(HashMap::new())),
             admin_cache: Arc::new(RwLock::new(HashSet::new())),
             prefix_cache: Arc::new(RwLock::new(HashMap::new())),
         };
@@ -36,39 +112,117 @@ impl Database {
     async fn preload_caches(&self) -> anyhow::Result<()> {
         let conn = self.conn.lock().await;
 
-        let mut stmt = conn.prepare("SELECT user_id FROM whitelist")?;
-        let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+        let mut stmt = conn.prepare("SELECT guild_id, user_id, username, kind, modules FROM whitelist")?;
+        let rows = stmt.query_map([], |row| {
+            let guild_id: String = row.get(0)?;
+            Ok((guild_id, WhitelistEntry::from_row(row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?)))
+        })?;
         let mut wl_cache = self.whitelist_cache.write().await;
         for row in rows {
-            if let Ok(id) = row {
-                wl_cache.insert(id);
+            if let Ok((guild_id, entry)) = row {
+                wl_cache.entry(guild_id).or_default().push(entry);
             }
         }
 
-        let mut stmt_admin = conn.prepare("SELECT user_id FROM admins")?;
-        let rows_admin = stmt_admin.query_map([], |row| row.get::<_, String>(0))?;
+        let mut stmt_admin = conn.prepare("SELECT guild_id, user_id FROM admins")?;
+        let rows_admin = stmt_admin.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?;
         let mut admin_cache = self.admin_cache.write().await;
         for row in rows_admin {
-            if let Ok(id) = row {
-                admin_cache.insert(id);
+            if let Ok((guild_id, user_id)) = row {
+                admin_cache.insert(access_key(&guild_id, &user_id));
+            }
+        }
+        Ok(())
+    }
+
+    fn migrate_access_table(conn: &Connection, table: &str) -> anyhow::Result<()> {
+        let exists: i32 = conn.query_row(
+            "SELECT count(*) FROM sqlite_master WHERE type = 'table' AND name = ?",
+            [table],
+            |row| row.get(0),
+        )?;
+        let scoped: i32 = conn.query_row(
+            &format!("SELECT count(*) FROM pragma_table_info('{}') WHERE name = 'guild_id'", table),
+            [],
+            |row| row.get(0),
+        )?;
+        let legacy = format!("{}_legacy", table);
+        let create = format!("CREATE TABLE IF NOT EXISTS {} (guild_id TEXT, user_id TEXT, username TEXT, PRIMARY KEY (guild_id, user_id))", table);
+        let tx = conn.unchecked_transaction()?;
+
+        if exists == 1 && scoped == 0 {
+            tx.execute(&format!("ALTER TABLE {} RENAME TO {}", table, legacy), [])?;
+            tx.execute(&create, [])?;
+        }
+
+        let legacy_exists: i32 = tx.query_row(
+            "SELECT count(*) FROM sqlite_master WHERE type = 'table' AND name = ?",
+            [&legacy],
+            |row| row.get(0),
+        )?;
+        let pending: i64 = if legacy_exists == 1 {
+            tx.query_row(&format!("SELECT count(*) FROM {}", legacy), [], |row| row.get(0))?
+        } else {
+            0
+        };
+
+        if pending > 0 {
+            match env::var("LEGACY_ACCESS_GUILD_ID") {
+                Ok(guild_id) if !guild_id.trim().is_empty() => {
+                    tx.execute(&create, [])?;
+                    let moved = tx.execute(
+                        &format!("INSERT OR IGNORE INTO {} (guild_id, user_id, username) SELECT ?, user_id, username FROM {}", table, legacy),
+                        [guild_id.trim()],
+                    )?;
+                    tx.execute(&format!("DELETE FROM {}", legacy), [])?;
+                    info!("Migration: Scoped {} legacy {} entries to guild {}", moved, table, guild_id.trim());
+                }
+                _ => {
+                    warn!(
+                        "Migration: {} legacy global {} entries in {} are not trusted. \
+                         Set LEGACY_ACCESS_GUILD_ID before startup to scope them to a guild, or re-add them per server.",
+                        pending, table, legacy
+                    );
+                }
             }
         }
+
+        tx.commit()?;
         Ok(())
     }
 
     async fn init(&self) -> anyhow::Result<()> {
         let conn = self.conn.lock().await;
+        Self::migrate_access_table(&conn, "whitelist")?;
+        Self::migrate_access_table(&conn, "admins")?;
         conn.execute(
             "CREATE TABLE IF NOT EXISTS whitelist (
-                user_id TEXT PRIMARY KEY,
-                username TEXT
+                guild_id TEXT,
+                user_id TEXT,
+                username TEXT,
+                kind TEXT DEFAULT 'user',
+                modules TEXT DEFAULT '*',
+                PRIMARY KEY (guild_id, user_id)
             )",
             [],
         )?;
+        for (col, default_val) in [("kind", "'user'"), ("modules", "'*'")] {
+            let exists: i32 = conn.query_row(
+                "SELECT count(*) FROM pragma_table_info('whitelist') WHERE name = ?",
+                [col],
+                |row| row.get(0),
+            )?;
+            if exists == 0 {
+                conn.execute(&format!("ALTER TABLE whitelist ADD COLUMN {} TEXT DEFAULT {}", col, default_val), [])?;
+                info!("Migration: Added column {} to whitelist", col);
+            }
+        }
         conn.execute(
             "CREATE TABLE IF NOT EXISTS admins (
-                user_id TEXT PRIMARY KEY,
-                username TEXT
+                guild_id TEXT,
+                user_id TEXT,
+                username TEXT,
+                PRIMARY KEY (guild_id, user_id)
             )",
             [],
         )?;
@@ -79,6 +233,52 @@ impl Database {
         conn.execute("CREATE TABLE IF NOT EXISTS bypass_users (guild_id TEXT, user_id TEXT, PRIMARY KEY (guild_id, user_id))", [])?;
         conn.execute("CREATE TABLE IF NOT EXISTS disabled_commands (guild_id TEXT, command_name TEXT, PRIMARY KEY (guild_id, command_name))", [])?;
         conn.execute("CREATE TABLE IF NOT EXISTS prefixes (guild_id TEXT PRIMARY KEY, prefix TEXT)", [])?;
+        conn.execute("CREATE TABLE IF NOT EXISTS antinuke_options (guild_id TEXT PRIMARY KEY, punishment TEXT DEFAULT 'ban', raid_action TEXT DEFAULT 'kick', log_channel TEXT, owner_dm INTEGER DEFAULT 0, ban_rollback TEXT DEFAULT 'confirm')", [])?;
+        for (col, definition) in [("raid_action", "TEXT DEFAULT 'kick'"), ("log_channel", "TEXT"), ("owner_dm", "INTEGER DEFAULT 0"), ("ban_rollback", "TEXT DEFAULT 'confirm'"), ("quarantine_role", "TEXT")] {
+            let exists: i32 = conn
+                .query_row("SELECT count(*) FROM pragma_table_info('antinuke_options') WHERE name = ?", [col], |row| row.get(0))
+                .unwrap_or(0);
+            if exists == 0 {
+                conn.execute(&format!("ALTER TABLE antinuke_options ADD COLUMN {} {}", col, definition), [])?;
+                info!("Migration: Added column {} to antinuke_options", col);
+            }
+        }
+        conn.execute("CREATE TABLE IF NOT EXISTS antinuke_thresholds (guild_id TEXT, module TEXT, max_actions INTEGER, window_secs INTEGER, PRIMARY KEY (guild_id, module))", [])?;
+        conn.execute("CREATE TABLE IF NOT EXISTS guild_snapshots (guild_id TEXT, kind TEXT, item_id TEXT, data TEXT, PRIMARY KEY (guild_id, kind, item_id))", [])?;
+        conn.execute("CREATE TABLE IF NOT EXISTS emoji_archive (guild_id TEXT, emoji_id TEXT, name TEXT, content_type TEXT, image BLOB, PRIMARY KEY (guild_id, emoji_id))", [])?;
+        conn.execute("CREATE TABLE IF NOT EXISTS nightmode (guild_id TEXT PRIMARY KEY, active INTEGER DEFAULT 0, scheduled INTEGER DEFAULT 0, start_time TEXT, end_time TEXT, overridden INTEGER DEFAULT 0)", [])?;
+        let exists: i32 = conn
+            .query_row("SELECT count(*) FROM pragma_table_info('nightmode') WHERE name = 'overridden'", [], |row| row.get(0))
+            .unwrap_or(0);
+        if exists == 0 {
+            conn.execute("ALTER TABLE nightmode ADD COLUMN overridden INTEGER DEFAULT 0", [])?;
+            info!("Migration: Added column overridden to nightmode");
+        }
+        conn.execute("CREATE TABLE IF NOT EXISTS nightmode_roles (guild_id TEXT, role_id TEXT, permissions TEXT, PRIMARY KEY (guild_id, role_id))", [])?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS incidents (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                guild_id TEXT NOT NULL,
+                module TEXT NOT NULL,
+                executor_id TEXT,
+                targets TEXT,
+                action TEXT,
+                punishment TEXT,
+                recovery TEXT,
+                audit_entry_id TEXT,
+                succeeded INTEGER DEFAULT 1,
+                detected_at TEXT,
+                resolved_at TEXT
+            )",
+            [],
+        )?;
+        conn.execute("CREATE INDEX IF NOT EXISTS idx_incidents_guild ON incidents (guild_id, id)", [])?;
+        conn.execute("CREATE TABLE IF NOT EXISTS backups (id INTEGER PRIMARY KEY AUTOINCREMENT, guild_id TEXT NOT NULL, label TEXT, created_by TEXT, created_at TEXT, automatic INTEGER DEFAULT 0, data TEXT)", [])?;
+        conn.execute("CREATE TABLE IF NOT EXISTS backup_schedules (guild_id TEXT PRIMARY KEY, interval_hours INTEGER, keep INTEGER, last_run TEXT)", [])?;
+        conn.execute("CREATE TABLE IF NOT EXISTS rate_tracker (guild_id TEXT, executor_id TEXT, module TEXT, window_secs INTEGER, hits TEXT, expires_at INTEGER, PRIMARY KEY (guild_id, executor_id, module))", [])?;
+        conn.execute("CREATE TABLE IF NOT EXISTS quarantined_members (guild_id TEXT, user_id TEXT, roles TEXT, reason TEXT, moderator_id TEXT, quarantined_at TEXT, PRIMARY KEY (guild_id, user_id))", [])?;
+        conn.execute("CREATE TABLE IF NOT EXISTS antinuke_monitor (guild_id TEXT, module TEXT, PRIMARY KEY (guild_id, module))", [])?;
+        conn.execute("CREATE TABLE IF NOT EXISTS trusted_bots (guild_id TEXT, bot_id TEXT, PRIMARY KEY (guild_id, bot_id))", [])?;
 
         conn.execute(
             "CREATE TABLE IF NOT EXISTS antinuke_config (
@@ -112,7 +312,9 @@ impl Database {
                 anti_guild_event_create INTEGER DEFAULT 0,
                 anti_guild_event_update INTEGER DEFAULT 0,
                 anti_guild_event_delete INTEGER DEFAULT 0,
+                anti_raid INTEGER DEFAULT 0,
                 auto_recovery INTEGER DEFAULT 0,
+                auto_lockdown INTEGER DEFAULT 0,
                 thread_lock_enabled INTEGER DEFAULT 1
             )",
             [],
@@ -134,7 +336,7 @@ impl Database {
             "anti_webhook_create", "anti_webhook_update", "anti_webhook_delete",
             "anti_automod_create", "anti_automod_update", "anti_automod_delete",
             "anti_guild_event_create", "anti_guild_event_update", "anti_guild_event_delete",
-            "auto_recovery", "thread_lock_enabled"
+            "anti_raid", "auto_recovery", "auto_lockdown", "thread_lock_enabled"
         ];
 
         for col in columns {
@@ -190,7 +392,7 @@ impl Database {
             "anti_webhook_create", "anti_webhook_update", "anti_webhook_delete",
             "anti_automod_create", "anti_automod_update", "anti_automod_delete",
             "anti_guild_event_create", "anti_guild_event_update", "anti_guild_event_delete",
-            "auto_recovery", "thread_lock_enabled"
+            "anti_raid", "auto_recovery", "auto_lockdown", "thread_lock_enabled"
         ];
 
         let val = if enabled { 1 } else { 0 };
@@ -235,7 +437,7 @@ impl Database {
             "anti_webhook_create", "anti_webhook_update", "anti_webhook_delete",
             "anti_automod_create", "anti_automod_update", "anti_automod_delete",
             "anti_guild_event_create", "anti_guild_event_update", "anti_guild_event_delete",
-            "auto_recovery", "thread_lock_enabled"
+            "anti_raid", "auto_recovery", "auto_lockdown", "thread_lock_enabled"
         ];
 
         let mut settings = HashMap::new();
@@ -268,36 +470,106 @@ impl Database {
         Ok(arc_settings)
     }
 
-    pub async fn add_whitelist(&self, user_id: &str, username: &str) -> anyhow::Result<()> {
+    pub async fn add_whitelist(&self, guild_id: &str, target_id: &str, kind: &str, name: &str, modules: &[String]) -> anyhow::Result<()> {
+        let modules_str = if modules.is_empty() { "*".to_string() } else { modules.join(",") };
         let conn = self.conn.lock().await;
         conn.execute(
-            "INSERT INTO whitelist (user_id, username) VALUES (?, ?) ON CONFLICT(user_id) DO UPDATE SET username = ?",
-            [user_id, username, username],
+            "INSERT INTO whitelist (guild_id, user_id, username, kind, modules) VALUES (?1, ?2, ?3, ?4, ?5) \
+             ON CONFLICT(guild_id, user_id) DO UPDATE SET username = ?3, kind = ?4, modules = ?5",
+            [guild_id, target_id, name, kind, &modules_str],
         )?;
         drop(conn);
+        let entry = WhitelistEntry::from_row(target_id.to_string(), Some(name.to_string()), Some(kind.to_string()), Some(modules_str));
         let mut cache = self.whitelist_cache.write().await;
-        cache.insert(user_id.to_string());
+        let entries = cache.entry(guild_id.to_string()).or_default();
+        entries.retain(|e| e.target_id != target_id);
+        entries.push(entry);
         Ok(())
     }
 
-    pub async fn remove_whitelist(&self, user_id: &str) -> anyhow::Result<()> {
+    pub async fn remove_whitelist(&self, guild_id: &str, target_id: &str) -> anyhow::Result<()> {
         let conn = self.conn.lock().await;
-        conn.execute("DELETE FROM whitelist WHERE user_id = ?", [user_id])?;
+        conn.execute("DELETE FROM whitelist WHERE guild_id = ? AND user_id = ?", [guild_id, target_id])?;
         drop(conn);
         let mut cache = self.whitelist_cache.write().await;
-        cache.remove(user_id);
+        if let Some(entries) = cache.get_mut(guild_id) {
+            entries.retain(|e| e.target_id != target_id);
+        }
         Ok(())
     }
 
-    pub async fn is_whitelisted(&self, user_id: &str) -> anyhow::Result<bool> {
+    pub async fn get_whitelist_entry(&self, guild_id: &str, target_id: &str) -> Option<WhitelistEntry> {
+        let cache = self.whitelist_cache.read().await;
+        cache.get(guild_id)?.iter().find(|e| e.target_id == target_id).cloned()
+    }
+
+    pub async fn is_whitelisted(&self, guild_id: &str, user_id: &str, module: &str) -> anyhow::Result<bool> {
+        let cache = self.whitelist_cache.read().await;
+        Ok(cache.get(guild_id)
+            .map(|entries| entries.iter().any(|e| e.kind == "user" && e.target_id == user_id && e.covers(module)))
+            .unwrap_or(false))
+    }
+
+    pub async fn whitelisted_roles(&self, guild_id: &str, module: &str) -> Vec<String> {
         let cache = self.whitelist_cache.read().await;
-        Ok(cache.contains(user_id))
+        cache.get(guild_id)
+            .map(|entries| entries.iter()
+                .filter(|e| e.kind == "role" && e.covers(module))
+                .map(|e| e.target_id.clone())
+                .collect())
+            .unwrap_or_default()
+    }
+
+    pub async fn cache_member_roles(&self, guild_id: &str, user_id: &str, roles: Vec<String>) {
+        let mut cache = self.member_roles_cache.write().await;
+        cache.insert(access_key(guild_id, user_id), roles);
+    }
+
+    pub async fn evict_member(&self, guild_id: &str, user_id: &str) {
+        let mut cache = self.member_roles_cache.write().await;
+        cache.remove(&access_key(guild_id, user_id));
     }
 
-    pub async fn list_whitelist(&self) -> anyhow::Result<Vec<(String, String)>> {
+    pub async fn cached_member_roles(&self, guild_id: &str, user_id: &str) -> Option<Vec<String>> {
+        let cache = self.member_roles_cache.read().await;
+        cache.get(&access_key(guild_id, user_id)).cloned()
+    }
+
+    pub async fn list_whitelist(&self, guild_id: &str) -> anyhow::Result<Vec<WhitelistEntry>> {
+        let cache = self.whitelist_cache.read().await;
+        Ok(cache.get(guild_id).cloned().unwrap_or_default())
+    }
+
+    pub async fn add_admin(&self, guild_id: &str, user_id: &str, username: &str) -> anyhow::Result<()> {
         let conn = self.conn.lock().await;
-        let mut stmt = conn.prepare("SELECT user_id, username FROM whitelist")?;
-        let rows = stmt.query_map([], |row| {
+        conn.execute(
+            "INSERT INTO admins (guild_id, user_id, username) VALUES (?, ?, ?) ON CONFLICT(guild_id, user_id) DO UPDATE SET username = ?",
+            [guild_id, user_id, username, username],
+        )?;
+        drop(conn);
+        let mut cache = self.admin_cache.write().await;
+        cache.insert(access_key(guild_id, user_id));
+        Ok(())
+    }
+
+    pub async fn remove_admin(&self, guild_id: &str, user_id: &str) -> anyhow::Result<()> {
+        let conn = self.conn.lock().await;
+        conn.execute("DELETE FROM admins WHERE guild_id = ? AND user_id = ?", [guild_id, user_id])?;
+        drop(conn);
+        let mut cache = self.admin_cache.write().await;
+        cache.remove(&access_key(guild_id, user_id));
+        Ok(())
+    }
+
+    pub async fn is_admin(&self, guild_id: &str, user_id: &str) -> anyhow::Result<bool> {
+        let cache = self.admin_cache.read().await;
+        Ok(cache.contains(&access_key(guild_id, user_id)))
+    }
+
+    pub async fn list_admins(&self, guild_id: &str) -> anyhow::Result<Vec<(String, String)>> {
+        let conn = self.conn.lock().await;
+        let mut stmt = conn.prepare("SELECT user_id, username FROM admins WHERE guild_id = ?")?;
+        let rows = stmt.query_map([guild_id], |row| {
             Ok((row.get(0)?, row.get(1)?))
         })?;
 
@@ -308,39 +580,614 @@ impl Database {
         Ok(list)
     }
 
-    pub async fn add_admin(&self, user_id: &str, username: &str) -> anyhow::Result<()> {
+    pub async fn get_punishment(&self, guild_id: &str) -> String {
+        let conn = self.conn.lock().await;
+        let mut stmt = match conn.prepare("SELECT punishment FROM antinuke_options WHERE guild_id = ?") {
+            Ok(s) => s,
+            Err(_) => return "ban".to_string(),
+        };
+        stmt.query_row([guild_id], |row| row.get(0)).unwrap_or_else(|_| "ban".to_string())
+    }
+
+    pub async fn set_punishment(&self, guild_id: &str, punishment: &str) -> anyhow::Result<()> {
         let conn = self.conn.lock().await;
         conn.execute(
-            "INSERT INTO admins (user_id, username) VALUES (?, ?) ON CONFLICT(user_id) DO UPDATE SET username = ?",
-            [user_id, username, username],
+            "INSERT INTO antinuke_options (guild_id, punishment) VALUES (?, ?) ON CONFLICT(guild_id) DO UPDATE SET punishment = ?",
+            [guild_id, punishment, punishment],
+        )?;
+        Ok(())
+    }
+
+    pub async fn get_raid_action(&self, guild_id: &str) -> String {
+        let conn = self.conn.lock().await;
+        let mut stmt = match conn.prepare("SELECT raid_action FROM antinuke_options WHERE guild_id = ?") {
+            Ok(s) => s,
+            Err(_) => return "kick".to_string(),
+        };
+        stmt.query_row([guild_id], |row| row.get::<_, Option<String>>(0))
+            .ok()
+            .flatten()
+            .unwrap_or_else(|| "kick".to_string())
+    }
+
+    pub async fn set_raid_action(&self, guild_id: &str, action: &str) -> anyhow::Result<()> {
+        let conn = self.conn.lock().await;
+        conn.execute(
+            "INSERT INTO antinuke_options (guild_id, raid_action) VALUES (?, ?) ON CONFLICT(guild_id) DO UPDATE SET raid_action = ?",
+            [guild_id, action, action],
+        )?;
+        Ok(())
+    }
+
+    pub async fn get_ban_rollback(&self, guild_id: &str) -> String {
+        let conn = self.conn.lock().await;
+        let mut stmt = match conn.prepare("SELECT ban_rollback FROM antinuke_options WHERE guild_id = ?") {
+            Ok(s) => s,
+            Err(_) => return "confirm".to_string(),
+        };
+        stmt.query_row([guild_id], |row| row.get::<_, Option<String>>(0))
+            .ok()
+            .flatten()
+            .unwrap_or_else(|| "confirm".to_string())
+    }
+
+    pub async fn set_ban_rollback(&self, guild_id: &str, mode: &str) -> anyhow::Result<()> {
+        let conn = self.conn.lock().await;
+        conn.execute(
+            "INSERT INTO antinuke_options (guild_id, ban_rollback) VALUES (?1, ?2) ON CONFLICT(guild_id) DO UPDATE SET ban_rollback = ?2",
+            [guild_id, mode],
+        )?;
+        Ok(())
+    }
+
+    pub async fn get_alert_options(&self, guild_id: &str) -> (Option<String>, bool) {
+        let conn = self.conn.lock().await;
+        let mut stmt = match conn.prepare("SELECT log_channel, owner_dm FROM antinuke_options WHERE guild_id = ?") {
+            Ok(s) => s,
+            Err(_) => return (None, false),
+        };
+        stmt.query_row([guild_id], |row| Ok((row.get::<_, Option<String>>(0)?, row.get::<_, Option<i32>>(1)?.unwrap_or(0) == 1)))
+            .unwrap_or((None, false))
+    }
+
+    pub async fn set_log_channel(&self, guild_id: &str, channel_id: Option<&str>) -> anyhow::Result<()> {
+        let conn = self.conn.lock().await;
+        conn.execute(
+            "INSERT INTO antinuke_options (guild_id, log_channel) VALUES (?1, ?2) ON CONFLICT(guild_id) DO UPDATE SET log_channel = ?2",
+            rusqlite::params![guild_id, channel_id],
+        )?;
+        Ok(())
+    }
+
+    pub async fn set_owner_dm(&self, guild_id: &str, enabled: bool) -> anyhow::Result<()> {
+        let conn = self.conn.lock().await;
+        conn.execute(
+            "INSERT INTO antinuke_options (guild_id, owner_dm) VALUES (?1, ?2) ON CONFLICT(guild_id) DO UPDATE SET owner_dm = ?2",
+            rusqlite::params![guild_id, enabled as i32],
+        )?;
+        Ok(())
+    }
+
+    pub async fn is_monitor_mode(&self, guild_id: &str, module: &str) -> bool {
+        let conn = self.conn.lock().await;
+        let mut stmt = match conn.prepare("SELECT 1 FROM antinuke_monitor WHERE guild_id = ? AND (module = ? OR module = '*')") {
+            Ok(s) => s,
+            Err(e) => {
+                error!("Failed to query monitor mode for guild {}: {:?}", guild_id, e);
+                return false;
+            }
+        };
+        stmt.exists([guild_id, module]).unwrap_or(false)
+    }
+
+    pub async fn set_monitor_mode(&self, guild_id: &str, module: &str, enabled: bool) -> anyhow::Result<()> {
+        let conn = self.conn.lock().await;
+        if enabled {
+            conn.execute("INSERT OR IGNORE INTO antinuke_monitor (guild_id, module) VALUES (?, ?)", [guild_id, module])?;
+        } else {
+            conn.execute("DELETE FROM antinuke_monitor WHERE guild_id = ? AND module = ?", [guild_id, module])?;
+        }
+        Ok(())
+    }
+
+    pub async fn list_monitor_modules(&self, guild_id: &str) -> anyhow::Result<HashSet<String>> {
+        let conn = self.conn.lock().await;
+        let mut stmt = conn.prepare("SELECT module FROM antinuke_monitor WHERE guild_id = ?")?;
+        let rows = stmt.query_map([guild_id], |row| row.get(0))?;
+        let mut set = HashSet::new();
+        for row in rows { set.insert(row?); }
+        Ok(set)
+    }
+
+    pub async fn get_threshold(&self, guild_id: &str, module: &str) -> (u32, u64) {
+        self.get_threshold_or(guild_id, module, (1, 10)).await
+    }
+
+    pub async fn get_threshold_or(&self, guild_id: &str, module: &str, default: (u32, u64)) -> (u32, u64) {
+        let conn = self.conn.lock().await;
+        let mut stmt = match conn.prepare("SELECT max_actions, window_secs FROM antinuke_thresholds WHERE guild_id = ? AND module = ?") {
+            Ok(s) => s,
+            Err(_) => return default,
+        };
+        stmt.query_row([guild_id, module], |row| Ok((row.get(0)?, row.get(1)?))).unwrap_or(default)
+    }
+
+    pub async fn set_threshold(&self, guild_id: &str, module: &str, max_actions: u32, window_secs: u64) -> anyhow::Result<()> {
+        let conn = self.conn.lock().await;
+        conn.execute(
+            "INSERT INTO antinuke_thresholds (guild_id, module, max_actions, window_secs) VALUES (?1, ?2, ?3, ?4) \
+             ON CONFLICT(guild_id, module) DO UPDATE SET max_actions = ?3, window_secs = ?4",
+            rusqlite::params![guild_id, module, max_actions, window_secs],
         )?;
-        drop(conn);
-        let mut cache = self.admin_cache.write().await;
-        cache.insert(user_id.to_string());
         Ok(())
     }
 
-    pub async fn remove_admin(&self, user_id: &str) -> anyhow::Result<()> {
+    pub async fn remove_threshold(&self, guild_id: &str, module: &str) -> anyhow::Result<()> {
+        let conn = self.conn.lock().await;
+        conn.execute("DELETE FROM antinuke_thresholds WHERE guild_id = ? AND module = ?", [guild_id, module])?;
+        Ok(())
+    }
+
+    pub async fn list_thresholds(&self, guild_id: &str) -> anyhow::Result<Vec<(String, u32, u64)>> {
+        let conn = self.conn.lock().await;
+        let mut stmt = conn.prepare("SELECT module, max_actions, window_secs FROM antinuke_thresholds WHERE guild_id = ? ORDER BY module")?;
+        let rows = stmt.query_map([guild_id], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?;
+        let mut list = Vec::new();
+        for row in rows {
+            list.push(row?);
+        }
+        Ok(list)
+    }
+
+    pub async fn track_action(&self, guild_id: &str, executor_id: &str, module: &str, window_secs: u64) -> usize {
+        let key = TrackerKey::new(guild_id, executor_id, module);
+        let now_ms = chrono::Utc::now().timestamp_millis();
+        let (count, expires_at, hits) = self.rate_tracker.hit(&key, window_secs, now_ms).await;
+
+        if self.rate_tracker.is_persistent() {
+            let conn = self.conn.lock().await;
+            let result = conn.execute(
+                "INSERT INTO rate_tracker (guild_id, executor_id, module, window_secs, hits, expires_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+                 ON CONFLICT(guild_id, executor_id, module) DO UPDATE SET window_secs = ?4, hits = ?5, expires_at = ?6",
+                rusqlite::params![guild_id, executor_id, module, window_secs as i64, serde_json::to_string(&hits).unwrap_or_default(), expires_at],
+            );
+            if let Err(e) = result {
+                error!("Failed to persist rate tracker window for {} in guild {}: {:?}", module, guild_id, e);
+            }
+        }
+        count
+    }
+
+    pub async fn evict_rate_tracker(&self) -> anyhow::Result<usize> {
+        let now_ms = chrono::Utc::now().timestamp_millis();
+        let evicted = self.rate_tracker.evict(now_ms).await;
+        if self.rate_tracker.is_persistent() {
+            let conn = self.conn.lock().await;
+            conn.execute("DELETE FROM rate_tracker WHERE expires_at <= ?", [now_ms])?;
+        }
+        Ok(evicted)
+    }
+
+    pub async fn restore_rate_tracker(&self) -> anyhow::Result<usize> {
+        self.rate_tracker.enable_persistence();
+        let now_ms = chrono::Utc::now().timestamp_millis();
         let conn = self.conn.lock().await;
-        conn.execute("DELETE FROM admins WHERE user_id = ?", [user_id])?;
+        conn.execute("DELETE FROM rate_tracker WHERE expires_at <= ?", [now_ms])?;
+
+        let mut stmt = conn.prepare("SELECT guild_id, executor_id, module, window_secs, hits FROM rate_tracker")?;
+        let rows = stmt.query_map([], |row| Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, String>(2)?,
+            row.get::<_, i64>(3)? as u64,
+            row.get::<_, String>(4)?,
+        )))?;
+        let mut entries = Vec::new();
+        for row in rows {
+            let (guild_id, executor_id, module, window_secs, hits) = row?;
+            let hits: VecDeque<i64> = serde_json::from_str(&hits).unwrap_or_default();
+            entries.push((TrackerKey::new(&guild_id, &executor_id, &module), TrackedWindow { window_secs, hits }));
+        }
+        drop(stmt);
         drop(conn);
-        let mut cache = self.admin_cache.write().await;
-        cache.remove(user_id);
+
+        let restored = entries.len();
+        self.rate_tracker.load(entries).await;
+        Ok(restored)
+    }
+
+    pub async fn save_snapshot(&self, guild_id: &str, kind: &str, item_id: &str, data: &serde_json::Value) -> anyhow::Result<()> {
+        let conn = self.conn.lock().await;
+        let text = data.to_string();
+        conn.execute(
+            "INSERT INTO guild_snapshots (guild_id, kind, item_id, data) VALUES (?, ?, ?, ?) \
+             ON CONFLICT(guild_id, kind, item_id) DO UPDATE SET data = ?",
+            [guild_id, kind, item_id, &text, &text],
+        )?;
         Ok(())
     }
 
-    pub async fn is_admin(&self, user_id: &str) -> anyhow::Result<bool> {
-        let cache = self.admin_cache.read().await;
-        Ok(cache.contains(user_id))
+    pub async fn get_snapshot(&self, guild_id: &str, kind: &str, item_id: &str) -> anyhow::Result<Option<serde_json::Value>> {
+        let conn = self.conn.lock().await;
+        let mut stmt = conn.prepare("SELECT data FROM guild_snapshots WHERE guild_id = ? AND kind = ? AND item_id = ?")?;
+        let text: Option<String> = stmt.query_row([guild_id, kind, item_id], |row| row.get(0)).ok();
+        Ok(text.and_then(|t| serde_json::from_str(&t).ok()))
+    }
+
+    pub async fn delete_snapshot(&self, guild_id: &str, kind: &str, item_id: &str) -> anyhow::Result<()> {
+        let conn = self.conn.lock().await;
+        conn.execute("DELETE FROM guild_snapshots WHERE guild_id = ? AND kind = ? AND item_id = ?", [guild_id, kind, item_id])?;
+        Ok(())
+    }
+
+    pub async fn list_snapshots(&self, guild_id: &str, kind: &str) -> anyhow::Result<Vec<serde_json::Value>> {
+        let conn = self.conn.lock().await;
+        let mut stmt = conn.prepare("SELECT data FROM guild_snapshots WHERE guild_id = ? AND kind = ?")?;
+        let rows = stmt.query_map([guild_id, kind], |row| row.get::<_, String>(0))?;
+        let mut list = Vec::new();
+        for row in rows {
+            if let Ok(v) = serde_json::from_str(&row?) {
+                list.push(v);
+            }
+        }
+        Ok(list)
+    }
+
+    pub async fn replace_snapshots(&self, guild_id: &str, kind: &str, items: &[serde_json::Value]) -> anyhow::Result<()> {
+        let mut conn = self.conn.lock().await;
+        let tx = conn.transaction()?;
+        tx.execute("DELETE FROM guild_snapshots WHERE guild_id = ? AND kind = ?", [guild_id, kind])?;
+        for item in items {
+            let item_id = item.get("id").and_then(|v| v.as_str()).unwrap_or("");
+            if item_id.is_empty() { continue; }
+            tx.execute(
+                "INSERT INTO guild_snapshots (guild_id, kind, item_id, data) VALUES (?, ?, ?, ?)",
+                [guild_id, kind, item_id, &item.to_string()],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    pub async fn archive_emoji(&self, guild_id: &str, emoji_id: &str, name: &str, content_type: &str, image: &[u8]) -> anyhow::Result<()> {
+        let conn = self.conn.lock().await;
+        conn.execute(
+            "INSERT INTO emoji_archive (guild_id, emoji_id, name, content_type, image) VALUES (?1, ?2, ?3, ?4, ?5) \
+             ON CONFLICT(guild_id, emoji_id) DO UPDATE SET name = ?3, content_type = ?4, image = ?5",
+            rusqlite::params![guild_id, emoji_id, name, content_type, image],
+        )?;
+        Ok(())
+    }
+
+    pub async fn get_archived_emoji(&self, guild_id: &str, emoji_id: &str) -> anyhow::Result<Option<(String, String, Vec<u8>)>> {
+        let conn = self.conn.lock().await;
+        let mut stmt = conn.prepare("SELECT name, content_type, image FROM emoji_archive WHERE guild_id = ? AND emoji_id = ?")?;
+        Ok(stmt.query_row([guild_id, emoji_id], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?))).ok())
+    }
+
+    pub async fn archived_emoji_ids(&self, guild_id: &str) -> anyhow::Result<HashSet<String>> {
+        let conn = self.conn.lock().await;
+        let mut stmt = conn.prepare("SELECT emoji_id FROM emoji_archive WHERE guild_id = ?")?;
+        let rows = stmt.query_map([guild_id], |row| row.get(0))?;
+        let mut ids = HashSet::new();
+        for row in rows {
+            ids.insert(row?);
+        }
+        Ok(ids)
+    }
+
+    pub async fn delete_archived_emoji(&self, guild_id: &str, emoji_id: &str) -> anyhow::Result<()> {
+        let conn = self.conn.lock().await;
+        conn.execute("DELETE FROM emoji_archive WHERE guild_id = ? AND emoji_id = ?", [guild_id, emoji_id])?;
+        Ok(())
+    }
+
+    pub async fn get_nightmode(&self, guild_id: &str) -> anyhow::Result<(bool, bool, Option<String>, Option<String>)> {
+        let conn = self.conn.lock().await;
+        let mut stmt = conn.prepare("SELECT active, scheduled, start_time, end_time FROM nightmode WHERE guild_id = ?")?;
+        let state = stmt
+            .query_row([guild_id], |row| Ok((row.get::<_, i32>(0)? == 1, row.get::<_, i32>(1)? == 1, row.get(2)?, row.get(3)?)))
+            .unwrap_or((false, false, None, None));
+        Ok(state)
+    }
+
+    pub async fn set_nightmode_active(&self, guild_id: &str, active: bool, scheduled: bool) -> anyhow::Result<()> {
+        let conn = self.conn.lock().await;
+        conn.execute(
+            "INSERT INTO nightmode (guild_id, active, scheduled) VALUES (?1, ?2, ?3) \
+             ON CONFLICT(guild_id) DO UPDATE SET active = ?2, scheduled = ?3",
+            rusqlite::params![guild_id, active as i32, scheduled as i32],
+        )?;
+        Ok(())
+    }
+
+    pub async fn set_nightmode_override(&self, guild_id: &str, overridden: bool) -> anyhow::Result<()> {
+        let conn = self.conn.lock().await;
+        conn.execute("UPDATE nightmode SET overridden = ? WHERE guild_id = ?", rusqlite::params![overridden as i32, guild_id])?;
+        Ok(())
+    }
+
+    pub async fn set_nightmode_schedule(&self, guild_id: &str, start_time: Option<&str>, end_time: Option<&str>) -> anyhow::Result<()> {
+        let conn = self.conn.lock().await;
+        conn.execute(
+            "INSERT INTO nightmode (guild_id, start_time, end_time) VALUES (?1, ?2, ?3) \
+             ON CONFLICT(guild_id) DO UPDATE SET start_time = ?2, end_time = ?3, overridden = 0",
+            rusqlite::params![guild_id, start_time, end_time],
+        )?;
+        Ok(())
     }
 
-    pub async fn list_admins(&self) -> anyhow::Result<Vec<(String, String)>> {
+    pub async fn list_nightmode_schedules(&self) -> anyhow::Result<Vec<(String, String, String, bool, bool, bool)>> {
         let conn = self.conn.lock().await;
-        let mut stmt = conn.prepare("SELECT user_id, username FROM admins")?;
+        let mut stmt = conn.prepare("SELECT guild_id, start_time, end_time, active, scheduled, overridden FROM nightmode WHERE start_time IS NOT NULL AND end_time IS NOT NULL")?;
         let rows = stmt.query_map([], |row| {
-            Ok((row.get(0)?, row.get(1)?))
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get::<_, i32>(3)? == 1, row.get::<_, i32>(4)? == 1, row.get::<_, i32>(5)? == 1))
         })?;
+        let mut list = Vec::new();
+        for row in rows {
+            list.push(row?);
+        }
+        Ok(list)
+    }
+
+    pub async fn save_nightmode_role(&self, guild_id: &str, role_id: &str, permissions: u64) -> anyhow::Result<()> {
+        let conn = self.conn.lock().await;
+        conn.execute(
+            "INSERT OR IGNORE INTO nightmode_roles (guild_id, role_id, permissions) VALUES (?, ?, ?)",
+            [guild_id, role_id, &permissions.to_string()],
+        )?;
+        Ok(())
+    }
+
+    pub async fn get_nightmode_roles(&self, guild_id: &str) -> anyhow::Result<Vec<(String, u64)>> {
+        let conn = self.conn.lock().await;
+        let mut stmt = conn.prepare("SELECT role_id, permissions FROM nightmode_roles WHERE guild_id = ?")?;
+        let rows = stmt.query_map([guild_id], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?;
+        let mut list = Vec::new();
+        for row in rows {
+            let (role_id, perms) = row?;
+            list.push((role_id, perms.parse().unwrap_or(0)));
+        }
+        Ok(list)
+    }
+
+    pub async fn remove_nightmode_role(&self, guild_id: &str, role_id: &str) -> anyhow::Result<()> {
+        let conn = self.conn.lock().await;
+        conn.execute("DELETE FROM nightmode_roles WHERE guild_id = ? AND role_id = ?", [guild_id, role_id])?;
+        Ok(())
+    }
+
+    pub async fn record_incident(&self, record: &IncidentRecord) -> anyhow::Result<i64> {
+        let conn = self.conn.lock().await;
+        conn.execute(
+            "INSERT INTO incidents (guild_id, module, executor_id, targets, action, punishment, recovery, audit_entry_id, succeeded, detected_at, resolved_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            rusqlite::params![
+                record.guild_id,
+                record.module,
+                record.executor_id,
+                serde_json::to_string(&record.targets)?,
+                record.action,
+                record.punishment,
+                serde_json::to_string(&record.recovery)?,
+                record.audit_entry_id,
+                record.succeeded,
+                record.detected_at,
+                record.resolved_at,
+            ],
+        )?;
+        Ok(conn.last_insert_rowid())
+    }
+
+    pub async fn append_incident_recovery(&self, guild_id: &str, incident_id: i64, lines: &[String]) -> anyhow::Result<()> {
+        let conn = self.conn.lock().await;
+        let existing: Option<String> = conn.query_row(
+            "SELECT recovery FROM incidents WHERE guild_id = ? AND id = ?",
+            rusqlite::params![guild_id, incident_id],
+            |row| row.get(0),
+        ).ok();
+        let mut recovery: Vec<String> = existing.and_then(|t| serde_json::from_str(&t).ok()).unwrap_or_default();
+        recovery.extend(lines.iter().cloned());
+        conn.execute(
+            "UPDATE incidents SET recovery = ? WHERE guild_id = ? AND id = ?",
+            rusqlite::params![serde_json::to_string(&recovery)?, guild_id, incident_id],
+        )?;
+        Ok(())
+    }
+
+    pub async fn get_incident(&self, guild_id: &str, incident_id: i64) -> anyhow::Result<Option<IncidentRecord>> {
+        let conn = self.conn.lock().await;
+        let mut stmt = conn.prepare(&format!("SELECT {} FROM incidents WHERE guild_id = ? AND id = ?", INCIDENT_COLUMNS))?;
+        let mut rows = stmt.query_map(rusqlite::params![guild_id, incident_id], IncidentRecord::from_row)?;
+        Ok(rows.next().transpose()?)
+    }
+
+    pub async fn list_incidents(&self, guild_id: &str, module: Option<&str>, executor_id: Option<&str>, limit: usize, offset: usize) -> anyhow::Result<(Vec<IncidentRecord>, usize)> {
+        let conn = self.conn.lock().await;
+        let filter = "WHERE guild_id = ?1 AND (?2 IS NULL OR module = ?2) AND (?3 IS NULL OR executor_id = ?3)";
+
+        let total: i64 = conn.query_row(
+            &format!("SELECT COUNT(*) FROM incidents {}", filter),
+            rusqlite::params![guild_id, module, executor_id],
+            |row| row.get(0),
+        )?;
+
+        let mut stmt = conn.prepare(&format!("SELECT {} FROM incidents {} ORDER BY id DESC LIMIT ?4 OFFSET ?5", INCIDENT_COLUMNS, filter))?;
+        let rows = stmt.query_map(rusqlite::params![guild_id, module, executor_id, limit as i64, offset as i64], IncidentRecord::from_row)?;
+        let mut list = Vec::new();
+        for row in rows { list.push(row?); }
+        Ok((list, total as usize))
+    }
+
+    pub async fn save_backup(&self, guild_id: &str, label: &str, created_by: &str, automatic: bool, data: &serde_json::Value) -> anyhow::Result<i64> {
+        let conn = self.conn.lock().await;
+        conn.execute(
+            "INSERT INTO backups (guild_id, label, created_by, created_at, automatic, data) VALUES (?, ?, ?, ?, ?, ?)",
+            rusqlite::params![guild_id, label, created_by, chrono::Utc::now().to_rfc3339(), automatic, data.to_string()],
+        )?;
+        Ok(conn.last_insert_rowid())
+    }
+
+    pub async fn list_backups(&self, guild_id: &str) -> anyhow::Result<Vec<(i64, String, String, String, bool)>> {
+        let conn = self.conn.lock().await;
+        let mut stmt = conn.prepare("SELECT id, label, created_by, created_at, automatic FROM backups WHERE guild_id = ? ORDER BY id DESC")?;
+        let rows = stmt.query_map([guild_id], |row| Ok((
+            row.get(0)?,
+            row.get::<_, Option<String>>(1)?.unwrap_or_default(),
+            row.get::<_, Option<String>>(2)?.unwrap_or_default(),
+            row.get::<_, Option<String>>(3)?.unwrap_or_default(),
+            row.get::<_, i64>(4)? != 0,
+        )))?;
+        let mut list = Vec::new();
+        for row in rows { list.push(row?); }
+        Ok(list)
+    }
+
+    pub async fn get_backup(&self, guild_id: &str, backup_id: i64) -> anyhow::Result<Option<serde_json::Value>> {
+        let conn = self.conn.lock().await;
+        let text: Option<String> = conn.query_row(
+            "SELECT data FROM backups WHERE guild_id = ? AND id = ?",
+            rusqlite::params![guild_id, backup_id],
+            |row| row.get(0),
+        ).ok();
+        Ok(text.and_then(|t| serde_json::from_str(&t).ok()))
+    }
+
+    pub async fn delete_backup(&self, guild_id: &str, backup_id: i64) -> anyhow::Result<usize> {
+        let conn = self.conn.lock().await;
+        let changes = conn.execute("DELETE FROM backups WHERE guild_id = ? AND id = ?", rusqlite::params![guild_id, backup_id])?;
+        Ok(changes)
+    }
+
+    pub async fn prune_automatic_backups(&self, guild_id: &str, keep: usize) -> anyhow::Result<usize> {
+        let conn = self.conn.lock().await;
+        let changes = conn.execute(
+            "DELETE FROM backups WHERE guild_id = ?1 AND automatic = 1 AND id NOT IN
+                (SELECT id FROM backups WHERE guild_id = ?1 AND automatic = 1 ORDER BY id DESC LIMIT ?2)",
+            rusqlite::params![guild_id, keep as i64],
+        )?;
+        Ok(changes)
+    }
+
+    pub async fn get_backup_schedule(&self, guild_id: &str) -> Option<(u64, usize)> {
+        let conn = self.conn.lock().await;
+        conn.query_row(
+            "SELECT interval_hours, keep FROM backup_schedules WHERE guild_id = ?",
+            [guild_id],
+            |row| Ok((row.get::<_, i64>(0)? as u64, row.get::<_, i64>(1)? as usize)),
+        ).ok()
+    }
+
+    pub async fn set_backup_schedule(&self, guild_id: &str, schedule: Option<(u64, usize)>) -> anyhow::Result<()> {
+        let conn = self.conn.lock().await;
+        match schedule {
+            Some((hours, keep)) => conn.execute(
+                "INSERT INTO backup_schedules (guild_id, interval_hours, keep) VALUES (?1, ?2, ?3)
+                 ON CONFLICT(guild_id) DO UPDATE SET interval_hours = ?2, keep = ?3",
+                rusqlite::params![guild_id, hours as i64, keep as i64],
+            )?,
+            None => conn.execute("DELETE FROM backup_schedules WHERE guild_id = ?", [guild_id])?,
+        };
+        Ok(())
+    }
+
+    pub async fn list_backup_schedules(&self) -> anyhow::Result<Vec<(String, u64, usize, Option<String>)>> {
+        let conn = self.conn.lock().await;
+        let mut stmt = conn.prepare("SELECT guild_id, interval_hours, keep, last_run FROM backup_schedules")?;
+        let rows = stmt.query_map([], |row| Ok((
+            row.get(0)?,
+            row.get::<_, i64>(1)? as u64,
+            row.get::<_, i64>(2)? as usize,
+            row.get(3)?,
+        )))?;
+        let mut list = Vec::new();
+        for row in rows { list.push(row?); }
+        Ok(list)
+    }
 
+    pub async fn mark_backup_run(&self, guild_id: &str) -> anyhow::Result<()> {
+        let conn = self.conn.lock().await;
+        conn.execute("UPDATE backup_schedules SET last_run = ? WHERE guild_id = ?", [chrono::Utc::now().to_rfc3339().as_str(), guild_id])?;
+        Ok(())
+    }
+
+    pub async fn get_quarantine_role(&self, guild_id: &str) -> Option<String> {
+        let conn = self.conn.lock().await;
+        conn.query_row("SELECT quarantine_role FROM antinuke_options WHERE guild_id = ?", [guild_id], |row| row.get::<_, Option<String>>(0))
+            .ok()
+            .flatten()
+    }
+
+    pub async fn set_quarantine_role(&self, guild_id: &str, role_id: Option<&str>) -> anyhow::Result<()> {
+        let conn = self.conn.lock().await;
+        conn.execute(
+            "INSERT INTO antinuke_options (guild_id, quarantine_role) VALUES (?1, ?2) ON CONFLICT(guild_id) DO UPDATE SET quarantine_role = ?2",
+            rusqlite::params![guild_id, role_id],
+        )?;
+        Ok(())
+    }
+
+    pub async fn save_quarantine(&self, guild_id: &str, user_id: &str, roles: &[String], reason: &str, moderator_id: &str) -> anyhow::Result<()> {
+        let conn = self.conn.lock().await;
+        conn.execute(
+            "INSERT INTO quarantined_members (guild_id, user_id, roles, reason, moderator_id, quarantined_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+             ON CONFLICT(guild_id, user_id) DO UPDATE SET reason = ?4, moderator_id = ?5",
+            rusqlite::params![guild_id, user_id, serde_json::to_string(roles)?, reason, moderator_id, chrono::Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    pub async fn get_quarantine(&self, guild_id: &str, user_id: &str) -> Option<(Vec<String>, String)> {
+        let conn = self.conn.lock().await;
+        conn.query_row(
+            "SELECT roles, reason FROM quarantined_members WHERE guild_id = ? AND user_id = ?",
+            [guild_id, user_id],
+            |row| Ok((row.get::<_, String>(0)?, row.get::<_, Option<String>>(1)?.unwrap_or_default())),
+        ).ok().map(|(roles, reason)| (serde_json::from_str(&roles).unwrap_or_default(), reason))
+    }
+
+    pub async fn remove_quarantine(&self, guild_id: &str, user_id: &str) -> anyhow::Result<usize> {
+        let conn = self.conn.lock().await;
+        let changes = conn.execute("DELETE FROM quarantined_members WHERE guild_id = ? AND user_id = ?", [guild_id, user_id])?;
+        Ok(changes)
+    }
+
+    pub async fn list_quarantined(&self, guild_id: &str) -> anyhow::Result<Vec<(String, String, String)>> {
+        let conn = self.conn.lock().await;
+        let mut stmt = conn.prepare("SELECT user_id, reason, quarantined_at FROM quarantined_members WHERE guild_id = ? ORDER BY quarantined_at DESC")?;
+        let rows = stmt.query_map([guild_id], |row| Ok((
+            row.get(0)?,
+            row.get::<_, Option<String>>(1)?.unwrap_or_default(),
+            row.get::<_, Option<String>>(2)?.unwrap_or_default(),
+        )))?;
+        let mut list = Vec::new();
+        for row in rows { list.push(row?); }
+        Ok(list)
+    }
+
+    pub async fn add_trusted_bot(&self, guild_id: &str, bot_id: &str) -> anyhow::Result<()> {
+        let conn = self.conn.lock().await;
+        conn.execute("INSERT OR IGNORE INTO trusted_bots (guild_id, bot_id) VALUES (?, ?)", [guild_id, bot_id])?;
+        Ok(())
+    }
+
+    pub async fn remove_trusted_bot(&self, guild_id: &str, bot_id: &str) -> anyhow::Result<usize> {
+        let conn = self.conn.lock().await;
+        let changes = conn.execute("DELETE FROM trusted_bots WHERE guild_id = ? AND bot_id = ?", [guild_id, bot_id])?;
+        Ok(changes)
+    }
+
+    pub async fn is_trusted_bot(&self, guild_id: &str, bot_id: &str) -> anyhow::Result<bool> {
+        let conn = self.conn.lock().await;
+        let mut stmt = conn.prepare("SELECT 1 FROM trusted_bots WHERE guild_id = ? AND bot_id = ?")?;
+        let exists: i32 = stmt.query_row([guild_id, bot_id], |row| row.get(0)).unwrap_or(0);
+        Ok(exists == 1)
+    }
+
+    pub async fn list_trusted_bots(&self, guild_id: &str) -> anyhow::Result<Vec<String>> {
+        let conn = self.conn.lock().await;
+        let mut stmt = conn.prepare("SELECT bot_id FROM trusted_bots WHERE guild_id = ?")?;
+        let rows = stmt.query_map([guild_id], |row| row.get(0))?;
         let mut list = Vec::new();
         for row in rows {
             list.push(row?);
@@ -63,7 +63,7 @@ async fn connect_and_run(token: &str, rest: Arc<RestClient>, prefix: char, db: A
         }
     });
 
-    let intents = 37377 | 4 | 32768 | 2 | 8; 
+    let intents = models::intent::IDENTIFY;
     let identify = json!({
         "op": 2,
         "d": {
@@ -115,7 +115,7 @@ async fn connect_and_run(token: &str, rest: Arc<RestClient>, prefix: char, db: A
                                         tokio::spawn(async move { handler::handle_interaction(int_data, rest_clone, db_clone).await; });
                                     }
 
-                                    nuke_event if nuke_event.starts_with("GUILD_") || nuke_event.starts_with("CHANNEL_") || nuke_event.contains("UPDATE") => {
+                                    nuke_event if nuke_event.starts_with("GUILD_") || nuke_event.starts_with("CHANNEL_") || nuke_event.starts_with("AUTO_MODERATION_RULE_") || nuke_event.contains("UPDATE") => {
                                         let rest_clone = rest.clone();
                                         let db_clone = Arc::clone(&db);
                                         let event_name = nuke_event.to_string();
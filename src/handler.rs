@@ -48,12 +48,13 @@ pub async fn handle_message(msg: Message, rest: Arc<RestClient>, prefix: char, d
         "help" => general::help(&rest, &msg).await,
 
         "security" | "s" | "sec" | "antinuke" | "admin" | "adm" | "extraowner" | "eo" | 
-        "whitelist" | "wl" | "wlist" | "unwhitelist" | "uwl" | "nightmode" | "nm" => {
+        "whitelist" | "wl" | "wlist" | "unwhitelist" | "uwl" | "nightmode" | "nm" | "backup" | "backups" => {
             security::security_cmd(&rest, &msg, Arc::clone(&db), &cmd, &args).await
         }
 
         "ban" | "kick" | "softban" | "unban" | "unbanall" | "mute" | "unmute" | "unmuteall" | "nick" | "slowmode" |
-        "lock" | "unlock" | "lockall" | "unlockall" | "hide" | "unhide" | "hideall" | "unhideall" | "block" | "unblock" |
+        "lock" | "unlock" | "lockall" | "unlockall" | "hide" | "unhide" | "hideall" | "unhideall" | "block" | "unblock" | "lockdown" |
+        "quarantine" | "qt" | "unquarantine" | "unqt" |
         "purge" | "clear" | "p" | "c" |
         "list" | "l" | "role" | "r" |
         "warn" | "warning" | "command" | "ignore" | "unignore" | "prefix" => {
@@ -45,6 +45,16 @@ async fn main() {
         }
     };
 
+    let persist_tracker = env::var("PERSIST_RATE_TRACKER")
+        .map(|v| matches!(v.trim().to_lowercase().as_str(), "1" | "true" | "yes" | "on"))
+        .unwrap_or(false);
+    if persist_tracker {
+        match db.restore_rate_tracker().await {
+            Ok(restored) => info!("Rate tracker persistence enabled ({} window(s) restored)", restored),
+            Err(e) => error!("❌ Failed to restore rate tracker: {:?}", e),
+        }
+    }
+
     info!("🦀 rimuru-bot starting (prefix='{}') — raw WebSocket + HTTP, no wrapper", prefix);
 
     let rest = Arc::new(RestClient::new(&token));
@@ -54,5 +64,9 @@ async fn main() {
         return;
     }
 
+    tokio::spawn(antinuke::nightmode::run_scheduler(Arc::clone(&rest), Arc::clone(&db)));
+    tokio::spawn(antinuke::backup::run_scheduler(Arc::clone(&rest), Arc::clone(&db)));
+    tokio::spawn(antinuke::tracker::run_eviction(Arc::clone(&db)));
+
     gateway::run(token, rest, prefix, db).await;
 }
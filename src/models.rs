@@ -30,9 +30,33 @@ pub mod op {
 #[allow(dead_code)]
 pub mod intent {
     pub const GUILDS: u32             = 1 << 0;
+    pub const GUILD_MEMBERS: u32      = 1 << 1;
+    pub const GUILD_MODERATION: u32   = 1 << 2;
+    pub const GUILD_EXPRESSIONS: u32  = 1 << 3;
+    pub const GUILD_WEBHOOKS: u32     = 1 << 5;
     pub const GUILD_MESSAGES: u32     = 1 << 9;  
     pub const DIRECT_MESSAGES: u32    = 1 << 12; 
     pub const MESSAGE_CONTENT: u32    = 1 << 15; 
+    pub const GUILD_SCHEDULED_EVENTS: u32 = 1 << 16;
+    pub const AUTO_MODERATION_CONFIGURATION: u32 = 1 << 20;
+
+    pub const IDENTIFY: u32 = GUILDS
+        | GUILD_MEMBERS
+        | GUILD_MODERATION
+        | GUILD_EXPRESSIONS
+        | GUILD_WEBHOOKS
+        | GUILD_MESSAGES
+        | DIRECT_MESSAGES
+        | MESSAGE_CONTENT
+        | GUILD_SCHEDULED_EVENTS
+        | AUTO_MODERATION_CONFIGURATION;
+}
+
+pub mod app_flag {
+    pub const GATEWAY_GUILD_MEMBERS: u64         = 1 << 14;
+    pub const GATEWAY_GUILD_MEMBERS_LIMITED: u64 = 1 << 15;
+    pub const GATEWAY_MESSAGE_CONTENT: u64       = 1 << 18;
+    pub const GATEWAY_MESSAGE_CONTENT_LIMITED: u64 = 1 << 19;
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -61,6 +85,16 @@ pub struct Message {
     pub channel_id: String,
     pub author: User,
     pub content: String,
+    #[serde(default)]
+    pub attachments: Vec<Attachment>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct Attachment {
+    pub filename: String,
+    pub url: String,
+    #[serde(default)]
+    pub size: u64,
 }
 
 #[derive(Debug, Deserialize)]
@@ -105,6 +139,36 @@ pub struct InteractionData {
     pub values: Option<Vec<String>>,
 }
 
+pub mod audit_action {
+    pub const GUILD_UPDATE: u8 = 1;
+    pub const CHANNEL_UPDATE: u8 = 11;
+    pub const CHANNEL_DELETE: u8 = 12;
+    pub const CHANNEL_OVERWRITE_CREATE: u8 = 13;
+    pub const CHANNEL_OVERWRITE_UPDATE: u8 = 14;
+    pub const CHANNEL_OVERWRITE_DELETE: u8 = 15;
+    pub const MEMBER_KICK: u8 = 20;
+    pub const MEMBER_PRUNE: u8 = 21;
+    pub const MEMBER_BAN_ADD: u8 = 22;
+    pub const MEMBER_BAN_REMOVE: u8 = 23;
+    pub const MEMBER_ROLE_UPDATE: u8 = 25;
+    pub const BOT_ADD: u8 = 28;
+    pub const WEBHOOK_CREATE: u8 = 50;
+    pub const WEBHOOK_UPDATE: u8 = 51;
+    pub const WEBHOOK_DELETE: u8 = 52;
+    pub const EMOJI_CREATE: u8 = 60;
+    pub const EMOJI_UPDATE: u8 = 61;
+    pub const EMOJI_DELETE: u8 = 62;
+    pub const STICKER_CREATE: u8 = 90;
+    pub const STICKER_UPDATE: u8 = 91;
+    pub const STICKER_DELETE: u8 = 92;
+    pub const GUILD_SCHEDULED_EVENT_CREATE: u8 = 100;
+    pub const GUILD_SCHEDULED_EVENT_UPDATE: u8 = 101;
+    pub const GUILD_SCHEDULED_EVENT_DELETE: u8 = 102;
+    pub const AUTO_MODERATION_RULE_CREATE: u8 = 140;
+    pub const AUTO_MODERATION_RULE_UPDATE: u8 = 141;
+    pub const AUTO_MODERATION_RULE_DELETE: u8 = 142;
+}
+
 pub mod interaction_type {
     pub const PING: u8 = 1;
     pub const APPLICATION_COMMAND: u8 = 2;
@@ -1,9 +1,14 @@
 use reqwest::{Client, header};
 use serde_json::json;
+use base64::Engine;
 use tracing::{info, error};
 
 const BASE: &str = "https://discord.com/api/v10";
 
+pub fn image_data_uri(content_type: &str, bytes: &[u8]) -> String {
+    format!("data:{};base64,{}", content_type, base64::engine::general_purpose::STANDARD.encode(bytes))
+}
+
 #[derive(Clone)]
 pub struct RestClient {
     client: Client,
@@ -71,6 +76,66 @@ impl RestClient {
         Ok(resp.json().await?)
     }
 
+    pub async fn modify_guild(&self, guild_id: &str, body: serde_json::Value, reason: &str) -> anyhow::Result<()> {
+        let url = format!("{}/guilds/{}", BASE, guild_id);
+        let resp = self.client.patch(&url).header("X-Audit-Log-Reason", reason).json(&body).send().await?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let text = resp.text().await.unwrap_or_default();
+            error!("modify_guild failed {}: {}", status, text);
+            anyhow::bail!("API error: {} - {}", status, text);
+        }
+
+        Ok(())
+    }
+
+    pub async fn modify_guild_vanity(&self, guild_id: &str, code: Option<&str>, reason: &str) -> anyhow::Result<()> {
+        let url = format!("{}/guilds/{}/vanity-url", BASE, guild_id);
+        let body = json!({ "code": code });
+        let resp = self.client.patch(&url).header("X-Audit-Log-Reason", reason).json(&body).send().await?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let text = resp.text().await.unwrap_or_default();
+            error!("modify_guild_vanity failed {}: {}", status, text);
+            anyhow::bail!("API error: {} - {}", status, text);
+        }
+
+        Ok(())
+    }
+
+    pub async fn download_image(&self, url: &str) -> anyhow::Result<(String, Vec<u8>)> {
+        let resp = reqwest::get(url).await?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            error!("download_image failed {} for {}", status, url);
+            anyhow::bail!("CDN error: {}", status);
+        }
+
+        let content_type = resp
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or("image/png")
+            .to_string();
+        let bytes = resp.bytes().await?;
+        Ok((content_type, bytes.to_vec()))
+    }
+
+    pub async fn download_attachment(&self, url: &str) -> anyhow::Result<Vec<u8>> {
+        let resp = reqwest::get(url).await?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            error!("download_attachment failed {} for {}", status, url);
+            anyhow::bail!("CDN error: {}", status);
+        }
+
+        Ok(resp.bytes().await?.to_vec())
+    }
+
     pub async fn validate_token(&self) -> anyhow::Result<crate::models::User> {
         let resp = self
             .client
@@ -90,6 +155,23 @@ impl RestClient {
         Ok(body)
     }
 
+    pub async fn get_current_application(&self) -> anyhow::Result<serde_json::Value> {
+        let resp = self
+            .client
+            .get(format!("{}/applications/@me", BASE))
+            .send()
+            .await?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let text = resp.text().await.unwrap_or_default();
+            error!("get_current_application failed {}: {}", status, text);
+            anyhow::bail!("API error: {}", status);
+        }
+
+        Ok(resp.json().await?)
+    }
+
     pub async fn send_message(&self, channel_id: &str, content: &str) -> anyhow::Result<()> {
 
         let body = json!({ 
@@ -146,6 +228,72 @@ impl RestClient {
         Ok(())
     }
 
+    pub async fn send_dm_embed(&self, user_id: &str, embed: serde_json::Value) -> anyhow::Result<()> {
+        let resp = self
+            .client
+            .post(format!("{}/users/@me/channels", BASE))
+            .json(&json!({ "recipient_id": user_id }))
+            .send()
+            .await?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let text = resp.text().await.unwrap_or_default();
+            error!("create_dm failed {}: {}", status, text);
+            anyhow::bail!("API error: {} - {}", status, text);
+        }
+
+        let channel: serde_json::Value = resp.json().await?;
+        let channel_id = channel["id"].as_str().unwrap_or("");
+        self.send_embed(channel_id, embed).await
+    }
+
+    pub async fn send_file(
+        &self,
+        channel_id: &str,
+        content: &str,
+        filename: &str,
+        data: &[u8],
+    ) -> anyhow::Result<()> {
+        let boundary = format!("rimuru-{}", chrono::Utc::now().timestamp_nanos_opt().unwrap_or(0));
+        let payload = json!({
+            "content": content,
+            "attachments": [{ "id": 0, "filename": filename }]
+        });
+
+        let mut body = Vec::new();
+        body.extend_from_slice(format!(
+            "--{}\r\nContent-Disposition: form-data; name=\"payload_json\"\r\nContent-Type: application/json\r\n\r\n{}\r\n",
+            boundary, payload
+        ).as_bytes());
+        body.extend_from_slice(format!(
+            "--{}\r\nContent-Disposition: form-data; name=\"files[0]\"; filename=\"{}\"\r\nContent-Type: application/octet-stream\r\n\r\n",
+            boundary, filename.replace('"', "")
+        ).as_bytes());
+        body.extend_from_slice(data);
+        body.extend_from_slice(format!("\r\n--{}--\r\n", boundary).as_bytes());
+
+        let url = format!("{}/channels/{}/messages", BASE, channel_id);
+        info!("POST {} (with file {})", url, filename);
+
+        let resp = self
+            .client
+            .post(&url)
+            .header(header::CONTENT_TYPE, format!("multipart/form-data; boundary={}", boundary))
+            .body(body)
+            .send()
+            .await?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let text = resp.text().await.unwrap_or_default();
+            error!("send_file failed {}: {}", status, text);
+            anyhow::bail!("Discord API error {}: {}", status, text);
+        }
+
+        Ok(())
+    }
+
     pub async fn send_complex_message(
         &self,
         channel_id: &str,
@@ -293,6 +441,35 @@ impl RestClient {
         Ok(body)
     }
 
+    pub async fn get_all_guild_members(&self, guild_id: &str) -> anyhow::Result<Vec<serde_json::Value>> {
+        let mut members = Vec::new();
+        let mut after = "0".to_string();
+        loop {
+            let resp = self
+                .client
+                .get(format!("{}/guilds/{}/members?limit=1000&after={}", BASE, guild_id, after))
+                .send()
+                .await?;
+
+            if !resp.status().is_success() {
+                let status = resp.status();
+                let text = resp.text().await.unwrap_or_default();
+                error!("get_all_guild_members failed {}: {}", status, text);
+                anyhow::bail!("API error: {}", status);
+            }
+
+            let page: Vec<serde_json::Value> = resp.json().await?;
+            let full = page.len() == 1000;
+            match page.last().and_then(|m| m["user"]["id"].as_str()) {
+                Some(last) => after = last.to_string(),
+                None => break,
+            }
+            members.extend(page);
+            if !full { break; }
+        }
+        Ok(members)
+    }
+
     pub async fn get_guild_member(&self, guild_id: &str, user_id: &str) -> anyhow::Result<serde_json::Value> {
         let resp = self
             .client
@@ -454,6 +631,34 @@ impl RestClient {
         Ok(roles)
     }
 
+    pub async fn modify_role(&self, guild_id: &str, role_id: &str, body: serde_json::Value, reason: &str) -> anyhow::Result<()> {
+        let url = format!("{}/guilds/{}/roles/{}", BASE, guild_id, role_id);
+        let resp = self.client.patch(&url).header("X-Audit-Log-Reason", reason).json(&body).send().await?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let text = resp.text().await.unwrap_or_default();
+            error!("modify_role failed {}: {}", status, text);
+            anyhow::bail!("API error: {} - {}", status, text);
+        }
+
+        Ok(())
+    }
+
+    pub async fn create_guild_channel(&self, guild_id: &str, body: serde_json::Value, reason: &str) -> anyhow::Result<serde_json::Value> {
+        let url = format!("{}/guilds/{}/channels", BASE, guild_id);
+        let resp = self.client.post(&url).header("X-Audit-Log-Reason", reason).json(&body).send().await?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let text = resp.text().await.unwrap_or_default();
+            error!("create_guild_channel failed {}: {}", status, text);
+            anyhow::bail!("API error: {} - {}", status, text);
+        }
+
+        Ok(resp.json().await?)
+    }
+
     pub async fn modify_role_positions(&self, guild_id: &str, role_id: &str, position: u64) -> anyhow::Result<()> {
         let url = format!("{}/guilds/{}/roles", BASE, guild_id);
         let body = json!([
@@ -471,6 +676,38 @@ impl RestClient {
         Ok(())
     }
 
+    pub async fn modify_role_order(&self, guild_id: &str, positions: &[(String, u64)], reason: &str) -> anyhow::Result<()> {
+        let url = format!("{}/guilds/{}/roles", BASE, guild_id);
+        let body: Vec<serde_json::Value> = positions.iter()
+            .map(|(id, position)| json!({ "id": id, "position": position }))
+            .collect();
+        let resp = self.client.patch(&url).header("X-Audit-Log-Reason", reason).json(&body).send().await?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let text = resp.text().await.unwrap_or_default();
+            error!("modify_role_order failed {}: {}", status, text);
+            anyhow::bail!("API error: {}", status);
+        }
+
+        Ok(())
+    }
+
+    pub async fn modify_role_permissions(&self, guild_id: &str, role_id: &str, permissions: u64, reason: &str) -> anyhow::Result<()> {
+        let url = format!("{}/guilds/{}/roles/{}", BASE, guild_id, role_id);
+        let body = json!({ "permissions": permissions.to_string() });
+        let resp = self.client.patch(&url).header("X-Audit-Log-Reason", reason).json(&body).send().await?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let text = resp.text().await.unwrap_or_default();
+            error!("modify_role_permissions failed {}: {}", status, text);
+            anyhow::bail!("API error: {} - {}", status, text);
+        }
+
+        Ok(())
+    }
+
     pub async fn add_member_role(&self, guild_id: &str, user_id: &str, role_id: &str) -> anyhow::Result<()> {
         let url = format!("{}/guilds/{}/members/{}/roles/{}", BASE, guild_id, user_id, role_id);
         let resp = self.client.put(&url).header("X-Audit-Log-Reason", "Rimuru Antinuke Auto-Role Setup").send().await?;
@@ -485,6 +722,20 @@ impl RestClient {
         Ok(())
     }
 
+    pub async fn remove_member_role(&self, guild_id: &str, user_id: &str, role_id: &str, reason: &str) -> anyhow::Result<()> {
+        let url = format!("{}/guilds/{}/members/{}/roles/{}", BASE, guild_id, user_id, role_id);
+        let resp = self.client.delete(&url).header("X-Audit-Log-Reason", reason).send().await?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let text = resp.text().await.unwrap_or_default();
+            error!("remove_member_role failed {}: {}", status, text);
+            anyhow::bail!("API error: {}", status);
+        }
+
+        Ok(())
+    }
+
     pub async fn timeout_member(&self, guild_id: &str, user_id: &str, until: Option<&str>, reason: &str) -> anyhow::Result<()> {
         let url = format!("{}/guilds/{}/members/{}", BASE, guild_id, user_id);
         let body = json!({ "communication_disabled_until": until });
@@ -515,6 +766,34 @@ impl RestClient {
         Ok(())
     }
 
+    pub async fn delete_channel_permission(&self, channel_id: &str, overwrite_id: &str, reason: &str) -> anyhow::Result<()> {
+        let url = format!("{}/channels/{}/permissions/{}", BASE, channel_id, overwrite_id);
+        let resp = self.client.delete(&url).header("X-Audit-Log-Reason", reason).send().await?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let text = resp.text().await.unwrap_or_default();
+            error!("delete_channel_permission failed {}: {}", status, text);
+            anyhow::bail!("API error: {} - {}", status, text);
+        }
+
+        Ok(())
+    }
+
+    pub async fn delete_message(&self, channel_id: &str, message_id: &str, reason: &str) -> anyhow::Result<()> {
+        let url = format!("{}/channels/{}/messages/{}", BASE, channel_id, message_id);
+        let resp = self.client.delete(&url).header("X-Audit-Log-Reason", reason).send().await?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let text = resp.text().await.unwrap_or_default();
+            error!("delete_message failed {}: {}", status, text);
+            anyhow::bail!("API error: {}", status);
+        }
+
+        Ok(())
+    }
+
     pub async fn bulk_delete_messages(&self, channel_id: &str, messages: Vec<String>) -> anyhow::Result<()> {
         let url = format!("{}/channels/{}/messages/bulk-delete", BASE, channel_id);
         let body = json!({ "messages": messages });
@@ -545,10 +824,9 @@ impl RestClient {
         Ok(messages)
     }
 
-    pub async fn modify_channel(&self, channel_id: &str, rate_limit_per_user: u16) -> anyhow::Result<()> {
+    pub async fn modify_channel(&self, channel_id: &str, body: serde_json::Value, reason: &str) -> anyhow::Result<()> {
         let url = format!("{}/channels/{}", BASE, channel_id);
-        let body = json!({ "rate_limit_per_user": rate_limit_per_user });
-        let resp = self.client.patch(&url).json(&body).send().await?;
+        let resp = self.client.patch(&url).header("X-Audit-Log-Reason", reason).json(&body).send().await?;
 
         if !resp.status().is_success() {
             let status = resp.status();
@@ -603,4 +881,204 @@ impl RestClient {
 
         Ok(())
     }
+
+    pub async fn modify_member_roles(&self, guild_id: &str, user_id: &str, roles: &[String], reason: &str) -> anyhow::Result<()> {
+        let url = format!("{}/guilds/{}/members/{}", BASE, guild_id, user_id);
+        let body = json!({ "roles": roles });
+        let resp = self.client.patch(&url).header("X-Audit-Log-Reason", reason).json(&body).send().await?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let text = resp.text().await.unwrap_or_default();
+            error!("modify_member_roles failed {}: {}", status, text);
+            anyhow::bail!("API error: {} - {}", status, text);
+        }
+
+        Ok(())
+    }
+
+    pub async fn create_guild_emoji(&self, guild_id: &str, name: &str, image: &str, reason: &str) -> anyhow::Result<serde_json::Value> {
+        let url = format!("{}/guilds/{}/emojis", BASE, guild_id);
+        let body = json!({ "name": name, "image": image });
+        let resp = self.client.post(&url).header("X-Audit-Log-Reason", reason).json(&body).send().await?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let text = resp.text().await.unwrap_or_default();
+            error!("create_guild_emoji failed {}: {}", status, text);
+            anyhow::bail!("API error: {} - {}", status, text);
+        }
+
+        Ok(resp.json().await?)
+    }
+
+    pub async fn modify_guild_emoji(&self, guild_id: &str, emoji_id: &str, name: &str, reason: &str) -> anyhow::Result<()> {
+        let url = format!("{}/guilds/{}/emojis/{}", BASE, guild_id, emoji_id);
+        let body = json!({ "name": name });
+        let resp = self.client.patch(&url).header("X-Audit-Log-Reason", reason).json(&body).send().await?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let text = resp.text().await.unwrap_or_default();
+            error!("modify_guild_emoji failed {}: {}", status, text);
+            anyhow::bail!("API error: {}", status);
+        }
+
+        Ok(())
+    }
+
+    pub async fn delete_guild_emoji(&self, guild_id: &str, emoji_id: &str, reason: &str) -> anyhow::Result<()> {
+        let url = format!("{}/guilds/{}/emojis/{}", BASE, guild_id, emoji_id);
+        let resp = self.client.delete(&url).header("X-Audit-Log-Reason", reason).send().await?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let text = resp.text().await.unwrap_or_default();
+            error!("delete_guild_emoji failed {}: {}", status, text);
+            anyhow::bail!("API error: {}", status);
+        }
+
+        Ok(())
+    }
+
+    pub async fn modify_guild_sticker(&self, guild_id: &str, sticker_id: &str, name: &str, reason: &str) -> anyhow::Result<()> {
+        let url = format!("{}/guilds/{}/stickers/{}", BASE, guild_id, sticker_id);
+        let body = json!({ "name": name });
+        let resp = self.client.patch(&url).header("X-Audit-Log-Reason", reason).json(&body).send().await?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let text = resp.text().await.unwrap_or_default();
+            error!("modify_guild_sticker failed {}: {}", status, text);
+            anyhow::bail!("API error: {}", status);
+        }
+
+        Ok(())
+    }
+
+    pub async fn delete_guild_sticker(&self, guild_id: &str, sticker_id: &str, reason: &str) -> anyhow::Result<()> {
+        let url = format!("{}/guilds/{}/stickers/{}", BASE, guild_id, sticker_id);
+        let resp = self.client.delete(&url).header("X-Audit-Log-Reason", reason).send().await?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let text = resp.text().await.unwrap_or_default();
+            error!("delete_guild_sticker failed {}: {}", status, text);
+            anyhow::bail!("API error: {}", status);
+        }
+
+        Ok(())
+    }
+
+    pub async fn get_guild_webhooks(&self, guild_id: &str) -> anyhow::Result<Vec<serde_json::Value>> {
+        let url = format!("{}/guilds/{}/webhooks", BASE, guild_id);
+        let resp = self.client.get(&url).send().await?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let text = resp.text().await.unwrap_or_default();
+            error!("get_guild_webhooks failed {}: {}", status, text);
+            anyhow::bail!("API error: {}", status);
+        }
+
+        Ok(resp.json().await?)
+    }
+
+    pub async fn get_channel_webhooks(&self, channel_id: &str) -> anyhow::Result<Vec<serde_json::Value>> {
+        let url = format!("{}/channels/{}/webhooks", BASE, channel_id);
+        let resp = self.client.get(&url).send().await?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let text = resp.text().await.unwrap_or_default();
+            error!("get_channel_webhooks failed {}: {}", status, text);
+            anyhow::bail!("API error: {}", status);
+        }
+
+        Ok(resp.json().await?)
+    }
+
+    pub async fn delete_webhook(&self, webhook_id: &str, reason: &str) -> anyhow::Result<()> {
+        let url = format!("{}/webhooks/{}", BASE, webhook_id);
+        let resp = self.client.delete(&url).header("X-Audit-Log-Reason", reason).send().await?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let text = resp.text().await.unwrap_or_default();
+            error!("delete_webhook failed {}: {}", status, text);
+            anyhow::bail!("API error: {}", status);
+        }
+
+        Ok(())
+    }
+
+    pub async fn get_automod_rules(&self, guild_id: &str) -> anyhow::Result<Vec<serde_json::Value>> {
+        let url = format!("{}/guilds/{}/auto-moderation/rules", BASE, guild_id);
+        let resp = self.client.get(&url).send().await?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let text = resp.text().await.unwrap_or_default();
+            error!("get_automod_rules failed {}: {}", status, text);
+            anyhow::bail!("API error: {}", status);
+        }
+
+        Ok(resp.json().await?)
+    }
+
+    pub async fn create_automod_rule(&self, guild_id: &str, body: serde_json::Value, reason: &str) -> anyhow::Result<serde_json::Value> {
+        let url = format!("{}/guilds/{}/auto-moderation/rules", BASE, guild_id);
+        let resp = self.client.post(&url).header("X-Audit-Log-Reason", reason).json(&body).send().await?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let text = resp.text().await.unwrap_or_default();
+            error!("create_automod_rule failed {}: {}", status, text);
+            anyhow::bail!("API error: {} - {}", status, text);
+        }
+
+        Ok(resp.json().await?)
+    }
+
+    pub async fn modify_automod_rule(&self, guild_id: &str, rule_id: &str, body: serde_json::Value, reason: &str) -> anyhow::Result<()> {
+        let url = format!("{}/guilds/{}/auto-moderation/rules/{}", BASE, guild_id, rule_id);
+        let resp = self.client.patch(&url).header("X-Audit-Log-Reason", reason).json(&body).send().await?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let text = resp.text().await.unwrap_or_default();
+            error!("modify_automod_rule failed {}: {}", status, text);
+            anyhow::bail!("API error: {} - {}", status, text);
+        }
+
+        Ok(())
+    }
+
+    pub async fn delete_automod_rule(&self, guild_id: &str, rule_id: &str, reason: &str) -> anyhow::Result<()> {
+        let url = format!("{}/guilds/{}/auto-moderation/rules/{}", BASE, guild_id, rule_id);
+        let resp = self.client.delete(&url).header("X-Audit-Log-Reason", reason).send().await?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let text = resp.text().await.unwrap_or_default();
+            error!("delete_automod_rule failed {}: {}", status, text);
+            anyhow::bail!("API error: {}", status);
+        }
+
+        Ok(())
+    }
+
+    pub async fn delete_scheduled_event(&self, guild_id: &str, event_id: &str, reason: &str) -> anyhow::Result<()> {
+        let url = format!("{}/guilds/{}/scheduled-events/{}", BASE, guild_id, event_id);
+        let resp = self.client.delete(&url).header("X-Audit-Log-Reason", reason).send().await?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let text = resp.text().await.unwrap_or_default();
+            error!("delete_scheduled_event failed {}: {}", status, text);
+            anyhow::bail!("API error: {}", status);
+        }
+
+        Ok(())
+    }
 }